@@ -0,0 +1,11 @@
+-- Audit trail for transaction edits: one row per update with full before/after snapshots
+CREATE TABLE transaction_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    transaction_id UUID REFERENCES transactions(id) ON DELETE CASCADE NOT NULL,
+    version INT NOT NULL,
+    changed_by UUID REFERENCES users(id) NOT NULL,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    old_values JSONB NOT NULL,
+    new_values JSONB NOT NULL,
+    UNIQUE (transaction_id, version)
+);
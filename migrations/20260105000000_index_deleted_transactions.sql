@@ -0,0 +1,3 @@
+-- Speeds up the trash view and the retention purge, which only look at soft-deleted rows
+CREATE INDEX idx_transactions_deleted_at ON transactions (user_id, deleted_at)
+WHERE deleted_at IS NOT NULL;
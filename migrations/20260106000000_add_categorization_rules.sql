@@ -0,0 +1,29 @@
+-- Free-form labels on transactions
+ALTER TABLE transactions ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}';
+
+-- User-defined auto-categorization rules.
+-- All non-null conditions must match; rules are evaluated in ascending priority.
+CREATE TABLE categorization_rules (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    priority INT NOT NULL DEFAULT 0,
+    is_active BOOLEAN NOT NULL DEFAULT TRUE,
+
+    -- Conditions
+    description_contains TEXT,
+    description_regex TEXT,
+    min_amount DECIMAL(19, 4),
+    max_amount DECIMAL(19, 4),
+    pocket_id UUID REFERENCES pockets(id) ON DELETE CASCADE,
+    currency VARCHAR(3) REFERENCES currencies(code),
+
+    -- Actions
+    set_category_id INT REFERENCES categories(id),
+    set_pocket_id UUID REFERENCES pockets(id) ON DELETE SET NULL,
+    add_tags TEXT[] NOT NULL DEFAULT '{}',
+
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_categorization_rules_user_id ON categorization_rules (user_id, priority);
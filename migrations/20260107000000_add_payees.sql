@@ -0,0 +1,21 @@
+-- Payees (merchants) with normalized aliases used to resolve free-text descriptions
+CREATE TABLE payees (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    default_category_id INT REFERENCES categories(id),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (user_id, name)
+);
+
+-- Aliases are stored normalized (see payees::normalize_name); one alias maps to one payee per user
+CREATE TABLE payee_aliases (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    payee_id UUID REFERENCES payees(id) ON DELETE CASCADE NOT NULL,
+    alias VARCHAR(100) NOT NULL,
+    UNIQUE (user_id, alias)
+);
+
+ALTER TABLE transactions ADD COLUMN payee_id UUID REFERENCES payees(id) ON DELETE SET NULL;
+CREATE INDEX idx_transactions_payee_id ON transactions (payee_id);
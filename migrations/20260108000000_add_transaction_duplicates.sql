@@ -0,0 +1,16 @@
+-- Likely duplicate pairs flagged on create; transaction_id is the newer entry
+CREATE TABLE transaction_duplicates (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    transaction_id UUID REFERENCES transactions(id) ON DELETE CASCADE NOT NULL,
+    duplicate_of_id UUID REFERENCES transactions(id) ON DELETE CASCADE NOT NULL,
+    score INT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'dismissed', 'merged')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    resolved_at TIMESTAMPTZ,
+    UNIQUE (transaction_id, duplicate_of_id)
+);
+
+CREATE INDEX idx_transaction_duplicates_pending
+    ON transaction_duplicates (user_id) WHERE status = 'pending';
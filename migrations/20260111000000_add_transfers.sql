@@ -0,0 +1,22 @@
+-- A transfer owns its two legs: a Transfer Out row in the source pocket and a
+-- Transfer In row in the destination pocket. Legs are soft-deleted together;
+-- the transfer row is removed when its legs are purged from the trash.
+-- Transfers recorded before this migration remain as unlinked transactions.
+CREATE TABLE transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    source_pocket_id UUID REFERENCES pockets(id) NOT NULL,
+    destination_pocket_id UUID REFERENCES pockets(id) NOT NULL,
+    amount DECIMAL(19, 4) NOT NULL,
+    description TEXT,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    -- Set right after the legs are inserted, in the same SQL transaction
+    out_transaction_id UUID,
+    in_transaction_id UUID,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_transfers_user_id ON transfers (user_id, occurred_at DESC);
+
+ALTER TABLE transactions ADD COLUMN transfer_id UUID REFERENCES transfers(id);
+CREATE INDEX idx_transactions_transfer_id ON transactions (transfer_id);
@@ -0,0 +1,21 @@
+-- Each pocket holds money in one currency. Existing pockets take the owner's base currency.
+ALTER TABLE pockets ADD COLUMN currency VARCHAR(3) REFERENCES currencies(code);
+
+UPDATE pockets p
+SET currency = COALESCE(
+    (SELECT us.base_currency FROM user_settings us WHERE us.user_id = p.user_id),
+    'SGD'
+);
+
+ALTER TABLE pockets ALTER COLUMN currency SET NOT NULL;
+
+-- Cross-currency transfers: `amount` leaves the source pocket (in its currency, fee included)
+-- and `destination_amount` arrives in the destination pocket's currency
+ALTER TABLE transfers
+ADD COLUMN destination_amount DECIMAL(19, 4),
+ADD COLUMN exchange_rate DECIMAL(19, 8),
+ADD COLUMN fee DECIMAL(19, 4) NOT NULL DEFAULT 0;
+
+UPDATE transfers SET destination_amount = amount;
+
+ALTER TABLE transfers ALTER COLUMN destination_amount SET NOT NULL;
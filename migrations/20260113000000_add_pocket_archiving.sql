@@ -0,0 +1,2 @@
+-- Archived pockets are hidden from the pocket list but keep their history
+ALTER TABLE pockets ADD COLUMN archived_at TIMESTAMPTZ;
@@ -0,0 +1,13 @@
+-- Savings goals; each pocket can carry one goal, measured against the pocket's balance
+CREATE TABLE goals (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    pocket_id UUID REFERENCES pockets(id) ON DELETE CASCADE NOT NULL UNIQUE,
+    name VARCHAR(100) NOT NULL,
+    target_amount DECIMAL(19, 4) NOT NULL CHECK (target_amount > 0),
+    target_date DATE,
+    monthly_contribution DECIMAL(19, 4) CHECK (monthly_contribution > 0),
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_goals_user_id ON goals (user_id);
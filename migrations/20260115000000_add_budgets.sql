@@ -0,0 +1,13 @@
+-- Monthly spending budgets per expense category, in the user's base currency.
+-- With rollover, each month's unspent (or overspent) amount carries into the next.
+CREATE TABLE budgets (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    category_id INT REFERENCES categories(id) NOT NULL,
+    amount DECIMAL(19, 4) NOT NULL CHECK (amount > 0),
+    rollover BOOLEAN NOT NULL DEFAULT FALSE,
+    -- First month the budget applies to (always the 1st of a month)
+    starts_on DATE NOT NULL DEFAULT date_trunc('month', NOW())::date,
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    UNIQUE (user_id, category_id)
+);
@@ -0,0 +1,20 @@
+-- Zero-based (envelope) budgeting. While enabled, income from `zero_based_since` onwards
+-- feeds a "to be assigned" pool that the user allocates to envelopes each month.
+ALTER TABLE user_settings ADD COLUMN zero_based_since DATE;
+
+-- Money assigned to an envelope (an expense category or a pocket) for one month, in base currency
+CREATE TABLE envelope_allocations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    month DATE NOT NULL,
+    category_id INT REFERENCES categories(id),
+    pocket_id UUID REFERENCES pockets(id) ON DELETE CASCADE,
+    amount DECIMAL(19, 4) NOT NULL CHECK (amount >= 0),
+    created_at TIMESTAMPTZ DEFAULT NOW(),
+    CHECK ((category_id IS NULL) <> (pocket_id IS NULL))
+);
+
+CREATE UNIQUE INDEX idx_envelope_allocations_category
+    ON envelope_allocations (user_id, month, category_id) WHERE category_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_envelope_allocations_pocket
+    ON envelope_allocations (user_id, month, pocket_id) WHERE pocket_id IS NOT NULL;
@@ -0,0 +1,47 @@
+-- Recurring bills paid to a payee on a day of each month
+CREATE TABLE bills (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    payee_id UUID REFERENCES payees(id) ON DELETE CASCADE NOT NULL,
+    -- In the currency the bill is paid in
+    expected_amount DECIMAL(19, 4) NOT NULL CHECK (expected_amount > 0),
+    -- Day of the month; months without that day fall due on their last day
+    due_day SMALLINT NOT NULL CHECK (due_day BETWEEN 1 AND 31),
+    -- When set, only payments from this pocket count
+    pocket_id UUID REFERENCES pockets(id) ON DELETE SET NULL,
+    -- No occurrence falls due before this date
+    starts_on DATE NOT NULL DEFAULT CURRENT_DATE,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_bills_user_id ON bills (user_id);
+
+-- The transaction that paid one occurrence of a bill
+CREATE TABLE bill_payments (
+    bill_id UUID REFERENCES bills(id) ON DELETE CASCADE NOT NULL,
+    due_date DATE NOT NULL,
+    transaction_id UUID REFERENCES transactions(id) ON DELETE CASCADE NOT NULL UNIQUE,
+    PRIMARY KEY (bill_id, due_date)
+);
+
+-- Overdue reminders already sent, so each occurrence is only reported once
+CREATE TABLE bill_reminders (
+    bill_id UUID REFERENCES bills(id) ON DELETE CASCADE NOT NULL,
+    due_date DATE NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (bill_id, due_date)
+);
+
+-- In-app notifications
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    kind VARCHAR(50) NOT NULL,
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_notifications_user_created ON notifications (user_id, created_at DESC);
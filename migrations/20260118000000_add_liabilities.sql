@@ -0,0 +1,26 @@
+-- Loans and other debts repaid on a fixed amortization schedule
+CREATE TABLE liabilities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    principal DECIMAL(19, 4) NOT NULL CHECK (principal > 0),
+    -- Nominal yearly interest rate in percent, e.g. 4.5
+    annual_rate DECIMAL(7, 4) NOT NULL CHECK (annual_rate >= 0 AND annual_rate <= 100),
+    term_months INTEGER NOT NULL CHECK (term_months > 0),
+    frequency VARCHAR(10) NOT NULL CHECK (frequency IN ('weekly', 'biweekly', 'monthly')),
+    -- Date of the first scheduled repayment
+    first_payment_on DATE NOT NULL,
+    currency VARCHAR(3) REFERENCES currencies(code) NOT NULL,
+    created_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE INDEX idx_liabilities_user_id ON liabilities (user_id);
+
+-- Transactions recorded as repayments of a liability. The principal/interest split is
+-- derived from the order of repayments, so it is not stored.
+CREATE TABLE liability_payments (
+    transaction_id UUID PRIMARY KEY REFERENCES transactions(id) ON DELETE CASCADE,
+    liability_id UUID REFERENCES liabilities(id) ON DELETE CASCADE NOT NULL
+);
+
+CREATE INDEX idx_liability_payments_liability_id ON liability_payments (liability_id);
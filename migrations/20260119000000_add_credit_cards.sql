@@ -0,0 +1,16 @@
+-- Pockets are cash by default; credit card pockets run a balance owed
+ALTER TABLE pockets
+ADD COLUMN kind VARCHAR(20) NOT NULL DEFAULT 'cash' CHECK (kind IN ('cash', 'credit_card'));
+
+-- Statement cycle of a credit card pocket
+CREATE TABLE credit_cards (
+    pocket_id UUID PRIMARY KEY REFERENCES pockets(id) ON DELETE CASCADE,
+    -- Day of the month the statement closes; short months close on their last day
+    statement_day SMALLINT NOT NULL CHECK (statement_day BETWEEN 1 AND 31),
+    -- Days from the statement closing to the payment due date
+    due_days SMALLINT NOT NULL CHECK (due_days BETWEEN 1 AND 60),
+    credit_limit DECIMAL(19, 4) CHECK (credit_limit > 0),
+    -- Minimum payment: this percentage of the statement balance, but at least the floor
+    minimum_payment_percent DECIMAL(5, 2) NOT NULL CHECK (minimum_payment_percent BETWEEN 0 AND 100),
+    minimum_payment_floor DECIMAL(19, 4) NOT NULL CHECK (minimum_payment_floor >= 0)
+);
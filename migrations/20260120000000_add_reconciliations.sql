@@ -0,0 +1,22 @@
+-- Cleared: matched against a bank statement. Reconciled: part of a completed reconciliation,
+-- and locked from edits until unlocked.
+ALTER TABLE transactions
+ADD COLUMN cleared_at TIMESTAMPTZ,
+ADD COLUMN reconciled_at TIMESTAMPTZ;
+
+CREATE INDEX idx_transactions_pocket_cleared ON transactions (pocket_id) WHERE cleared_at IS NOT NULL;
+
+-- A pocket's bank statement being reconciled, or reconciled already
+CREATE TABLE reconciliations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID REFERENCES users(id) NOT NULL,
+    pocket_id UUID REFERENCES pockets(id) ON DELETE CASCADE NOT NULL,
+    statement_date DATE NOT NULL,
+    -- Balance printed on the statement, signed like the pocket balance
+    ending_balance DECIMAL(19, 4) NOT NULL,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- At most one reconciliation in progress per pocket
+CREATE UNIQUE INDEX idx_reconciliations_open ON reconciliations (pocket_id) WHERE completed_at IS NULL;
@@ -0,0 +1,6 @@
+-- Card authorizations are recorded as pending until they post, possibly for a different
+-- amount. Void transactions stay visible but no longer count anywhere.
+ALTER TABLE transactions
+ADD COLUMN status VARCHAR(10) NOT NULL DEFAULT 'posted' CHECK (status IN ('pending', 'posted', 'void'));
+
+CREATE INDEX idx_transactions_pending ON transactions (user_id) WHERE status = 'pending';
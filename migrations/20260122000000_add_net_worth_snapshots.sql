@@ -0,0 +1,16 @@
+-- Net worth at the end of each day, in the user's base currency at the time. `items` holds
+-- the pockets, assets and liabilities behind the totals with the exchange rates used.
+CREATE TABLE net_worth_snapshots (
+    user_id UUID REFERENCES users(id) NOT NULL,
+    snapshot_date DATE NOT NULL,
+    base_currency VARCHAR(3) REFERENCES currencies(code) NOT NULL,
+    cash_balance DECIMAL(19, 4) NOT NULL,
+    investment_balance DECIMAL(19, 4) NOT NULL,
+    liabilities_balance DECIMAL(19, 4) NOT NULL,
+    total_net_worth DECIMAL(19, 4) NOT NULL,
+    items JSONB NOT NULL,
+    -- Reconstructed from transaction history instead of recorded on the day
+    is_backfill BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, snapshot_date)
+);
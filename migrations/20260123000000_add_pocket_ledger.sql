@@ -0,0 +1,29 @@
+-- How each live transaction moves its pocket's balance: in the pocket's currency (the original
+-- amount when it was entered in that currency, the base amount otherwise), positive for income
+-- and negative for spending. Pending spending already reduces the available balance; pending
+-- income only counts once posted.
+CREATE VIEW pocket_ledger AS
+SELECT
+    t.id AS transaction_id,
+    t.user_id,
+    t.pocket_id,
+    t.occurred_at,
+    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+        * CASE WHEN c.is_income THEN 1 ELSE -1 END AS amount,
+    (t.status = 'posted' OR NOT c.is_income) AS available,
+    (t.status = 'posted') AS posted
+FROM transactions t
+JOIN categories c ON t.category_id = c.id
+JOIN pockets p ON t.pocket_id = p.id
+WHERE t.deleted_at IS NULL AND t.status <> 'void';
+
+-- Available and posted balance of one pocket, in its currency
+CREATE FUNCTION pocket_balances(target_pocket_id UUID)
+RETURNS TABLE (balance NUMERIC, posted_balance NUMERIC)
+LANGUAGE sql STABLE AS $$
+    SELECT
+        COALESCE(SUM(amount) FILTER (WHERE available), 0),
+        COALESCE(SUM(amount) FILTER (WHERE posted), 0)
+    FROM pocket_ledger
+    WHERE pocket_id = target_pocket_id
+$$;
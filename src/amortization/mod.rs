@@ -0,0 +1,175 @@
+mod tests;
+
+use chrono::{Duration, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::schemas::{AmortizationEntry, LiabilityPaymentRow, LiabilityRow, PaymentFrequency};
+
+/// Longest term accepted for a liability (50 years)
+pub const MAX_TERM_MONTHS: i32 = 600;
+
+/// A repayment split into the interest accrued since the previous one and the principal it
+/// pays down
+#[derive(Debug, PartialEq)]
+pub struct Split {
+    pub principal: Decimal,
+    pub interest: Decimal,
+    /// Outstanding balance after the repayment
+    pub balance: Decimal,
+}
+
+pub fn periods_per_year(frequency: PaymentFrequency) -> u32 {
+    match frequency {
+        PaymentFrequency::Weekly => 52,
+        PaymentFrequency::Biweekly => 26,
+        PaymentFrequency::Monthly => 12,
+    }
+}
+
+/// Interest charged per payment period, as a fraction of the outstanding balance
+pub fn periodic_rate(annual_rate: Decimal, frequency: PaymentFrequency) -> Decimal {
+    annual_rate / Decimal::ONE_HUNDRED / Decimal::from(periods_per_year(frequency))
+}
+
+/// Number of repayments over the term; weekly and biweekly terms round to the nearest payment
+pub fn payment_count(term_months: i32, frequency: PaymentFrequency) -> u32 {
+    let months = term_months.max(1) as u32;
+    match frequency {
+        PaymentFrequency::Monthly => months,
+        _ => ((months * periods_per_year(frequency) + 6) / 12).max(1),
+    }
+}
+
+/// Level payment that clears `principal` in `count` payments at `rate` per period. Uses the
+/// discounted form `principal * rate / (1 - (1 + rate)^-count)`, whose discount factor shrinks
+/// towards zero instead of growing past `Decimal`'s range on long, high-rate terms.
+pub fn level_payment(principal: Decimal, rate: Decimal, count: u32) -> Decimal {
+    if rate.is_zero() {
+        return (principal / Decimal::from(count)).round_dp(2);
+    }
+    let discount = (0..count).fold(Decimal::ONE, |acc, _| acc / (Decimal::ONE + rate));
+    (principal * rate / (Decimal::ONE - discount)).round_dp(2)
+}
+
+/// Due date of the repayment at `index` (0 for the first); monthly dates keep the day of the
+/// month where it exists and use the month's last day otherwise
+pub fn payment_date(first: NaiveDate, frequency: PaymentFrequency, index: u32) -> NaiveDate {
+    match frequency {
+        PaymentFrequency::Weekly => first + Duration::weeks(index as i64),
+        PaymentFrequency::Biweekly => first + Duration::weeks(2 * index as i64),
+        PaymentFrequency::Monthly => first + Months::new(index),
+    }
+}
+
+/// Scheduled payment of a liability under its original terms
+pub fn scheduled_payment(liability: &LiabilityRow) -> Decimal {
+    let frequency = PaymentFrequency::from_db(&liability.frequency);
+    level_payment(
+        liability.principal,
+        periodic_rate(liability.annual_rate, frequency),
+        payment_count(liability.term_months, frequency),
+    )
+}
+
+/// Full amortization schedule. Interest is rounded to cents each period and the final payment
+/// absorbs the rounding so the balance ends at exactly zero.
+pub fn schedule(liability: &LiabilityRow) -> Vec<AmortizationEntry> {
+    let frequency = PaymentFrequency::from_db(&liability.frequency);
+    let rate = periodic_rate(liability.annual_rate, frequency);
+    let count = payment_count(liability.term_months, frequency);
+    let payment = level_payment(liability.principal, rate, count);
+
+    let mut balance = liability.principal;
+    let mut entries = Vec::with_capacity(count as usize);
+    for index in 0..count {
+        let interest = (balance * rate).round_dp(2);
+        let principal = if index + 1 == count {
+            balance
+        } else {
+            (payment - interest).clamp(Decimal::ZERO, balance)
+        };
+        balance -= principal;
+        entries.push(AmortizationEntry {
+            number: index + 1,
+            due_date: payment_date(liability.first_payment_on, frequency, index),
+            payment: principal + interest,
+            principal,
+            interest,
+            balance,
+        });
+        if balance.is_zero() {
+            break;
+        }
+    }
+    entries
+}
+
+/// Start of the period whose interest the first repayment pays
+fn accrual_start(first: NaiveDate, frequency: PaymentFrequency) -> NaiveDate {
+    match frequency {
+        PaymentFrequency::Weekly => first - Duration::weeks(1),
+        PaymentFrequency::Biweekly => first - Duration::weeks(2),
+        PaymentFrequency::Monthly => first - Months::new(1),
+    }
+}
+
+/// Periods elapsed between the start of accrual and `day`: 1 on the first due date, 2 on the
+/// second, and the fraction of a period's days in between
+fn schedule_position(first: NaiveDate, frequency: PaymentFrequency, day: NaiveDate) -> Decimal {
+    let boundary = |index: u32| match index {
+        0 => accrual_start(first, frequency),
+        _ => payment_date(first, frequency, index - 1),
+    };
+    if day <= boundary(0) {
+        return Decimal::ZERO;
+    }
+    let mut index = 0;
+    while boundary(index + 1) <= day {
+        index += 1;
+    }
+    let (start, end) = (boundary(index), boundary(index + 1));
+    Decimal::from(index)
+        + Decimal::from((day - start).num_days()) / Decimal::from((end - start).num_days())
+}
+
+/// Split recorded repayments, oldest first. Each one pays the interest accrued on the
+/// outstanding balance since the previous repayment (or since the period before the first due
+/// date) before any principal, so a repayment made on schedule pays one period's interest, a
+/// late one more and an extra one in the same period less. A repayment smaller than the
+/// interest is all interest, and principal is never paid down below zero.
+pub fn split_payments(liability: &LiabilityRow, payments: &[LiabilityPaymentRow]) -> Vec<Split> {
+    let frequency = PaymentFrequency::from_db(&liability.frequency);
+    let rate = periodic_rate(liability.annual_rate, frequency);
+
+    let mut balance = liability.principal;
+    let mut accrued_to = Decimal::ZERO;
+    payments
+        .iter()
+        .map(|payment| {
+            let position = schedule_position(
+                liability.first_payment_on,
+                frequency,
+                payment.occurred_at.date_naive(),
+            )
+            .max(accrued_to);
+            let periods = position - accrued_to;
+            accrued_to = position;
+
+            let interest = (balance * rate * periods).round_dp(2).min(payment.amount);
+            let principal = (payment.amount - interest).min(balance);
+            balance -= principal;
+            Split {
+                principal,
+                interest,
+                balance,
+            }
+        })
+        .collect()
+}
+
+/// What is still owed after the recorded repayments
+pub fn outstanding(liability: &LiabilityRow, payments: &[LiabilityPaymentRow]) -> Decimal {
+    split_payments(liability, payments)
+        .last()
+        .map_or(liability.principal, |split| split.balance)
+}
@@ -0,0 +1,205 @@
+#![cfg(test)]
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{LiabilityPaymentRow, LiabilityRow, PaymentFrequency};
+
+use super::{
+    MAX_TERM_MONTHS, outstanding, payment_count, schedule, scheduled_payment, split_payments,
+};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_loan(principal: Decimal, annual_rate: Decimal, term_months: i32) -> LiabilityRow {
+    LiabilityRow {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        name: "Car loan".to_string(),
+        principal,
+        annual_rate,
+        term_months,
+        frequency: "monthly".to_string(),
+        first_payment_on: date(2026, 1, 31),
+        currency: "SGD".to_string(),
+        created_at: None,
+    }
+}
+
+fn make_payment(loan: &LiabilityRow, day: NaiveDate, amount: Decimal) -> LiabilityPaymentRow {
+    LiabilityPaymentRow {
+        liability_id: loan.id,
+        transaction_id: Uuid::new_v4(),
+        occurred_at: Utc.from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap()),
+        amount,
+    }
+}
+
+// ============================================================================
+// Schedule Tests
+// ============================================================================
+
+mod schedule_generation {
+    use super::*;
+
+    #[test]
+    fn level_payment_matches_annuity_formula() {
+        let loan = make_loan(dec!(100000), dec!(6), 360);
+
+        assert_eq!(scheduled_payment(&loan), dec!(599.55));
+    }
+
+    #[test]
+    fn longest_term_at_highest_rate_does_not_overflow() {
+        let mut loan = make_loan(dec!(999999999999999.9999), dec!(100), MAX_TERM_MONTHS);
+        loan.frequency = "weekly".to_string();
+
+        let payment = scheduled_payment(&loan);
+        let entries = schedule(&loan);
+
+        // Interest alone is principal / 52 a week; the payment barely exceeds it
+        assert!(payment >= dec!(19230769230769.23));
+        assert!(payment < dec!(19230769230770));
+        assert_eq!(entries.last().unwrap().balance, Decimal::ZERO);
+    }
+
+    #[test]
+    fn interest_free_loan_repays_evenly() {
+        let loan = make_loan(dec!(1200), Decimal::ZERO, 12);
+
+        let entries = schedule(&loan);
+
+        assert_eq!(entries.len(), 12);
+        assert!(entries.iter().all(|e| e.payment == dec!(100)));
+        assert!(entries.iter().all(|e| e.interest.is_zero()));
+    }
+
+    #[test]
+    fn schedule_clears_the_balance_exactly() {
+        let loan = make_loan(dec!(12000), dec!(5), 12);
+
+        let entries = schedule(&loan);
+
+        assert_eq!(entries.len(), 12);
+        assert_eq!(entries[0].interest, dec!(50));
+        assert_eq!(entries[0].principal, dec!(977.29));
+        assert_eq!(entries.last().unwrap().balance, Decimal::ZERO);
+        let repaid: Decimal = entries.iter().map(|e| e.principal).sum();
+        assert_eq!(repaid, dec!(12000));
+    }
+
+    #[test]
+    fn monthly_dates_clamp_to_month_end() {
+        let loan = make_loan(dec!(1200), Decimal::ZERO, 3);
+
+        let dates: Vec<NaiveDate> = schedule(&loan).iter().map(|e| e.due_date).collect();
+
+        assert_eq!(
+            dates,
+            vec![date(2026, 1, 31), date(2026, 2, 28), date(2026, 3, 31)]
+        );
+    }
+
+    #[test]
+    fn weekly_terms_round_to_whole_payments() {
+        assert_eq!(payment_count(12, PaymentFrequency::Weekly), 52);
+        assert_eq!(payment_count(12, PaymentFrequency::Biweekly), 26);
+        assert_eq!(payment_count(1, PaymentFrequency::Biweekly), 2);
+    }
+}
+
+// ============================================================================
+// Repayment Split Tests
+// ============================================================================
+
+mod repayment_split {
+    use super::*;
+
+    #[test]
+    fn interest_is_paid_before_principal() {
+        let loan = make_loan(dec!(12000), dec!(12), 12);
+
+        let payments = [
+            make_payment(&loan, date(2026, 1, 31), dec!(1000)),
+            make_payment(&loan, date(2026, 2, 28), dec!(1000)),
+        ];
+
+        let splits = split_payments(&loan, &payments);
+
+        assert_eq!(splits[0].interest, dec!(120));
+        assert_eq!(splits[0].principal, dec!(880));
+        assert_eq!(splits[0].balance, dec!(11120));
+        assert_eq!(splits[1].interest, dec!(111.20));
+        assert_eq!(splits[1].balance, dec!(10231.20));
+    }
+
+    #[test]
+    fn small_repayment_is_all_interest() {
+        let loan = make_loan(dec!(12000), dec!(12), 12);
+
+        let splits = split_payments(&loan, &[make_payment(&loan, date(2026, 1, 31), dec!(50))]);
+
+        assert_eq!(splits[0].interest, dec!(50));
+        assert_eq!(splits[0].principal, Decimal::ZERO);
+        assert_eq!(splits[0].balance, dec!(12000));
+    }
+
+    #[test]
+    fn overpayment_stops_at_zero() {
+        let loan = make_loan(dec!(1000), Decimal::ZERO, 12);
+
+        let payments = [
+            make_payment(&loan, date(2026, 1, 31), dec!(600)),
+            make_payment(&loan, date(2026, 2, 28), dec!(600)),
+        ];
+
+        assert_eq!(outstanding(&loan, &payments), Decimal::ZERO);
+        assert_eq!(outstanding(&loan, &[]), dec!(1000));
+    }
+
+    #[test]
+    fn late_repayment_accrues_interest_until_it_is_made() {
+        let loan = make_loan(dec!(12000), dec!(12), 12);
+
+        // Paid at the second due date: two periods of interest
+        let splits = split_payments(&loan, &[make_payment(&loan, date(2026, 2, 28), dec!(1000))]);
+
+        assert_eq!(splits[0].interest, dec!(240));
+        assert_eq!(splits[0].principal, dec!(760));
+    }
+
+    #[test]
+    fn repayments_in_one_period_share_its_interest() {
+        let loan = make_loan(dec!(12000), dec!(12), 12);
+        let payments = [
+            // Halfway through January's 31-day period, then on its due date
+            make_payment(&loan, date(2026, 1, 15), dec!(500)),
+            make_payment(&loan, date(2026, 1, 31), dec!(500)),
+        ];
+
+        let splits = split_payments(&loan, &payments);
+
+        assert_eq!(splits[0].interest, dec!(58.06));
+        assert_eq!(splits[0].balance, dec!(11558.06));
+        assert_eq!(splits[1].interest, dec!(59.65));
+        assert_eq!(splits[1].balance, dec!(11117.71));
+    }
+
+    #[test]
+    fn repayment_before_the_first_period_is_all_principal() {
+        let loan = make_loan(dec!(12000), dec!(12), 12);
+
+        let splits = split_payments(&loan, &[make_payment(&loan, date(2025, 12, 1), dec!(1000))]);
+
+        assert_eq!(splits[0].interest, Decimal::ZERO);
+        assert_eq!(splits[0].balance, dec!(11000));
+    }
+}
@@ -0,0 +1,95 @@
+mod tests;
+
+use std::collections::HashSet;
+
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::schemas::{BillMatchRow, BillRow, BillStatus};
+
+/// How many days either side of a due date a payment may land and still count for it
+pub const MATCH_WINDOW_DAYS: i64 = 10;
+
+/// How far back unpaid occurrences are still reported as overdue
+pub const OVERDUE_LOOKBACK_DAYS: i64 = 90;
+
+/// How far a payment may differ from the expected amount, as a share of it
+const AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(2, 0, 0, false, 1); // 0.2
+
+/// The bill's due date in the given month; months without `due_day` use their last day
+pub fn due_date(due_day: i16, year: i32, month: u32) -> NaiveDate {
+    let first = NaiveDate::from_ymd_opt(year, month, 1).expect("valid month");
+    let last_day = ((first + Months::new(1)) - first).num_days() as u32;
+    first
+        .with_day((due_day as u32).clamp(1, last_day))
+        .expect("day within month")
+}
+
+/// Due dates of a bill within `[from, to]`, never before the bill starts
+pub fn occurrences(bill: &BillRow, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+    let from = from.max(bill.starts_on);
+    let mut dates = Vec::new();
+    let mut month = from.with_day(1).expect("every month has a first day");
+    while month <= to {
+        let due = due_date(bill.due_day, month.year(), month.month());
+        if due >= from && due <= to {
+            dates.push(due);
+        }
+        month = month + Months::new(1);
+    }
+    dates
+}
+
+/// The occurrence a transaction pays, if it looks like a payment of this bill: an expense to
+/// the bill's payee (from the bill's pocket, when it has one) close to the expected amount and
+/// within [`MATCH_WINDOW_DAYS`] of the nearest due date.
+pub fn matching_due_date(bill: &BillRow, candidate: &BillMatchRow) -> Option<NaiveDate> {
+    if candidate.is_income || candidate.payee_id != Some(bill.payee_id) {
+        return None;
+    }
+    if bill.pocket_id.is_some() && candidate.pocket_id != bill.pocket_id {
+        return None;
+    }
+    if (candidate.amount - bill.expected_amount).abs() > bill.expected_amount * AMOUNT_TOLERANCE {
+        return None;
+    }
+
+    let paid_on = candidate.occurred_at.date_naive();
+    let month = paid_on.with_day(1).expect("every month has a first day");
+    [month - Months::new(1), month, month + Months::new(1)]
+        .into_iter()
+        .map(|m| due_date(bill.due_day, m.year(), m.month()))
+        .filter(|due| *due >= bill.starts_on)
+        .min_by_key(|due| (*due - paid_on).num_days().abs())
+        .filter(|due| (*due - paid_on).num_days().abs() <= MATCH_WINDOW_DAYS)
+}
+
+/// First bill occurrence the transaction pays that is not already paid. When several bills
+/// fit, the one whose expected amount is closest wins.
+pub fn find_match(
+    bills: &[BillRow],
+    paid: &HashSet<(Uuid, NaiveDate)>,
+    candidate: &BillMatchRow,
+) -> Option<(Uuid, NaiveDate)> {
+    bills
+        .iter()
+        .filter_map(|bill| {
+            matching_due_date(bill, candidate)
+                .filter(|due| !paid.contains(&(bill.id, *due)))
+                .map(|due| (bill, due))
+        })
+        .min_by_key(|(bill, _)| (candidate.amount - bill.expected_amount).abs())
+        .map(|(bill, due)| (bill.id, due))
+}
+
+/// Unpaid occurrences become overdue the day after they fall due
+pub fn status(due: NaiveDate, paid: bool, today: NaiveDate) -> BillStatus {
+    if paid {
+        BillStatus::Paid
+    } else if due < today {
+        BillStatus::Overdue
+    } else {
+        BillStatus::Upcoming
+    }
+}
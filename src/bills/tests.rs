@@ -0,0 +1,176 @@
+#![cfg(test)]
+
+use std::collections::HashSet;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{BillMatchRow, BillRow, BillStatus};
+
+use super::{due_date, find_match, matching_due_date, occurrences, status};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_bill(payee_id: Uuid, expected: Decimal, due_day: i16) -> BillRow {
+    BillRow {
+        id: Uuid::new_v4(),
+        user_id: Uuid::nil(),
+        name: "Electricity".to_string(),
+        payee_id,
+        payee_name: "SP Group".to_string(),
+        expected_amount: expected,
+        due_day,
+        pocket_id: None,
+        pocket_name: None,
+        pocket_icon: None,
+        pocket_currency: None,
+        starts_on: date(2026, 1, 1),
+        created_at: None,
+    }
+}
+
+fn payment(payee_id: Uuid, amount: Decimal, on: NaiveDate) -> BillMatchRow {
+    BillMatchRow {
+        id: Uuid::new_v4(),
+        payee_id: Some(payee_id),
+        pocket_id: None,
+        amount,
+        occurred_at: Utc.from_utc_datetime(&on.and_hms_opt(9, 0, 0).unwrap()),
+        is_income: false,
+    }
+}
+
+// ============================================================================
+// Schedule Tests
+// ============================================================================
+
+mod schedule {
+    use super::*;
+
+    #[test]
+    fn short_months_use_last_day() {
+        assert_eq!(due_date(31, 2026, 2), date(2026, 2, 28));
+        assert_eq!(due_date(31, 2026, 4), date(2026, 4, 30));
+        assert_eq!(due_date(15, 2026, 4), date(2026, 4, 15));
+    }
+
+    #[test]
+    fn occurrences_respect_range_and_start() {
+        let mut bill = make_bill(Uuid::new_v4(), dec!(100), 5);
+        bill.starts_on = date(2026, 9, 10);
+
+        let dates = occurrences(&bill, date(2026, 8, 1), date(2026, 12, 4));
+
+        assert_eq!(dates, vec![date(2026, 10, 5), date(2026, 11, 5)]);
+    }
+
+    #[test]
+    fn status_follows_due_date() {
+        let today = date(2026, 10, 18);
+        assert_eq!(
+            status(date(2026, 10, 17), false, today),
+            BillStatus::Overdue
+        );
+        assert_eq!(
+            status(date(2026, 10, 18), false, today),
+            BillStatus::Upcoming
+        );
+        assert_eq!(status(date(2026, 10, 17), true, today), BillStatus::Paid);
+    }
+}
+
+// ============================================================================
+// Matching Tests
+// ============================================================================
+
+mod matching {
+    use super::*;
+
+    #[test]
+    fn payment_near_due_date_matches() {
+        let payee = Uuid::new_v4();
+        let bill = make_bill(payee, dec!(120), 5);
+
+        // Paid a little early, for slightly more than expected
+        let due = matching_due_date(&bill, &payment(payee, dec!(131.40), date(2026, 9, 29)));
+
+        assert_eq!(due, Some(date(2026, 10, 5)));
+    }
+
+    #[test]
+    fn amount_far_from_expected_does_not_match() {
+        let payee = Uuid::new_v4();
+        let bill = make_bill(payee, dec!(120), 5);
+
+        assert_eq!(
+            matching_due_date(&bill, &payment(payee, dec!(200), date(2026, 10, 5))),
+            None
+        );
+    }
+
+    #[test]
+    fn payment_outside_window_does_not_match() {
+        let payee = Uuid::new_v4();
+        let bill = make_bill(payee, dec!(120), 5);
+
+        assert_eq!(
+            matching_due_date(&bill, &payment(payee, dec!(120), date(2026, 10, 20))),
+            None
+        );
+    }
+
+    #[test]
+    fn other_payee_or_income_does_not_match() {
+        let payee = Uuid::new_v4();
+        let bill = make_bill(payee, dec!(120), 5);
+        let mut refund = payment(payee, dec!(120), date(2026, 10, 5));
+        refund.is_income = true;
+
+        assert_eq!(
+            matching_due_date(
+                &bill,
+                &payment(Uuid::new_v4(), dec!(120), date(2026, 10, 5))
+            ),
+            None
+        );
+        assert_eq!(matching_due_date(&bill, &refund), None);
+    }
+
+    #[test]
+    fn paid_occurrence_is_not_matched_twice() {
+        let payee = Uuid::new_v4();
+        let bill = make_bill(payee, dec!(120), 5);
+        let paid = HashSet::from([(bill.id, date(2026, 10, 5))]);
+
+        let found = find_match(
+            std::slice::from_ref(&bill),
+            &paid,
+            &payment(payee, dec!(120), date(2026, 10, 6)),
+        );
+
+        assert_eq!(found, None);
+    }
+
+    #[test]
+    fn closest_amount_wins() {
+        let payee = Uuid::new_v4();
+        let internet = make_bill(payee, dec!(50), 10);
+        let mobile = make_bill(payee, dec!(45), 10);
+
+        let found = find_match(
+            &[internet, mobile.clone()],
+            &HashSet::new(),
+            &payment(payee, dec!(44), date(2026, 10, 9)),
+        );
+
+        assert_eq!(found, Some((mobile.id, date(2026, 10, 10))));
+    }
+}
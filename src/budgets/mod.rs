@@ -0,0 +1,112 @@
+mod tests;
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::schemas::{Budget, BudgetAlert, BudgetLine, CategorySummary};
+
+/// How many months back a rollover budget carries unspent or overspent amounts from
+pub const MAX_ROLLOVER_MONTHS: u32 = 12;
+
+/// Share of the available amount at which a `warning` alert is raised
+const WARNING_RATIO: Decimal = Decimal::from_parts(8, 0, 0, false, 1); // 0.8
+
+/// First day of the month containing `date`
+pub fn month_of(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("every month has a first day")
+}
+
+/// Inclusive UTC bounds of the month starting at `month`, as expected by
+/// `TransactionRepository::get_spending_analysis`
+pub fn month_bounds(month: NaiveDate) -> (DateTime<Utc>, DateTime<Utc>) {
+    let start = month.and_time(NaiveTime::MIN).and_utc();
+    let next = (month + Months::new(1)).and_time(NaiveTime::MIN).and_utc();
+    (start, next - Duration::microseconds(1))
+}
+
+/// Earlier months whose leftovers roll into `month`, oldest first
+pub fn rollover_months(budget: &Budget, month: NaiveDate) -> Vec<NaiveDate> {
+    if !budget.rollover {
+        return Vec::new();
+    }
+    let earliest = budget
+        .starts_on
+        .max(month - Months::new(MAX_ROLLOVER_MONTHS));
+    let mut months = Vec::new();
+    let mut current = earliest;
+    while current < month {
+        months.push(current);
+        current = current + Months::new(1);
+    }
+    months
+}
+
+/// Sum of what was left (or overspent) in each earlier month, given what was spent in each
+pub fn carried_over(amount: Decimal, spent_per_month: &[Decimal]) -> Decimal {
+    spent_per_month.iter().map(|spent| amount - spent).sum()
+}
+
+/// Month-end spending if the pace so far continues. Only defined while `month` is in progress.
+pub fn projected_spend(spent: Decimal, month: NaiveDate, today: NaiveDate) -> Option<Decimal> {
+    if month_of(today) != month {
+        return None;
+    }
+    let days_in_month = ((month + Months::new(1)) - month).num_days();
+    Some(spent * Decimal::from(days_in_month) / Decimal::from(today.day()))
+}
+
+/// Alert level for `spent` against `available`. With nothing available any spending exceeds it.
+pub fn alert(spent: Decimal, available: Decimal) -> Option<BudgetAlert> {
+    if available <= Decimal::ZERO {
+        return (spent > Decimal::ZERO).then_some(BudgetAlert::Exceeded);
+    }
+    if spent >= available {
+        Some(BudgetAlert::Exceeded)
+    } else if spent >= available * WARNING_RATIO {
+        Some(BudgetAlert::Warning)
+    } else {
+        None
+    }
+}
+
+/// Budget-vs-actual line for one category in `month`
+pub fn line(
+    budget: &Budget,
+    carried_over: Decimal,
+    spent: Decimal,
+    month: NaiveDate,
+    today: NaiveDate,
+) -> BudgetLine {
+    let available = budget.amount + carried_over;
+    let percent_used = if available > Decimal::ZERO {
+        spent / available * Decimal::ONE_HUNDRED
+    } else if spent > Decimal::ZERO {
+        Decimal::ONE_HUNDRED
+    } else {
+        Decimal::ZERO
+    };
+
+    BudgetLine {
+        budget_id: budget.id,
+        category_id: budget.category_id,
+        category_name: budget.category_name.clone(),
+        category_icon: budget.category_icon.clone(),
+        budgeted: budget.amount,
+        carried_over,
+        available,
+        spent,
+        remaining: available - spent,
+        percent_used,
+        projected_spend: projected_spend(spent, month, today),
+        alert: alert(spent, available),
+    }
+}
+
+/// Expense total for a category in a `get_spending_analysis` result
+pub fn spent(categories: &[CategorySummary], category_id: i32) -> Decimal {
+    categories
+        .iter()
+        .find(|c| c.category_id == category_id && !c.is_income)
+        .map(|c| c.total)
+        .unwrap_or(Decimal::ZERO)
+}
@@ -0,0 +1,133 @@
+#![cfg(test)]
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{Budget, BudgetAlert};
+
+use super::{alert, carried_over, line, month_bounds, projected_spend, rollover_months};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_budget(amount: Decimal, rollover: bool, starts_on: NaiveDate) -> Budget {
+    Budget {
+        id: Uuid::new_v4(),
+        category_id: 1,
+        category_name: "Food".to_string(),
+        category_icon: "restaurant".to_string(),
+        amount,
+        rollover,
+        starts_on,
+        created_at: None,
+    }
+}
+
+// ============================================================================
+// Rollover Tests
+// ============================================================================
+
+mod rollover {
+    use super::*;
+
+    #[test]
+    fn months_run_from_start_to_previous_month() {
+        let budget = make_budget(dec!(500), true, date(2026, 7, 1));
+
+        assert_eq!(
+            rollover_months(&budget, date(2026, 10, 1)),
+            vec![date(2026, 7, 1), date(2026, 8, 1), date(2026, 9, 1)]
+        );
+    }
+
+    #[test]
+    fn window_is_capped() {
+        let budget = make_budget(dec!(500), true, date(2020, 1, 1));
+
+        let months = rollover_months(&budget, date(2026, 10, 1));
+
+        assert_eq!(months.len(), 12);
+        assert_eq!(months[0], date(2025, 10, 1));
+    }
+
+    #[test]
+    fn disabled_rollover_carries_nothing() {
+        let budget = make_budget(dec!(500), false, date(2026, 1, 1));
+
+        assert!(rollover_months(&budget, date(2026, 10, 1)).is_empty());
+    }
+
+    #[test]
+    fn underspending_and_overspending_net_out() {
+        // Given: 100 left in one month, 30 overspent in the next
+        let carried = carried_over(dec!(500), &[dec!(400), dec!(530)]);
+
+        assert_eq!(carried, dec!(70));
+    }
+}
+
+// ============================================================================
+// Pace And Alert Tests
+// ============================================================================
+
+mod pace_and_alerts {
+    use super::*;
+
+    #[test]
+    fn pace_extrapolates_to_month_end() {
+        // 10 days into a 30-day month
+        let projected = projected_spend(dec!(200), date(2026, 9, 1), date(2026, 9, 10));
+
+        assert_eq!(projected, Some(dec!(600)));
+    }
+
+    #[test]
+    fn pace_only_applies_to_current_month() {
+        assert_eq!(
+            projected_spend(dec!(200), date(2026, 9, 1), date(2026, 10, 18)),
+            None
+        );
+    }
+
+    #[test]
+    fn alerts_at_thresholds() {
+        assert_eq!(alert(dec!(79.99), dec!(100)), None);
+        assert_eq!(alert(dec!(80), dec!(100)), Some(BudgetAlert::Warning));
+        assert_eq!(alert(dec!(100), dec!(100)), Some(BudgetAlert::Exceeded));
+        assert_eq!(alert(dec!(1), Decimal::ZERO), Some(BudgetAlert::Exceeded));
+        assert_eq!(alert(Decimal::ZERO, dec!(-20)), None);
+    }
+
+    #[test]
+    fn line_includes_carry_over() {
+        let budget = make_budget(dec!(500), true, date(2026, 1, 1));
+
+        let line = line(
+            &budget,
+            dec!(100),
+            dec!(450),
+            date(2026, 9, 1),
+            date(2026, 10, 18),
+        );
+
+        assert_eq!(line.available, dec!(600));
+        assert_eq!(line.remaining, dec!(150));
+        assert_eq!(line.percent_used, dec!(75));
+        assert_eq!(line.alert, None);
+    }
+
+    #[test]
+    fn month_bounds_cover_whole_month() {
+        let (start, end) = month_bounds(date(2026, 2, 1));
+
+        assert_eq!(start.to_rfc3339(), "2026-02-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-02-28T23:59:59.999999+00:00");
+    }
+}
@@ -0,0 +1,123 @@
+mod tests;
+
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::schemas::{
+    BalanceInterval, CashFlowBreakdown, CashFlowLine, CashFlowPoint, CashFlowRow,
+};
+
+/// First day of the bucket containing `date`, matching Postgres `date_trunc`
+pub fn bucket_start(interval: BalanceInterval, date: NaiveDate) -> NaiveDate {
+    match interval {
+        BalanceInterval::Day => date,
+        BalanceInterval::Week => {
+            date - Duration::days(i64::from(date.weekday().num_days_from_monday()))
+        }
+        BalanceInterval::Month => date.with_day(1).expect("every month has a first day"),
+    }
+}
+
+/// Number of buckets between the ones containing `start` and `end`, both included
+pub fn bucket_count(interval: BalanceInterval, start: NaiveDate, end: NaiveDate) -> i64 {
+    let (first, last) = (bucket_start(interval, start), bucket_start(interval, end));
+    match interval {
+        BalanceInterval::Day => (last - first).num_days() + 1,
+        BalanceInterval::Week => (last - first).num_days() / 7 + 1,
+        BalanceInterval::Month => {
+            i64::from(last.year() - first.year()) * 12 + i64::from(last.month())
+                - i64::from(first.month())
+                + 1
+        }
+    }
+}
+
+fn next_bucket(interval: BalanceInterval, bucket: NaiveDate) -> Option<NaiveDate> {
+    match interval {
+        BalanceInterval::Day => bucket.succ_opt(),
+        BalanceInterval::Week => bucket.checked_add_signed(Duration::weeks(1)),
+        BalanceInterval::Month => bucket.checked_add_months(Months::new(1)),
+    }
+}
+
+/// One point per bucket from `start` to `end`, zero-filled where nothing happened. Rows hold
+/// per-category, per-pocket totals; with a breakdown they are merged into one line per category
+/// or pocket, sorted by name.
+pub fn build(
+    interval: BalanceInterval,
+    start: NaiveDate,
+    end: NaiveDate,
+    breakdown: Option<CashFlowBreakdown>,
+    rows: Vec<CashFlowRow>,
+) -> Vec<CashFlowPoint> {
+    let mut points = BTreeMap::new();
+    let mut bucket = Some(bucket_start(interval, start));
+    let last = bucket_start(interval, end);
+    while let Some(date) = bucket.filter(|d| *d <= last) {
+        points.insert(
+            date,
+            CashFlowPoint {
+                date,
+                income: Decimal::ZERO,
+                spending: Decimal::ZERO,
+                net: Decimal::ZERO,
+                breakdown: Vec::new(),
+            },
+        );
+        bucket = next_bucket(interval, date);
+    }
+
+    for row in rows {
+        let Some(point) = points.get_mut(&row.bucket) else {
+            continue;
+        };
+        let (income, spending) = if row.is_income {
+            (row.total, Decimal::ZERO)
+        } else {
+            (Decimal::ZERO, row.total)
+        };
+        point.income += income;
+        point.spending += spending;
+        point.net += income - spending;
+
+        let (category_id, pocket_id, name) = match breakdown {
+            None => continue,
+            Some(CashFlowBreakdown::Category) => (Some(row.category_id), None, row.category_name),
+            Some(CashFlowBreakdown::Pocket) => match row.pocket_id {
+                Some(id) => (None, Some(id), row.pocket_name.unwrap_or_default()),
+                None => continue,
+            },
+        };
+        let line = match point
+            .breakdown
+            .iter_mut()
+            .find(|l| l.category_id == category_id && l.pocket_id == pocket_id)
+        {
+            Some(line) => line,
+            None => {
+                point.breakdown.push(CashFlowLine {
+                    category_id,
+                    pocket_id,
+                    name,
+                    income: Decimal::ZERO,
+                    spending: Decimal::ZERO,
+                    net: Decimal::ZERO,
+                });
+                point.breakdown.last_mut().expect("line was just pushed")
+            }
+        };
+        line.income += income;
+        line.spending += spending;
+        line.net += income - spending;
+    }
+
+    points
+        .into_values()
+        .map(|mut point| {
+            point.breakdown.sort_by(|a, b| a.name.cmp(&b.name));
+            point
+        })
+        .collect()
+}
@@ -0,0 +1,179 @@
+#![cfg(test)]
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{BalanceInterval, CashFlowBreakdown, CashFlowRow};
+
+use super::{bucket_count, bucket_start, build};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_row(
+    bucket: NaiveDate,
+    category: (i32, &str, bool),
+    pocket: Uuid,
+    total: Decimal,
+) -> CashFlowRow {
+    CashFlowRow {
+        bucket,
+        category_id: category.0,
+        category_name: category.1.to_string(),
+        is_income: category.2,
+        pocket_id: Some(pocket),
+        pocket_name: Some(format!("Pocket {}", pocket.as_u128() % 10)),
+        total,
+    }
+}
+
+const FOOD: (i32, &str, bool) = (1, "Food", false);
+const RENT: (i32, &str, bool) = (2, "Rent", false);
+const SALARY: (i32, &str, bool) = (3, "Salary", true);
+
+// ============================================================================
+// Bucket Tests
+// ============================================================================
+
+mod buckets {
+    use super::*;
+
+    #[test]
+    fn test_weeks_start_on_monday() {
+        // 2026-10-18 is a Sunday
+        assert_eq!(
+            bucket_start(BalanceInterval::Week, date(2026, 10, 18)),
+            date(2026, 10, 12)
+        );
+        assert_eq!(
+            bucket_start(BalanceInterval::Week, date(2026, 10, 12)),
+            date(2026, 10, 12)
+        );
+        assert_eq!(
+            bucket_start(BalanceInterval::Month, date(2026, 10, 18)),
+            date(2026, 10, 1)
+        );
+    }
+
+    #[test]
+    fn test_count_includes_partial_buckets() {
+        let (start, end) = (date(2026, 1, 31), date(2026, 3, 1));
+        assert_eq!(bucket_count(BalanceInterval::Day, start, end), 30);
+        // Mon 26 Jan .. Mon 23 Feb
+        assert_eq!(bucket_count(BalanceInterval::Week, start, end), 5);
+        assert_eq!(bucket_count(BalanceInterval::Month, start, end), 3);
+        assert_eq!(bucket_count(BalanceInterval::Month, end, end), 1);
+    }
+}
+
+// ============================================================================
+// Build Tests
+// ============================================================================
+
+mod build_series {
+    use super::*;
+
+    #[test]
+    fn test_empty_buckets_are_zero_filled() {
+        let pocket = Uuid::new_v4();
+        let rows = vec![make_row(date(2026, 3, 1), FOOD, pocket, dec!(40))];
+
+        let points = build(
+            BalanceInterval::Month,
+            date(2026, 1, 15),
+            date(2026, 3, 10),
+            None,
+            rows,
+        );
+
+        let dates: Vec<_> = points.iter().map(|p| p.date).collect();
+        assert_eq!(
+            dates,
+            vec![date(2026, 1, 1), date(2026, 2, 1), date(2026, 3, 1)]
+        );
+        assert_eq!(points[0].spending, Decimal::ZERO);
+        assert_eq!(points[1].net, Decimal::ZERO);
+        assert_eq!(points[2].spending, dec!(40));
+        assert_eq!(points[2].net, dec!(-40));
+        assert!(points[2].breakdown.is_empty());
+    }
+
+    #[test]
+    fn test_totals_split_income_and_spending() {
+        let pocket = Uuid::new_v4();
+        let day = date(2026, 3, 2);
+        let rows = vec![
+            make_row(day, SALARY, pocket, dec!(1000)),
+            make_row(day, FOOD, pocket, dec!(40)),
+            make_row(day, RENT, pocket, dec!(500)),
+        ];
+
+        let points = build(BalanceInterval::Day, day, day, None, rows);
+
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].income, dec!(1000));
+        assert_eq!(points[0].spending, dec!(540));
+        assert_eq!(points[0].net, dec!(460));
+    }
+
+    #[test]
+    fn test_category_breakdown_merges_pockets() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let week = date(2026, 3, 2);
+        let rows = vec![
+            make_row(week, FOOD, a, dec!(40)),
+            make_row(week, FOOD, b, dec!(10)),
+            make_row(week, SALARY, a, dec!(1000)),
+        ];
+
+        let points = build(
+            BalanceInterval::Week,
+            week,
+            date(2026, 3, 8),
+            Some(CashFlowBreakdown::Category),
+            rows,
+        );
+
+        let lines = &points[0].breakdown;
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].name, "Food");
+        assert_eq!(lines[0].category_id, Some(1));
+        assert_eq!(lines[0].spending, dec!(50));
+        assert_eq!(lines[0].net, dec!(-50));
+        assert_eq!(lines[1].name, "Salary");
+        assert_eq!(lines[1].income, dec!(1000));
+    }
+
+    #[test]
+    fn test_pocket_breakdown_merges_categories() {
+        let pocket = Uuid::new_v4();
+        let day = date(2026, 3, 2);
+        let rows = vec![
+            make_row(day, SALARY, pocket, dec!(1000)),
+            make_row(day, FOOD, pocket, dec!(40)),
+        ];
+
+        let points = build(
+            BalanceInterval::Day,
+            day,
+            day,
+            Some(CashFlowBreakdown::Pocket),
+            rows,
+        );
+
+        let lines = &points[0].breakdown;
+        assert_eq!(lines.len(), 1);
+        assert_eq!(lines[0].pocket_id, Some(pocket));
+        assert_eq!(lines[0].category_id, None);
+        assert_eq!(lines[0].income, dec!(1000));
+        assert_eq!(lines[0].spending, dec!(40));
+        assert_eq!(lines[0].net, dec!(960));
+    }
+}
@@ -0,0 +1,118 @@
+mod tests;
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::schemas::{CategoryDelta, CategorySummary, ComparisonPeriod, Delta, SpendingComparison};
+
+/// Range to compare `start..=end` against. A range covering whole calendar months is compared
+/// with the same number of months before it (so October is compared with September, not with
+/// the last 31 days); any other range with the same number of days before it.
+pub fn comparison_range(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    period: ComparisonPeriod,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let months = match period {
+        ComparisonPeriod::LastYear => Some(12),
+        ComparisonPeriod::Previous => whole_months(start, end),
+    };
+    if let Some(months) = months
+        && let (Some(from), Some(to)) = (
+            start.checked_sub_months(Months::new(months)),
+            end.checked_sub_months(Months::new(months)),
+        )
+    {
+        return (from, to);
+    }
+
+    let days = Duration::days((end.date_naive() - start.date_naive()).num_days() + 1);
+    (start - days, end - days)
+}
+
+/// Number of calendar months covered when the range starts at midnight on the 1st and ends on
+/// the last second of a month (or at midnight on the following 1st)
+fn whole_months(start: DateTime<Utc>, end: DateTime<Utc>) -> Option<u32> {
+    if start.day() != 1 || start.time() != NaiveTime::MIN {
+        return None;
+    }
+    let next = end + Duration::seconds(1);
+    let boundary = next.date_naive().and_time(NaiveTime::MIN).and_utc();
+    if next.day() != 1 || next - boundary > Duration::seconds(1) {
+        return None;
+    }
+    let months =
+        (boundary.year() - start.year()) * 12 + boundary.month() as i32 - start.month() as i32;
+    u32::try_from(months).ok().filter(|m| *m > 0)
+}
+
+pub fn delta(current: Decimal, previous: Decimal) -> Delta {
+    let change = current - previous;
+    Delta {
+        current,
+        previous,
+        change,
+        change_percent: (!previous.is_zero())
+            .then(|| change / previous.abs() * Decimal::ONE_HUNDRED),
+    }
+}
+
+fn totals(categories: &[CategorySummary]) -> (Decimal, Decimal) {
+    categories
+        .iter()
+        .fold((Decimal::ZERO, Decimal::ZERO), |(income, spent), c| {
+            if c.is_income {
+                (income + c.total, spent)
+            } else {
+                (income, spent + c.total)
+            }
+        })
+}
+
+/// Compare per-category totals of the analysed range with those of the comparison period.
+/// Categories keep the order of the analysed range; ones only seen in the comparison period
+/// follow in their own order.
+pub fn build(
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+    current: &[CategorySummary],
+    previous: Vec<CategorySummary>,
+) -> SpendingComparison {
+    let (income, spent) = totals(current);
+    let (previous_income, previous_spent) = totals(&previous);
+
+    let mut categories: Vec<CategoryDelta> = current
+        .iter()
+        .map(|c| CategoryDelta {
+            category_id: c.category_id,
+            category: c.category.clone(),
+            is_income: c.is_income,
+            icon: c.icon.clone(),
+            delta: delta(c.total, Decimal::ZERO),
+        })
+        .collect();
+    for prev in previous {
+        match categories
+            .iter_mut()
+            .find(|c| c.category_id == prev.category_id)
+        {
+            Some(c) => c.delta = delta(c.delta.current, prev.total),
+            None => categories.push(CategoryDelta {
+                category_id: prev.category_id,
+                category: prev.category,
+                is_income: prev.is_income,
+                icon: prev.icon,
+                delta: delta(Decimal::ZERO, prev.total),
+            }),
+        }
+    }
+
+    SpendingComparison {
+        start_date,
+        end_date,
+        total_income: delta(income, previous_income),
+        total_spent: delta(spent, previous_spent),
+        net_income: delta(income - spent, previous_income - previous_spent),
+        categories,
+    }
+}
@@ -0,0 +1,147 @@
+#![cfg(test)]
+
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::schemas::{CategorySummary, ComparisonPeriod};
+
+use super::{build, comparison_range, delta};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn at(y: i32, m: u32, d: u32, h: u32, min: u32, s: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(y, m, d, h, min, s).unwrap()
+}
+
+fn make_category(id: i32, name: &str, is_income: bool, total: Decimal) -> CategorySummary {
+    CategorySummary {
+        category_id: id,
+        category: name.to_string(),
+        total,
+        is_income,
+        icon: "help_outline".to_string(),
+    }
+}
+
+// ============================================================================
+// Range Tests
+// ============================================================================
+
+mod range {
+    use super::*;
+
+    #[test]
+    fn test_whole_month_compares_with_previous_month() {
+        let (start, end) = comparison_range(
+            at(2026, 10, 1, 0, 0, 0),
+            at(2026, 10, 31, 23, 59, 59),
+            ComparisonPeriod::Previous,
+        );
+        assert_eq!(start, at(2026, 9, 1, 0, 0, 0));
+        assert_eq!(end, at(2026, 9, 30, 23, 59, 59));
+    }
+
+    #[test]
+    fn test_exclusive_month_end_is_recognised() {
+        let (start, end) = comparison_range(
+            at(2026, 3, 1, 0, 0, 0),
+            at(2026, 4, 1, 0, 0, 0),
+            ComparisonPeriod::Previous,
+        );
+        assert_eq!(start, at(2026, 2, 1, 0, 0, 0));
+        assert_eq!(end, at(2026, 3, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn test_other_ranges_shift_by_their_days() {
+        let (start, end) = comparison_range(
+            at(2026, 10, 12, 0, 0, 0),
+            at(2026, 10, 18, 23, 59, 59),
+            ComparisonPeriod::Previous,
+        );
+        assert_eq!(start, at(2026, 10, 5, 0, 0, 0));
+        assert_eq!(end, at(2026, 10, 11, 23, 59, 59));
+    }
+
+    #[test]
+    fn test_last_year_clamps_leap_day() {
+        let (start, end) = comparison_range(
+            at(2028, 2, 1, 0, 0, 0),
+            at(2028, 2, 29, 23, 59, 59),
+            ComparisonPeriod::LastYear,
+        );
+        assert_eq!(start, at(2027, 2, 1, 0, 0, 0));
+        assert_eq!(end, at(2027, 2, 28, 23, 59, 59));
+    }
+}
+
+// ============================================================================
+// Delta Tests
+// ============================================================================
+
+mod deltas {
+    use super::*;
+
+    #[test]
+    fn test_percent_relative_to_previous() {
+        let d = delta(dec!(150), dec!(100));
+        assert_eq!(d.change, dec!(50));
+        assert_eq!(d.change_percent, Some(dec!(50)));
+
+        // Net income going from -100 to -50 is an improvement
+        let d = delta(dec!(-50), dec!(-100));
+        assert_eq!(d.change, dec!(50));
+        assert_eq!(d.change_percent, Some(dec!(50)));
+    }
+
+    #[test]
+    fn test_no_percent_without_previous_value() {
+        let d = delta(dec!(20), Decimal::ZERO);
+        assert_eq!(d.change, dec!(20));
+        assert_eq!(d.change_percent, None);
+    }
+
+    #[test]
+    fn test_build_merges_categories_of_both_periods() {
+        let current = vec![
+            make_category(3, "Salary", true, dec!(1000)),
+            make_category(1, "Food", false, dec!(300)),
+        ];
+        let previous = vec![
+            make_category(1, "Food", false, dec!(200)),
+            make_category(2, "Rent", false, dec!(500)),
+        ];
+
+        let comparison = build(
+            at(2026, 9, 1, 0, 0, 0),
+            at(2026, 9, 30, 23, 59, 59),
+            &current,
+            previous,
+        );
+
+        let ids: Vec<_> = comparison
+            .categories
+            .iter()
+            .map(|c| c.category_id)
+            .collect();
+        assert_eq!(ids, vec![3, 1, 2]);
+        assert_eq!(comparison.categories[0].delta.change_percent, None);
+        assert_eq!(comparison.categories[1].delta.change, dec!(100));
+        assert_eq!(
+            comparison.categories[1].delta.change_percent,
+            Some(dec!(50))
+        );
+        assert_eq!(comparison.categories[2].delta.current, Decimal::ZERO);
+        assert_eq!(
+            comparison.categories[2].delta.change_percent,
+            Some(dec!(-100))
+        );
+
+        assert_eq!(comparison.total_income, delta(dec!(1000), Decimal::ZERO));
+        assert_eq!(comparison.total_spent, delta(dec!(300), dec!(700)));
+        assert_eq!(comparison.net_income, delta(dec!(700), dec!(-700)));
+    }
+}
@@ -0,0 +1,87 @@
+mod tests;
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use rust_decimal::Decimal;
+
+use crate::bills;
+use crate::schemas::{CardActivityRow, CardStatement, CreditCard, CreditCardSettings, Pocket};
+
+/// Days from statement closing to the payment due date when not configured
+pub const DEFAULT_DUE_DAYS: i16 = 21;
+
+/// Minimum payment percentage when not configured
+const DEFAULT_MINIMUM_PAYMENT_PERCENT: Decimal = Decimal::ONE;
+
+/// Settings with defaults filled in
+pub fn settings(req: &CreditCardSettings) -> CreditCard {
+    CreditCard {
+        statement_day: req.statement_day,
+        due_days: req.due_days.unwrap_or(DEFAULT_DUE_DAYS),
+        credit_limit: req.credit_limit,
+        minimum_payment_percent: req
+            .minimum_payment_percent
+            .unwrap_or(DEFAULT_MINIMUM_PAYMENT_PERCENT),
+        minimum_payment_floor: req.minimum_payment_floor.unwrap_or(Decimal::ZERO),
+    }
+}
+
+/// Statement closing date in the month of `month`
+fn closing_in(card: &CreditCard, month: NaiveDate) -> NaiveDate {
+    bills::due_date(card.statement_day, month.year(), month.month())
+}
+
+/// Last statement closing strictly before `today`; spending on the closing date itself still
+/// belongs to that statement
+pub fn last_closing(card: &CreditCard, today: NaiveDate) -> NaiveDate {
+    let closing = closing_in(card, today);
+    if closing < today {
+        closing
+    } else {
+        closing_in(card, today - Months::new(1))
+    }
+}
+
+/// Minimum payment on a statement balance: the configured share of it, at least the floor,
+/// never more than the balance itself
+pub fn minimum_payment(card: &CreditCard, statement_balance: Decimal) -> Decimal {
+    if statement_balance <= Decimal::ZERO {
+        return Decimal::ZERO;
+    }
+    (statement_balance * card.minimum_payment_percent / Decimal::ONE_HUNDRED)
+        .round_dp(2)
+        .max(card.minimum_payment_floor)
+        .min(statement_balance)
+}
+
+/// The card's last closed statement. Pocket balances go negative as the card is used, so the
+/// amounts owed are the negated balances.
+pub fn statement(
+    pocket: &Pocket,
+    card: CreditCard,
+    activity: &CardActivityRow,
+    today: NaiveDate,
+) -> CardStatement {
+    let closing_date = last_closing(&card, today);
+    let previous_closing = closing_in(&card, closing_date - Months::new(1));
+    let statement_balance = (-activity.balance_at_closing).max(Decimal::ZERO);
+    let minimum = minimum_payment(&card, statement_balance);
+    let paid = activity.credits_since_closing;
+    let current_balance = (-pocket.balance).max(Decimal::ZERO);
+
+    CardStatement {
+        pocket_id: pocket.id,
+        currency: pocket.currency.clone(),
+        period_start: previous_closing + Duration::days(1),
+        closing_date,
+        due_date: closing_date + Duration::days(card.due_days as i64),
+        statement_balance,
+        minimum_payment: minimum,
+        paid_since_closing: paid,
+        remaining_statement_balance: (statement_balance - paid).max(Decimal::ZERO),
+        remaining_minimum_payment: (minimum - paid).max(Decimal::ZERO),
+        current_balance,
+        available_credit: card.credit_limit.map(|limit| limit + pocket.balance),
+        next_closing_date: closing_in(&card, closing_date + Months::new(1)),
+        settings: card,
+    }
+}
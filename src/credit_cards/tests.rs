@@ -0,0 +1,129 @@
+#![cfg(test)]
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{CardActivityRow, CreditCard, Pocket};
+
+use super::{last_closing, minimum_payment, statement};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_card(statement_day: i16) -> CreditCard {
+    CreditCard {
+        statement_day,
+        due_days: 21,
+        credit_limit: Some(dec!(5000)),
+        minimum_payment_percent: dec!(3),
+        minimum_payment_floor: dec!(50),
+    }
+}
+
+fn make_pocket(balance: Decimal) -> Pocket {
+    Pocket {
+        id: Uuid::new_v4(),
+        name: "Visa".to_string(),
+        description: None,
+        icon: "credit_card".to_string(),
+        currency: "SGD".to_string(),
+        kind: "credit_card".to_string(),
+        is_default: false,
+        archived_at: None,
+        created_at: None,
+        balance,
+        posted_balance: balance,
+    }
+}
+
+// ============================================================================
+// Cycle Tests
+// ============================================================================
+
+mod cycle {
+    use super::*;
+
+    #[test]
+    fn closing_date_itself_is_still_open() {
+        let card = make_card(15);
+
+        assert_eq!(last_closing(&card, date(2026, 10, 15)), date(2026, 9, 15));
+        assert_eq!(last_closing(&card, date(2026, 10, 16)), date(2026, 10, 15));
+    }
+
+    #[test]
+    fn short_months_close_on_their_last_day() {
+        let card = make_card(31);
+
+        assert_eq!(last_closing(&card, date(2026, 3, 10)), date(2026, 2, 28));
+    }
+
+    #[test]
+    fn statement_covers_the_previous_cycle() {
+        let card = make_card(15);
+        let activity = CardActivityRow {
+            balance_at_closing: dec!(-1200),
+            credits_since_closing: Decimal::ZERO,
+        };
+
+        let s = statement(
+            &make_pocket(dec!(-1500)),
+            card,
+            &activity,
+            date(2026, 10, 18),
+        );
+
+        assert_eq!(s.period_start, date(2026, 9, 16));
+        assert_eq!(s.closing_date, date(2026, 10, 15));
+        assert_eq!(s.due_date, date(2026, 11, 5));
+        assert_eq!(s.next_closing_date, date(2026, 11, 15));
+        assert_eq!(s.statement_balance, dec!(1200));
+        assert_eq!(s.current_balance, dec!(1500));
+        assert_eq!(s.available_credit, Some(dec!(3500)));
+    }
+}
+
+// ============================================================================
+// Payment Tests
+// ============================================================================
+
+mod payments {
+    use super::*;
+
+    #[test]
+    fn minimum_is_a_share_with_a_floor() {
+        let card = make_card(15);
+
+        assert_eq!(minimum_payment(&card, dec!(4000)), dec!(120));
+        assert_eq!(minimum_payment(&card, dec!(1000)), dec!(50));
+        assert_eq!(minimum_payment(&card, dec!(30)), dec!(30));
+        assert_eq!(minimum_payment(&card, dec!(-20)), Decimal::ZERO);
+    }
+
+    #[test]
+    fn payments_after_closing_reduce_what_is_due() {
+        let card = make_card(15);
+        let activity = CardActivityRow {
+            balance_at_closing: dec!(-4000),
+            credits_since_closing: dec!(100),
+        };
+
+        let s = statement(
+            &make_pocket(dec!(-3900)),
+            card,
+            &activity,
+            date(2026, 10, 18),
+        );
+
+        assert_eq!(s.minimum_payment, dec!(120));
+        assert_eq!(s.remaining_minimum_payment, dec!(20));
+        assert_eq!(s.remaining_statement_balance, dec!(3900));
+    }
+}
@@ -0,0 +1,82 @@
+mod tests;
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::payees::normalize_name;
+
+/// How far apart two transactions can be and still count as the same purchase
+pub const WINDOW_DAYS: i64 = 3;
+
+/// Minimum score (out of 100) for a pair to be flagged
+pub const FLAG_THRESHOLD: i32 = 75;
+
+const AMOUNT_WEIGHT: i32 = 40;
+const TIME_WEIGHT: i64 = 30;
+const DESCRIPTION_WEIGHT: usize = 30;
+
+/// Amounts within 1% of each other are treated as near matches (e.g. FX rounding)
+const AMOUNT_TOLERANCE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
+/// The transaction attributes duplicate detection compares
+pub struct DuplicateInput<'a> {
+    pub pocket_id: Uuid,
+    /// Amount in base currency
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+    pub description: Option<&'a str>,
+}
+
+/// Likelihood (0-100) that `a` and `b` are the same transaction entered twice.
+/// Returns None when they can't be duplicates at all: different pockets,
+/// amounts outside the tolerance, or more than `WINDOW_DAYS` apart.
+pub fn score(a: &DuplicateInput, b: &DuplicateInput) -> Option<i32> {
+    if a.pocket_id != b.pocket_id {
+        return None;
+    }
+
+    let amount_score = if a.amount == b.amount {
+        AMOUNT_WEIGHT
+    } else {
+        let larger = a.amount.max(b.amount);
+        if larger <= Decimal::ZERO || (a.amount - b.amount).abs() / larger > AMOUNT_TOLERANCE {
+            return None;
+        }
+        AMOUNT_WEIGHT * 3 / 4
+    };
+
+    let window = Duration::days(WINDOW_DAYS).num_seconds();
+    let gap = (a.occurred_at - b.occurred_at).num_seconds().abs();
+    if gap > window {
+        return None;
+    }
+    let time_score = (TIME_WEIGHT * (window - gap) / window) as i32;
+
+    Some(amount_score + time_score + description_score(a.description, b.description))
+}
+
+pub fn is_likely_duplicate(score: i32) -> bool {
+    score >= FLAG_THRESHOLD
+}
+
+/// Token overlap (Jaccard) of the normalized descriptions, scaled to `DESCRIPTION_WEIGHT`.
+/// Two missing descriptions count as half a match; one missing counts as none.
+fn description_score(a: Option<&str>, b: Option<&str>) -> i32 {
+    let (a, b) = match (a, b) {
+        (None, None) => return (DESCRIPTION_WEIGHT / 2) as i32,
+        (Some(a), Some(b)) => (normalize_name(a), normalize_name(b)),
+        _ => return 0,
+    };
+
+    let a: HashSet<&str> = a.split_whitespace().collect();
+    let b: HashSet<&str> = b.split_whitespace().collect();
+    let union = a.union(&b).count();
+    if union == 0 {
+        return (DESCRIPTION_WEIGHT / 2) as i32;
+    }
+
+    (DESCRIPTION_WEIGHT * a.intersection(&b).count() / union) as i32
+}
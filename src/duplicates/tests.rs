@@ -0,0 +1,122 @@
+#![cfg(test)]
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use super::{DuplicateInput, is_likely_duplicate, score};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn noon() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2026, 3, 14, 12, 0, 0).unwrap()
+}
+
+fn make_input<'a>(
+    pocket_id: Uuid,
+    amount: Decimal,
+    occurred_at: DateTime<Utc>,
+    description: Option<&'a str>,
+) -> DuplicateInput<'a> {
+    DuplicateInput {
+        pocket_id,
+        amount,
+        occurred_at,
+        description,
+    }
+}
+
+// ============================================================================
+// Scoring Tests
+// ============================================================================
+
+mod scoring {
+    use super::*;
+
+    #[test]
+    fn identical_entries_score_full_marks() {
+        let pocket = Uuid::new_v4();
+        let a = make_input(pocket, dec!(12.50), noon(), Some("Starbucks"));
+        let b = make_input(pocket, dec!(12.50), noon(), Some("STARBUCKS #123"));
+
+        assert_eq!(score(&a, &b), Some(100));
+    }
+
+    #[test]
+    fn different_pockets_are_never_duplicates() {
+        let a = make_input(Uuid::new_v4(), dec!(12.50), noon(), Some("Starbucks"));
+        let b = make_input(Uuid::new_v4(), dec!(12.50), noon(), Some("Starbucks"));
+
+        assert_eq!(score(&a, &b), None);
+    }
+
+    #[test]
+    fn outside_window_is_not_a_duplicate() {
+        let pocket = Uuid::new_v4();
+        let a = make_input(pocket, dec!(12.50), noon(), Some("Starbucks"));
+        let b = make_input(
+            pocket,
+            dec!(12.50),
+            noon() + Duration::days(4),
+            Some("Starbucks"),
+        );
+
+        assert_eq!(score(&a, &b), None);
+    }
+
+    #[test]
+    fn amount_tolerance_is_one_percent() {
+        let pocket = Uuid::new_v4();
+        let a = make_input(pocket, dec!(100.00), noon(), Some("Hotel"));
+        let close = make_input(pocket, dec!(100.90), noon(), Some("Hotel"));
+        let far = make_input(pocket, dec!(102.00), noon(), Some("Hotel"));
+
+        // Near match loses a quarter of the amount weight
+        assert_eq!(score(&a, &close), Some(90));
+        assert_eq!(score(&a, &far), None);
+    }
+
+    #[test]
+    fn time_gap_reduces_score() {
+        let pocket = Uuid::new_v4();
+        let a = make_input(pocket, dec!(8), noon(), Some("Grab ride"));
+        let b = make_input(
+            pocket,
+            dec!(8),
+            noon() + Duration::hours(36),
+            Some("Grab ride"),
+        );
+
+        // Half the window away keeps half the time weight
+        assert_eq!(score(&a, &b), Some(40 + 15 + 30));
+    }
+}
+
+// ============================================================================
+// Threshold Tests
+// ============================================================================
+
+mod threshold {
+    use super::*;
+
+    #[test]
+    fn same_amount_same_day_different_merchant_is_not_flagged() {
+        let pocket = Uuid::new_v4();
+        let a = make_input(pocket, dec!(5), noon(), Some("Starbucks"));
+        let b = make_input(pocket, dec!(5), noon(), Some("Netflix"));
+
+        assert!(!is_likely_duplicate(score(&a, &b).unwrap()));
+    }
+
+    #[test]
+    fn missing_descriptions_on_both_sides_still_flag() {
+        let pocket = Uuid::new_v4();
+        let a = make_input(pocket, dec!(5), noon(), None);
+        let b = make_input(pocket, dec!(5), noon() + Duration::hours(1), None);
+
+        assert!(is_likely_duplicate(score(&a, &b).unwrap()));
+    }
+}
@@ -0,0 +1,96 @@
+mod tests;
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::schemas::{
+    Envelope, EnvelopeActivityRow, EnvelopeAllocationRow, EnvelopeKind, EnvelopeReport,
+};
+
+/// An envelope is either a pocket or an expense category
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Key {
+    Pocket(Uuid),
+    Category(i32),
+}
+
+/// Build the report for `month` from assignments and activity up to the end of that month.
+/// Income feeds the "to be assigned" pool. Spending draws from the pocket's envelope when the
+/// pocket is one, otherwise from the category's; categories spent from without any assignment
+/// show up with a negative balance.
+pub fn build(
+    month: NaiveDate,
+    allocations: &[EnvelopeAllocationRow],
+    activity: &[EnvelopeActivityRow],
+) -> EnvelopeReport {
+    let mut envelopes: HashMap<Key, Envelope> = HashMap::new();
+    let mut income = Decimal::ZERO;
+    let mut total_income = Decimal::ZERO;
+    let mut total_assigned = Decimal::ZERO;
+
+    for allocation in allocations.iter().filter(|a| a.month <= month) {
+        let key = match (allocation.pocket_id, allocation.category_id) {
+            (Some(pocket_id), _) => Key::Pocket(pocket_id),
+            (None, Some(category_id)) => Key::Category(category_id),
+            (None, None) => continue,
+        };
+        let envelope = envelopes
+            .entry(key)
+            .or_insert_with(|| empty(key, &allocation.name));
+        envelope.available += allocation.amount;
+        if allocation.month == month {
+            envelope.assigned += allocation.amount;
+        }
+        total_assigned += allocation.amount;
+    }
+
+    for row in activity.iter().filter(|r| r.month <= month) {
+        if row.is_income {
+            total_income += row.total;
+            if row.month == month {
+                income += row.total;
+            }
+            continue;
+        }
+        let key = row
+            .pocket_id
+            .map(Key::Pocket)
+            .unwrap_or(Key::Category(row.category_id));
+        let envelope = envelopes
+            .entry(key)
+            .or_insert_with(|| empty(key, &row.name));
+        envelope.available -= row.total;
+        if row.month == month {
+            envelope.spent += row.total;
+        }
+    }
+
+    let mut envelopes: Vec<Envelope> = envelopes.into_values().collect();
+    envelopes.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.name.cmp(&b.name)));
+
+    EnvelopeReport {
+        month,
+        income,
+        to_be_assigned: total_income - total_assigned,
+        envelopes,
+    }
+}
+
+fn empty(key: Key, name: &str) -> Envelope {
+    let (kind, category_id, pocket_id) = match key {
+        Key::Pocket(id) => (EnvelopeKind::Pocket, None, Some(id)),
+        Key::Category(id) => (EnvelopeKind::Category, Some(id), None),
+    };
+    Envelope {
+        kind,
+        category_id,
+        pocket_id,
+        name: name.to_string(),
+        assigned: Decimal::ZERO,
+        spent: Decimal::ZERO,
+        available: Decimal::ZERO,
+    }
+}
@@ -0,0 +1,138 @@
+#![cfg(test)]
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{EnvelopeActivityRow, EnvelopeAllocationRow, EnvelopeKind};
+
+use super::build;
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn month(m: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, m, 1).unwrap()
+}
+
+fn assign_category(m: u32, category_id: i32, name: &str, amount: Decimal) -> EnvelopeAllocationRow {
+    EnvelopeAllocationRow {
+        month: month(m),
+        category_id: Some(category_id),
+        pocket_id: None,
+        name: name.to_string(),
+        amount,
+    }
+}
+
+fn income(m: u32, total: Decimal) -> EnvelopeActivityRow {
+    EnvelopeActivityRow {
+        month: month(m),
+        category_id: 3,
+        pocket_id: None,
+        name: "Salary".to_string(),
+        is_income: true,
+        total,
+    }
+}
+
+fn spend(m: u32, category_id: i32, name: &str, total: Decimal) -> EnvelopeActivityRow {
+    EnvelopeActivityRow {
+        month: month(m),
+        category_id,
+        pocket_id: None,
+        name: name.to_string(),
+        is_income: false,
+        total,
+    }
+}
+
+// ============================================================================
+// Report Tests
+// ============================================================================
+
+mod report {
+    use super::*;
+
+    #[test]
+    fn income_minus_assignments_is_to_be_assigned() {
+        let allocations = vec![
+            assign_category(9, 1, "Food", dec!(600)),
+            assign_category(9, 2, "Transport", dec!(200)),
+        ];
+        let activity = vec![income(9, dec!(3000))];
+
+        let report = build(month(9), &allocations, &activity);
+
+        assert_eq!(report.income, dec!(3000));
+        assert_eq!(report.to_be_assigned, dec!(2200));
+    }
+
+    #[test]
+    fn unspent_money_stays_in_the_envelope() {
+        // Given: 600 assigned in September, 450 spent; 500 more assigned in October
+        let allocations = vec![
+            assign_category(9, 1, "Food", dec!(600)),
+            assign_category(10, 1, "Food", dec!(500)),
+        ];
+        let activity = vec![
+            spend(9, 1, "Food", dec!(450)),
+            spend(10, 1, "Food", dec!(100)),
+        ];
+
+        let report = build(month(10), &allocations, &activity);
+
+        let food = &report.envelopes[0];
+        assert_eq!(food.assigned, dec!(500));
+        assert_eq!(food.spent, dec!(100));
+        assert_eq!(food.available, dec!(550));
+    }
+
+    #[test]
+    fn later_months_are_ignored() {
+        let allocations = vec![assign_category(11, 1, "Food", dec!(600))];
+        let activity = vec![income(9, dec!(1000)), income(11, dec!(1000))];
+
+        let report = build(month(10), &allocations, &activity);
+
+        assert!(report.envelopes.is_empty());
+        assert_eq!(report.income, Decimal::ZERO);
+        assert_eq!(report.to_be_assigned, dec!(1000));
+    }
+
+    #[test]
+    fn spending_without_assignment_is_overspent() {
+        let activity = vec![spend(10, 4, "Shopping", dec!(80))];
+
+        let report = build(month(10), &[], &activity);
+
+        assert_eq!(report.envelopes.len(), 1);
+        assert_eq!(report.envelopes[0].available, dec!(-80));
+    }
+
+    #[test]
+    fn pocket_envelopes_come_first() {
+        let pocket_id = Uuid::new_v4();
+        let allocations = vec![
+            assign_category(10, 1, "Food", dec!(100)),
+            EnvelopeAllocationRow {
+                month: month(10),
+                category_id: None,
+                pocket_id: Some(pocket_id),
+                name: "Japan trip".to_string(),
+                amount: dec!(300),
+            },
+        ];
+        let mut flight = spend(10, 6, "Japan trip", dec!(250));
+        flight.pocket_id = Some(pocket_id);
+
+        let report = build(month(10), &allocations, &[flight]);
+
+        assert_eq!(report.envelopes[0].kind, EnvelopeKind::Pocket);
+        assert_eq!(report.envelopes[0].available, dec!(50));
+        assert_eq!(report.envelopes[1].name, "Food");
+        assert_eq!(report.envelopes[1].available, dec!(100));
+    }
+}
@@ -0,0 +1,129 @@
+mod tests;
+
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+// The JSON structure for errors
+#[derive(Serialize)]
+pub struct ErrorResponse {
+    pub success: bool,
+    pub errors: Vec<ErrorDetail>,
+    /// Per-operation errors of a rejected batch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<Vec<BatchItemError>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ErrorDetail {
+    pub code: String,
+    pub message: String,
+}
+
+/// Why one operation of a batch was rejected
+#[derive(Serialize, Debug)]
+pub struct BatchItemError {
+    pub index: usize,
+    pub op: &'static str,
+    pub error: ErrorDetail,
+}
+
+// The Enum for code logic
+// Every variant ends in `Error`; renaming them all isn't worth the churn, so the lint is allowed
+#[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
+pub enum AppError {
+    DatabaseError(sqlx::Error),
+    ValidationError(String),
+    AuthError(String),
+    NotFoundError(String),
+    ConflictError(String),
+    PayloadTooLargeError(String),
+    InternalServerError(String),
+    /// A batch failed validation; nothing was applied
+    BatchError(Vec<BatchItemError>),
+}
+
+impl AppError {
+    /// Client-facing message (database details are never exposed)
+    pub fn message(&self) -> String {
+        match self {
+            AppError::DatabaseError(_) => "Internal Server Error".to_string(),
+            AppError::ValidationError(msg)
+            | AppError::AuthError(msg)
+            | AppError::NotFoundError(msg)
+            | AppError::ConflictError(msg)
+            | AppError::PayloadTooLargeError(msg)
+            | AppError::InternalServerError(msg) => msg.clone(),
+            AppError::BatchError(items) => {
+                format!("{} batch operations failed validation", items.len())
+            }
+        }
+    }
+
+    /// Error code and message as shown to clients
+    pub fn detail(&self) -> ErrorDetail {
+        ErrorDetail {
+            code: self.code().to_string(),
+            message: self.message(),
+        }
+    }
+
+    fn code(&self) -> &'static str {
+        match self {
+            AppError::DatabaseError(_) => "DB-500",
+            AppError::ValidationError(_) | AppError::BatchError(_) => "VAL-400",
+            AppError::AuthError(_) => "AUTH-401",
+            AppError::NotFoundError(_) => "NOT-404",
+            AppError::ConflictError(_) => "CON-409",
+            AppError::PayloadTooLargeError(_) => "PAY-413",
+            AppError::InternalServerError(_) => "INT-500",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            AppError::DatabaseError(_) | AppError::InternalServerError(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            AppError::ValidationError(_) | AppError::BatchError(_) => StatusCode::BAD_REQUEST,
+            AppError::AuthError(_) => StatusCode::UNAUTHORIZED,
+            AppError::NotFoundError(_) => StatusCode::NOT_FOUND,
+            AppError::ConflictError(_) => StatusCode::CONFLICT,
+            AppError::PayloadTooLargeError(_) => StatusCode::PAYLOAD_TOO_LARGE,
+        }
+    }
+}
+
+// Convert AppError -> HTTP Response
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        if let AppError::DatabaseError(e) = &self {
+            println!("Database Error: {:?}", e);
+        }
+        let status = self.status();
+        let errors = vec![self.detail()];
+        let results = match self {
+            AppError::BatchError(items) => Some(items),
+            _ => None,
+        };
+
+        let body = Json(ErrorResponse {
+            success: false,
+            errors,
+            results,
+        });
+
+        (status, body).into_response()
+    }
+}
+
+// Allow ? operator for SQLx errors
+impl From<sqlx::Error> for AppError {
+    fn from(err: sqlx::Error) -> Self {
+        AppError::DatabaseError(err)
+    }
+}
@@ -0,0 +1,83 @@
+#![cfg(test)]
+
+use axum::{http::StatusCode, response::IntoResponse};
+use serde_json::{Value, json};
+
+use super::{AppError, BatchItemError};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+async fn body_of(error: AppError) -> (StatusCode, Value) {
+    let response = error.into_response();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+// ============================================================================
+// Response Tests
+// ============================================================================
+
+mod response {
+    use super::*;
+
+    #[tokio::test]
+    async fn single_error_has_no_results() {
+        let (status, body) = body_of(AppError::NotFoundError("Pocket not found".to_string())).await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body,
+            json!({
+                "success": false,
+                "errors": [{ "code": "NOT-404", "message": "Pocket not found" }]
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_error_lists_each_failing_operation() {
+        let items = vec![
+            BatchItemError {
+                index: 3,
+                op: "update",
+                error: AppError::NotFoundError("Transaction not found".to_string()).detail(),
+            },
+            BatchItemError {
+                index: 7,
+                op: "delete",
+                error: AppError::ConflictError("Transaction is reconciled".to_string()).detail(),
+            },
+        ];
+
+        let (status, body) = body_of(AppError::BatchError(items)).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            json!({
+                "success": false,
+                "errors": [{
+                    "code": "VAL-400",
+                    "message": "2 batch operations failed validation"
+                }],
+                "results": [
+                    {
+                        "index": 3,
+                        "op": "update",
+                        "error": { "code": "NOT-404", "message": "Transaction not found" }
+                    },
+                    {
+                        "index": 7,
+                        "op": "delete",
+                        "error": { "code": "CON-409", "message": "Transaction is reconciled" }
+                    }
+                ]
+            })
+        );
+    }
+}
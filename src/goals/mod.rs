@@ -0,0 +1,88 @@
+mod tests;
+
+use chrono::{Datelike, Months, NaiveDate};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::schemas::{GoalProgress, GoalRow, MonthlyFlowRow, ProjectionBasis};
+
+/// Number of full calendar months of pocket history used to estimate the saving rate
+pub const HISTORY_MONTHS: u32 = 6;
+
+/// First day of the month containing `date`
+pub fn month_start(date: NaiveDate) -> NaiveDate {
+    date.with_day(1).expect("every month has a first day")
+}
+
+/// First day of the history window used by [`average_monthly_saving`]
+pub fn history_start(today: NaiveDate) -> NaiveDate {
+    month_start(today) - Months::new(HISTORY_MONTHS)
+}
+
+/// Average net saving per month over the full months in `flows` before `today`'s month.
+/// The average starts at the earliest month with activity so a new pocket is not diluted
+/// by months before it existed; quiet months after that count as zero.
+pub fn average_monthly_saving(flows: &[MonthlyFlowRow], today: NaiveDate) -> Decimal {
+    let current = month_start(today);
+    let past: Vec<&MonthlyFlowRow> = flows.iter().filter(|f| f.month < current).collect();
+    let Some(first) = past.iter().map(|f| f.month).min() else {
+        return Decimal::ZERO;
+    };
+
+    let total: Decimal = past.iter().map(|f| f.net).sum();
+    total / Decimal::from(months_between(first, current))
+}
+
+/// Progress, required saving and projected completion of a goal as of `today`.
+/// Projections use the goal's monthly contribution when it has one, otherwise the
+/// pocket's average monthly saving.
+pub fn project(goal: &GoalRow, average_monthly_saving: Decimal, today: NaiveDate) -> GoalProgress {
+    let saved = goal.balance;
+    let remaining = (goal.target_amount - saved).max(Decimal::ZERO);
+    let percent = (saved / goal.target_amount * Decimal::ONE_HUNDRED)
+        .clamp(Decimal::ZERO, Decimal::ONE_HUNDRED);
+
+    // A date in the current month (or already past) leaves no time to spread the saving over
+    let required_monthly_saving = goal.target_date.map(|date| {
+        let months = months_between(today, date);
+        if remaining.is_zero() || months <= 0 {
+            remaining
+        } else {
+            remaining / Decimal::from(months)
+        }
+    });
+
+    let (projection_basis, rate) = match goal.monthly_contribution {
+        Some(contribution) => (ProjectionBasis::Plan, contribution),
+        None => (ProjectionBasis::History, average_monthly_saving),
+    };
+    let projected_completion_date = if remaining.is_zero() {
+        Some(today)
+    } else if rate <= Decimal::ZERO {
+        None
+    } else {
+        (remaining / rate)
+            .ceil()
+            .to_u32()
+            .and_then(|months| today.checked_add_months(Months::new(months)))
+    };
+    let on_track = goal
+        .target_date
+        .map(|date| projected_completion_date.is_some_and(|p| p <= date));
+
+    GoalProgress {
+        saved,
+        remaining,
+        percent,
+        average_monthly_saving,
+        required_monthly_saving,
+        projection_basis,
+        projected_completion_date,
+        on_track,
+    }
+}
+
+/// Calendar months from `from`'s month to `to`'s month, ignoring the day
+fn months_between(from: NaiveDate, to: NaiveDate) -> i64 {
+    i64::from(to.year() - from.year()) * 12 + i64::from(to.month()) - i64::from(from.month())
+}
@@ -0,0 +1,164 @@
+#![cfg(test)]
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{GoalRow, MonthlyFlowRow, ProjectionBasis};
+
+use super::{average_monthly_saving, history_start, project};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_goal(target: Decimal, balance: Decimal) -> GoalRow {
+    GoalRow {
+        id: Uuid::new_v4(),
+        pocket_id: Uuid::new_v4(),
+        pocket_name: "Japan trip".to_string(),
+        pocket_icon: "flight".to_string(),
+        pocket_currency: "SGD".to_string(),
+        name: "Japan trip".to_string(),
+        target_amount: target,
+        target_date: None,
+        monthly_contribution: None,
+        balance,
+        created_at: None,
+    }
+}
+
+fn flow(month: NaiveDate, net: Decimal) -> MonthlyFlowRow {
+    MonthlyFlowRow {
+        pocket_id: Uuid::nil(),
+        month,
+        net,
+    }
+}
+
+// ============================================================================
+// Saving Rate Tests
+// ============================================================================
+
+mod saving_rate {
+    use super::*;
+
+    #[test]
+    fn history_window_covers_full_months() {
+        assert_eq!(history_start(date(2026, 10, 18)), date(2026, 4, 1));
+    }
+
+    #[test]
+    fn current_month_is_ignored() {
+        let flows = vec![
+            flow(date(2026, 9, 1), dec!(300)),
+            flow(date(2026, 10, 1), dec!(5000)),
+        ];
+
+        assert_eq!(
+            average_monthly_saving(&flows, date(2026, 10, 18)),
+            dec!(300)
+        );
+    }
+
+    #[test]
+    fn quiet_months_count_as_zero() {
+        // Given: activity in July and September, nothing in August
+        let flows = vec![
+            flow(date(2026, 7, 1), dec!(400)),
+            flow(date(2026, 9, 1), dec!(200)),
+        ];
+
+        assert_eq!(
+            average_monthly_saving(&flows, date(2026, 10, 18)),
+            dec!(200)
+        );
+    }
+
+    #[test]
+    fn no_history_means_no_saving() {
+        assert_eq!(
+            average_monthly_saving(&[], date(2026, 10, 18)),
+            Decimal::ZERO
+        );
+    }
+}
+
+// ============================================================================
+// Projection Tests
+// ============================================================================
+
+mod projection {
+    use super::*;
+
+    #[test]
+    fn plan_drives_projection_and_required_saving() {
+        // Given: 1000 of 4000 saved, due at the end of April, planning 500 a month
+        let mut goal = make_goal(dec!(4000), dec!(1000));
+        goal.target_date = Some(date(2027, 4, 30));
+        goal.monthly_contribution = Some(dec!(500));
+
+        let progress = project(&goal, dec!(100), date(2026, 10, 18));
+
+        assert_eq!(progress.percent, dec!(25));
+        assert_eq!(progress.remaining, dec!(3000));
+        assert_eq!(progress.required_monthly_saving, Some(dec!(500)));
+        assert_eq!(progress.projection_basis, ProjectionBasis::Plan);
+        assert_eq!(progress.projected_completion_date, Some(date(2027, 4, 18)));
+        assert_eq!(progress.on_track, Some(true));
+    }
+
+    #[test]
+    fn history_is_used_without_a_plan() {
+        let mut goal = make_goal(dec!(1000), dec!(100));
+        goal.target_date = Some(date(2026, 12, 31));
+
+        let progress = project(&goal, dec!(250), date(2026, 10, 18));
+
+        assert_eq!(progress.projection_basis, ProjectionBasis::History);
+        // 900 remaining at 250 a month needs 4 months
+        assert_eq!(progress.projected_completion_date, Some(date(2027, 2, 18)));
+        assert_eq!(progress.required_monthly_saving, Some(dec!(450)));
+        assert_eq!(progress.on_track, Some(false));
+    }
+
+    #[test]
+    fn shrinking_pocket_never_completes() {
+        let goal = make_goal(dec!(1000), dec!(100));
+
+        let progress = project(&goal, dec!(-50), date(2026, 10, 18));
+
+        assert_eq!(progress.projected_completion_date, None);
+        assert_eq!(progress.on_track, None);
+    }
+
+    #[test]
+    fn reached_goal_is_complete() {
+        let mut goal = make_goal(dec!(1000), dec!(1200));
+        goal.target_date = Some(date(2027, 1, 1));
+
+        let progress = project(&goal, Decimal::ZERO, date(2026, 10, 18));
+
+        assert_eq!(progress.percent, dec!(100));
+        assert_eq!(progress.remaining, Decimal::ZERO);
+        assert_eq!(progress.required_monthly_saving, Some(Decimal::ZERO));
+        assert_eq!(progress.projected_completion_date, Some(date(2026, 10, 18)));
+        assert_eq!(progress.on_track, Some(true));
+    }
+
+    #[test]
+    fn past_due_goal_needs_everything_now() {
+        let mut goal = make_goal(dec!(1000), dec!(400));
+        goal.target_date = Some(date(2026, 9, 30));
+
+        let progress = project(&goal, dec!(100), date(2026, 10, 18));
+
+        assert_eq!(progress.required_monthly_saving, Some(dec!(600)));
+        assert_eq!(progress.on_track, Some(false));
+    }
+}
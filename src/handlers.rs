@@ -1,6 +1,8 @@
 use axum::{
     Json,
     extract::{Query, State},
+    http::header,
+    response::{IntoResponse, Response},
 };
 
 use crate::AppState;
@@ -9,12 +11,26 @@ use crate::error::AppError;
 use crate::repository::{PortfolioRepository, SettingsRepository};
 use crate::response::ApiResponse;
 use crate::schemas::{
-    AuthResponse, Category, CreatePocket, CreatePortfolioItem, CreateTransaction, DateRangeParams,
-    FinancialHealth, LoginRequest, PaginatedTransactions, Pocket, PocketId, RefreshTokenRequest,
-    RegisterRequest, SpendingAnalysisResponse, TransactionDetail, TransactionId,
-    TransactionQueryParams, TransferRequest, UpdateCurrency, UpdateInvestment, UpdatePocket,
-    UpdateTransaction, UserProfile,
+    AddPayeeAlias, AmortizationEntry, AssignEnvelope, AuthResponse, BackfillNetWorth,
+    BalanceHistoryParams, BalancePoint, BatchTransactionRequest, BatchTransactionResponse, Bill,
+    BillId, BillOccurrence, Budget, BudgetId, BudgetLine, BudgetReport, BudgetReportParams,
+    CardStatement, CashFlowParams, CashFlowPoint, CategorizationRule, Category, ClearTransactions,
+    CreateBill, CreateBudget, CreateGoal, CreateLiability, CreatePayee, CreatePocket,
+    CreatePortfolioItem, CreateTransaction, CreditCardSettings, DeletePocketParams,
+    DeletePocketResponse, DuplicatePair, EnvelopeParams, EnvelopeReport, FinancialHealth, Goal,
+    GoalId, Liability, LiabilityId, LiabilityPayment, LoginRequest, MergeDuplicateRequest,
+    NetWorthHistoryParams, NetWorthSnapshot, Notification, PaginatedDeletedTransactions,
+    PaginatedTransactions, PaginatedTransfers, PaginationParams, PayCardRequest, Payee, PayeeId,
+    PayeeSpending, Pocket, PocketId, PocketQueryParams, PostTransaction, Reconciliation,
+    ReconciliationDetail, ReconciliationId, RecordLiabilityPayment, RefreshTokenRequest,
+    RegisterRequest, RevertTransactionRequest, RuleDryRunResponse, RuleId, RuleRequest,
+    SpendingAnalysisParams, SpendingAnalysisResponse, StartReconciliation, StatementFormat,
+    StatementParams, TopPayeesParams, TransactionDetail, TransactionId, TransactionQueryParams,
+    TransactionVersion, Transfer, TransferId, TransferRequest, UpcomingBillsParams, UpdateBill,
+    UpdateBudget, UpdateBudgetingMode, UpdateCurrency, UpdateGoal, UpdateInvestment,
+    UpdateLiability, UpdatePayee, UpdatePocket, UpdateTransaction, UpdateTransfer, UserProfile,
 };
+use crate::statements;
 
 // --- Auth Handlers ---
 
@@ -70,6 +86,19 @@ pub async fn create_transaction(
     )))
 }
 
+pub async fn batch_transactions(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<BatchTransactionRequest>,
+) -> Result<Json<ApiResponse<BatchTransactionResponse>>, AppError> {
+    let response = state
+        .transaction_service()
+        .batch_transactions(user_id.0, payload)
+        .await?;
+    let message = format!("{} operations applied", response.results.len());
+    Ok(Json(ApiResponse::success(response, Some(message))))
+}
+
 pub async fn update_transaction(
     State(state): State<AppState>,
     user_id: UserId,
@@ -101,6 +130,53 @@ pub async fn delete_transaction(
     )))
 }
 
+pub async fn unlock_transaction(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .unlock_transaction(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Transaction unlocked".to_string(),
+        None,
+    )))
+}
+
+pub async fn post_transaction(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    payload: Option<Json<PostTransaction>>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    state
+        .transaction_service()
+        .post_transaction(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Transaction posted".to_string(),
+        None,
+    )))
+}
+
+pub async fn void_transaction(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .void_transaction(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Transaction voided".to_string(),
+        None,
+    )))
+}
+
 pub async fn restore_transaction(
     State(state): State<AppState>,
     user_id: UserId,
@@ -116,25 +192,160 @@ pub async fn restore_transaction(
     )))
 }
 
-pub async fn get_transactions(
+pub async fn get_transaction_history(
     State(state): State<AppState>,
     user_id: UserId,
-    Query(params): Query<TransactionQueryParams>,
-) -> Result<Json<ApiResponse<PaginatedTransactions>>, AppError> {
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Vec<TransactionVersion>>>, AppError> {
+    let history = state
+        .transaction_service()
+        .get_transaction_history(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(history, None)))
+}
+
+pub async fn revert_transaction(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<RevertTransactionRequest>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .revert_transaction(path.0, user_id.0, payload.version)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        format!("Transaction reverted to version {}", payload.version),
+        None,
+    )))
+}
+
+pub async fn get_trash(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<PaginationParams>,
+) -> Result<Json<ApiResponse<PaginatedDeletedTransactions>>, AppError> {
     let result = state
         .transaction_service()
-        .get_transactions(
+        .get_trash(
             user_id.0,
-            params.start_date,
-            params.end_date,
-            params.pocket_id,
             params.page,
             params.limit,
+            state.trash_retention_days,
         )
         .await?;
     Ok(Json(ApiResponse::success(result, None)))
 }
 
+pub async fn empty_trash(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let purged = state.transaction_service().empty_trash(user_id.0).await?;
+    Ok(Json(ApiResponse::success(
+        format!("Permanently deleted {} transactions", purged),
+        None,
+    )))
+}
+
+pub async fn recompute_exchange_rate(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<TransactionDetail>>, AppError> {
+    let transaction = state
+        .transaction_service()
+        .recompute_exchange_rate(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        transaction,
+        Some("Exchange rate recomputed".to_string()),
+    )))
+}
+
+pub async fn recompute_exchange_rates(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let updated = state
+        .transaction_service()
+        .recompute_exchange_rates(user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        format!("Recomputed exchange rates for {} transactions", updated),
+        None,
+    )))
+}
+
+pub async fn get_duplicates(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<DuplicatePair>>>, AppError> {
+    let pairs = state
+        .transaction_service()
+        .get_duplicates(user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(pairs, None)))
+}
+
+pub async fn merge_duplicate(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    payload: Option<Json<MergeDuplicateRequest>>,
+) -> Result<Json<ApiResponse<TransactionId>>, AppError> {
+    let id = state
+        .transaction_service()
+        .merge_duplicate(path.0, user_id.0, payload.map(|p| p.0).unwrap_or_default())
+        .await?;
+    Ok(Json(ApiResponse::success(
+        TransactionId { id },
+        Some("Duplicates merged".to_string()),
+    )))
+}
+
+pub async fn dismiss_duplicate(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .dismiss_duplicate(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Duplicate dismissed".to_string(),
+        None,
+    )))
+}
+
+pub async fn delete_transaction_permanently(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .delete_transaction_permanently(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Transaction permanently deleted".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_transactions(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<TransactionQueryParams>,
+) -> Result<Json<ApiResponse<PaginatedTransactions>>, AppError> {
+    let result = state
+        .transaction_service()
+        .get_transactions(user_id.0, params)
+        .await?;
+    Ok(Json(ApiResponse::success(result, None)))
+}
+
 pub async fn get_transaction(
     State(state): State<AppState>,
     user_id: UserId,
@@ -150,15 +361,55 @@ pub async fn get_transaction(
 pub async fn get_spending_analysis(
     State(state): State<AppState>,
     user_id: UserId,
-    Query(params): Query<DateRangeParams>,
+    Query(params): Query<SpendingAnalysisParams>,
 ) -> Result<Json<ApiResponse<SpendingAnalysisResponse>>, AppError> {
     let rows = state
         .transaction_service()
-        .get_spending_analysis(user_id.0, params.start_date, params.end_date)
+        .get_spending_analysis(user_id.0, params)
         .await?;
     Ok(Json(ApiResponse::success(rows, None)))
 }
 
+pub async fn get_cash_flow(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<CashFlowParams>,
+) -> Result<Json<ApiResponse<Vec<CashFlowPoint>>>, AppError> {
+    let points = state
+        .transaction_service()
+        .get_cash_flow(user_id.0, params)
+        .await?;
+    Ok(Json(ApiResponse::success(points, None)))
+}
+
+pub async fn get_net_worth_history(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<NetWorthHistoryParams>,
+) -> Result<Json<ApiResponse<Vec<NetWorthSnapshot>>>, AppError> {
+    let history = state
+        .net_worth_service()
+        .get_history(user_id.0, params)
+        .await?;
+    Ok(Json(ApiResponse::success(history, None)))
+}
+
+pub async fn backfill_net_worth(
+    State(state): State<AppState>,
+    user_id: UserId,
+    payload: Option<Json<BackfillNetWorth>>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let payload = payload.map(|Json(p)| p).unwrap_or_default();
+    let written = state
+        .net_worth_service()
+        .backfill(user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        format!("Backfilled {} net-worth snapshots", written),
+        None,
+    )))
+}
+
 pub async fn get_categories(
     State(state): State<AppState>,
     _user_id: UserId,
@@ -302,8 +553,12 @@ pub async fn create_pocket(
 pub async fn get_pockets(
     State(state): State<AppState>,
     user_id: UserId,
+    Query(params): Query<PocketQueryParams>,
 ) -> Result<Json<ApiResponse<Vec<Pocket>>>, AppError> {
-    let pockets = state.pocket_service().get_pockets(user_id.0).await?;
+    let pockets = state
+        .pocket_service()
+        .get_pockets(user_id.0, params.include_archived)
+        .await?;
     Ok(Json(ApiResponse::success(pockets, None)))
 }
 
@@ -316,6 +571,49 @@ pub async fn get_pocket(
     Ok(Json(ApiResponse::success(pocket, None)))
 }
 
+/// JSON by default; `format=csv` returns the statement as a CSV download
+pub async fn get_pocket_statement(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Query(params): Query<StatementParams>,
+) -> Result<Response, AppError> {
+    let statement = state
+        .pocket_service()
+        .get_statement(path.0, user_id.0, params.start_date, params.end_date)
+        .await?;
+
+    if params.format == StatementFormat::Csv {
+        let filename = format!(
+            "attachment; filename=\"statement-{}-{}.csv\"",
+            params.start_date.format("%Y-%m-%d"),
+            params.end_date.format("%Y-%m-%d")
+        );
+        return Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (header::CONTENT_DISPOSITION, filename),
+            ],
+            statements::to_csv(&statement),
+        )
+            .into_response());
+    }
+    Ok(Json(ApiResponse::success(statement, None)).into_response())
+}
+
+pub async fn get_pocket_balance_history(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Query(params): Query<BalanceHistoryParams>,
+) -> Result<Json<ApiResponse<Vec<BalancePoint>>>, AppError> {
+    let points = state
+        .pocket_service()
+        .get_balance_history(path.0, user_id.0, params)
+        .await?;
+    Ok(Json(ApiResponse::success(points, None)))
+}
+
 pub async fn update_pocket(
     State(state): State<AppState>,
     user_id: UserId,
@@ -336,28 +634,810 @@ pub async fn delete_pocket(
     State(state): State<AppState>,
     user_id: UserId,
     path: axum::extract::Path<uuid::Uuid>,
+    Query(params): Query<DeletePocketParams>,
+) -> Result<Json<ApiResponse<DeletePocketResponse>>, AppError> {
+    let removed = state
+        .pocket_service()
+        .delete_pocket(path.0, user_id.0, params.reassign_to)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        removed,
+        Some("Pocket deleted".to_string()),
+    )))
+}
+
+pub async fn archive_pocket(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
 ) -> Result<Json<ApiResponse<String>>, AppError> {
     state
         .pocket_service()
-        .delete_pocket(path.0, user_id.0)
+        .archive_pocket(path.0, user_id.0, true)
         .await?;
     Ok(Json(ApiResponse::success(
-        "Pocket deleted".to_string(),
+        "Pocket archived".to_string(),
         None,
     )))
 }
 
-pub async fn transfer_funds(
+pub async fn unarchive_pocket(
     State(state): State<AppState>,
     user_id: UserId,
-    Json(payload): Json<TransferRequest>,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .pocket_service()
+        .archive_pocket(path.0, user_id.0, false)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Pocket unarchived".to_string(),
+        None,
+    )))
+}
+
+pub async fn update_credit_card(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<CreditCardSettings>,
 ) -> Result<Json<ApiResponse<String>>, AppError> {
     state
+        .pocket_service()
+        .update_credit_card(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Credit card settings updated".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_card_statement(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<CardStatement>>, AppError> {
+    let statement = state
+        .pocket_service()
+        .get_card_statement(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(statement, None)))
+}
+
+pub async fn pay_card(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<PayCardRequest>,
+) -> Result<Json<ApiResponse<TransferId>>, AppError> {
+    let transfer = state
+        .pocket_service()
+        .card_payment(path.0, user_id.0, payload)
+        .await?;
+    let id = state
         .transaction_service()
-        .transfer_funds(user_id.0, payload)
+        .transfer_funds(user_id.0, transfer)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        TransferId { id },
+        Some("Card payment recorded".to_string()),
+    )))
+}
+
+pub async fn start_reconciliation(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<StartReconciliation>,
+) -> Result<Json<ApiResponse<ReconciliationId>>, AppError> {
+    let id = state
+        .reconciliation_service()
+        .start(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        ReconciliationId { id },
+        Some("Reconciliation started".to_string()),
+    )))
+}
+
+pub async fn get_reconciliations(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Vec<Reconciliation>>>, AppError> {
+    let reconciliations = state
+        .reconciliation_service()
+        .get_reconciliations(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(reconciliations, None)))
+}
+
+pub async fn get_reconciliation(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<ReconciliationDetail>>, AppError> {
+    let reconciliation = state
+        .reconciliation_service()
+        .get_reconciliation(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(reconciliation, None)))
+}
+
+pub async fn clear_transactions(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<ClearTransactions>,
+) -> Result<Json<ApiResponse<Reconciliation>>, AppError> {
+    let reconciliation = state
+        .reconciliation_service()
+        .set_cleared(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(reconciliation, None)))
+}
+
+pub async fn complete_reconciliation(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Reconciliation>>, AppError> {
+    let reconciliation = state
+        .reconciliation_service()
+        .complete(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        reconciliation,
+        Some("Reconciliation completed".to_string()),
+    )))
+}
+
+pub async fn delete_reconciliation(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .reconciliation_service()
+        .delete(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Reconciliation deleted".to_string(),
+        None,
+    )))
+}
+
+pub async fn transfer_funds(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<TransferRequest>,
+) -> Result<Json<ApiResponse<TransferId>>, AppError> {
+    let id = state
+        .transaction_service()
+        .transfer_funds(user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        TransferId { id },
+        Some("Transfer successful".to_string()),
+    )))
+}
+
+pub async fn get_transfers(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<PaginationParams>,
+) -> Result<Json<ApiResponse<PaginatedTransfers>>, AppError> {
+    let result = state
+        .transaction_service()
+        .get_transfers(user_id.0, params.page, params.limit)
+        .await?;
+    Ok(Json(ApiResponse::success(result, None)))
+}
+
+pub async fn get_transfer(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Transfer>>, AppError> {
+    let transfer = state
+        .transaction_service()
+        .get_transfer(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(transfer, None)))
+}
+
+pub async fn update_transfer(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<UpdateTransfer>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .update_transfer(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Transfer updated".to_string(),
+        None,
+    )))
+}
+
+pub async fn delete_transfer(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .transaction_service()
+        .delete_transfer(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Transfer deleted".to_string(),
+        None,
+    )))
+}
+
+// --- Categorization Rule Handlers ---
+
+pub async fn get_rules(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<CategorizationRule>>>, AppError> {
+    let rules = state.rule_service().get_rules(user_id.0).await?;
+    Ok(Json(ApiResponse::success(rules, None)))
+}
+
+pub async fn create_rule(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<RuleRequest>,
+) -> Result<Json<ApiResponse<RuleId>>, AppError> {
+    let id = state.rule_service().create_rule(user_id.0, payload).await?;
+    Ok(Json(ApiResponse::success(
+        RuleId { id },
+        Some("Rule created".to_string()),
+    )))
+}
+
+pub async fn update_rule(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<RuleRequest>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .rule_service()
+        .update_rule(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success("Rule updated".to_string(), None)))
+}
+
+pub async fn delete_rule(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state.rule_service().delete_rule(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success("Rule deleted".to_string(), None)))
+}
+
+pub async fn dry_run_rule(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<RuleDryRunResponse>>, AppError> {
+    let result = state.rule_service().dry_run(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success(result, None)))
+}
+
+pub async fn apply_rule(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let (updated, pocket_skipped) = state
+        .rule_service()
+        .apply_to_history(path.0, user_id.0)
+        .await?;
+    let mut message = format!("Rule applied to {} transactions", updated);
+    if pocket_skipped > 0 {
+        message.push_str(&format!(
+            "; {} kept in their pocket because of a currency mismatch",
+            pocket_skipped
+        ));
+    }
+    Ok(Json(ApiResponse::success(message, None)))
+}
+
+// --- Payee Handlers ---
+
+pub async fn create_payee(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<CreatePayee>,
+) -> Result<Json<ApiResponse<PayeeId>>, AppError> {
+    let id = state
+        .payee_service()
+        .create_payee(user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        PayeeId { id },
+        Some("Payee created".to_string()),
+    )))
+}
+
+pub async fn get_payees(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<Payee>>>, AppError> {
+    let payees = state.payee_service().get_payees(user_id.0).await?;
+    Ok(Json(ApiResponse::success(payees, None)))
+}
+
+pub async fn get_payee(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Payee>>, AppError> {
+    let payee = state.payee_service().get_payee(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success(payee, None)))
+}
+
+pub async fn update_payee(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<UpdatePayee>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .payee_service()
+        .update_payee(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Payee updated".to_string(),
+        None,
+    )))
+}
+
+pub async fn delete_payee(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .payee_service()
+        .delete_payee(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Payee deleted".to_string(),
+        None,
+    )))
+}
+
+pub async fn add_payee_alias(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<AddPayeeAlias>,
+) -> Result<Json<ApiResponse<PayeeId>>, AppError> {
+    let id = state
+        .payee_service()
+        .add_alias(path.0, user_id.0, &payload.alias)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        PayeeId { id },
+        Some("Alias added".to_string()),
+    )))
+}
+
+pub async fn delete_payee_alias(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<(uuid::Uuid, uuid::Uuid)>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let (payee_id, alias_id) = path.0;
+    state
+        .payee_service()
+        .delete_alias(payee_id, alias_id, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Alias deleted".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_top_payees(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<TopPayeesParams>,
+) -> Result<Json<ApiResponse<Vec<PayeeSpending>>>, AppError> {
+    let payees = state
+        .payee_service()
+        .get_top_payees(user_id.0, params.start_date, params.end_date, params.limit)
+        .await?;
+    Ok(Json(ApiResponse::success(payees, None)))
+}
+
+pub async fn create_goal(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<CreateGoal>,
+) -> Result<Json<ApiResponse<GoalId>>, AppError> {
+    let id = state.goal_service().create_goal(user_id.0, payload).await?;
+    Ok(Json(ApiResponse::success(
+        GoalId { id },
+        Some("Goal created".to_string()),
+    )))
+}
+
+pub async fn get_goals(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<Goal>>>, AppError> {
+    let goals = state.goal_service().get_goals(user_id.0).await?;
+    Ok(Json(ApiResponse::success(goals, None)))
+}
+
+pub async fn get_goal(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Goal>>, AppError> {
+    let goal = state.goal_service().get_goal(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success(goal, None)))
+}
+
+pub async fn update_goal(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<UpdateGoal>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .goal_service()
+        .update_goal(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success("Goal updated".to_string(), None)))
+}
+
+pub async fn delete_goal(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state.goal_service().delete_goal(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success("Goal deleted".to_string(), None)))
+}
+
+pub async fn create_budget(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<CreateBudget>,
+) -> Result<Json<ApiResponse<BudgetId>>, AppError> {
+    let id = state
+        .budget_service()
+        .create_budget(user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        BudgetId { id },
+        Some("Budget created".to_string()),
+    )))
+}
+
+pub async fn get_budgets(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<Budget>>>, AppError> {
+    let budgets = state.budget_service().get_budgets(user_id.0).await?;
+    Ok(Json(ApiResponse::success(budgets, None)))
+}
+
+pub async fn get_budget(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Budget>>, AppError> {
+    let budget = state.budget_service().get_budget(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success(budget, None)))
+}
+
+pub async fn update_budget(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<UpdateBudget>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .budget_service()
+        .update_budget(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Budget updated".to_string(),
+        None,
+    )))
+}
+
+pub async fn delete_budget(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .budget_service()
+        .delete_budget(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Budget deleted".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_budget_report(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<BudgetReportParams>,
+) -> Result<Json<ApiResponse<BudgetReport>>, AppError> {
+    let report = state
+        .budget_service()
+        .get_report(user_id.0, params.month)
+        .await?;
+    Ok(Json(ApiResponse::success(report, None)))
+}
+
+pub async fn get_budget_alerts(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<BudgetLine>>>, AppError> {
+    let alerts = state.budget_service().get_alerts(user_id.0).await?;
+    Ok(Json(ApiResponse::success(alerts, None)))
+}
+
+pub async fn update_budgeting_mode(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<UpdateBudgetingMode>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .envelope_service()
+        .set_mode(user_id.0, payload.zero_based)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Budgeting mode updated".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_envelopes(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<EnvelopeParams>,
+) -> Result<Json<ApiResponse<EnvelopeReport>>, AppError> {
+    let report = state
+        .envelope_service()
+        .get_report(user_id.0, params.month)
+        .await?;
+    Ok(Json(ApiResponse::success(report, None)))
+}
+
+pub async fn assign_envelope(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<AssignEnvelope>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state.envelope_service().assign(user_id.0, payload).await?;
+    Ok(Json(ApiResponse::success(
+        "Envelope assigned".to_string(),
+        None,
+    )))
+}
+
+pub async fn create_bill(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<CreateBill>,
+) -> Result<Json<ApiResponse<BillId>>, AppError> {
+    let id = state.bill_service().create_bill(user_id.0, payload).await?;
+    Ok(Json(ApiResponse::success(
+        BillId { id },
+        Some("Bill created".to_string()),
+    )))
+}
+
+pub async fn get_bills(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<Bill>>>, AppError> {
+    let bills = state.bill_service().get_bills(user_id.0).await?;
+    Ok(Json(ApiResponse::success(bills, None)))
+}
+
+pub async fn get_bill(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Bill>>, AppError> {
+    let bill = state.bill_service().get_bill(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success(bill, None)))
+}
+
+pub async fn update_bill(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<UpdateBill>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .bill_service()
+        .update_bill(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success("Bill updated".to_string(), None)))
+}
+
+pub async fn delete_bill(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state.bill_service().delete_bill(path.0, user_id.0).await?;
+    Ok(Json(ApiResponse::success("Bill deleted".to_string(), None)))
+}
+
+pub async fn get_upcoming_bills(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Query(params): Query<UpcomingBillsParams>,
+) -> Result<Json<ApiResponse<Vec<BillOccurrence>>>, AppError> {
+    let upcoming = state
+        .bill_service()
+        .get_upcoming(user_id.0, params.days)
+        .await?;
+    Ok(Json(ApiResponse::success(upcoming, None)))
+}
+
+pub async fn create_liability(
+    State(state): State<AppState>,
+    user_id: UserId,
+    Json(payload): Json<CreateLiability>,
+) -> Result<Json<ApiResponse<LiabilityId>>, AppError> {
+    let id = state
+        .liability_service()
+        .create_liability(user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        LiabilityId { id },
+        Some("Liability created".to_string()),
+    )))
+}
+
+pub async fn get_liabilities(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<Liability>>>, AppError> {
+    let liabilities = state.liability_service().get_liabilities(user_id.0).await?;
+    Ok(Json(ApiResponse::success(liabilities, None)))
+}
+
+pub async fn get_liability(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Liability>>, AppError> {
+    let liability = state
+        .liability_service()
+        .get_liability(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(liability, None)))
+}
+
+pub async fn update_liability(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<UpdateLiability>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .liability_service()
+        .update_liability(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Liability updated".to_string(),
+        None,
+    )))
+}
+
+pub async fn delete_liability(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .liability_service()
+        .delete_liability(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Liability deleted".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_liability_schedule(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Vec<AmortizationEntry>>>, AppError> {
+    let schedule = state
+        .liability_service()
+        .get_schedule(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(schedule, None)))
+}
+
+pub async fn get_liability_payments(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<Vec<LiabilityPayment>>>, AppError> {
+    let payments = state
+        .liability_service()
+        .get_payments(path.0, user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(payments, None)))
+}
+
+pub async fn record_liability_payment(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+    Json(payload): Json<RecordLiabilityPayment>,
+) -> Result<Json<ApiResponse<LiabilityPayment>>, AppError> {
+    let payment = state
+        .liability_service()
+        .record_payment(path.0, user_id.0, payload)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        payment,
+        Some("Repayment recorded".to_string()),
+    )))
+}
+
+pub async fn remove_liability_payment(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<(uuid::Uuid, uuid::Uuid)>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let (id, transaction_id) = path.0;
+    state
+        .liability_service()
+        .remove_payment(id, user_id.0, transaction_id)
+        .await?;
+    Ok(Json(ApiResponse::success(
+        "Repayment removed".to_string(),
+        None,
+    )))
+}
+
+pub async fn get_notifications(
+    State(state): State<AppState>,
+    user_id: UserId,
+) -> Result<Json<ApiResponse<Vec<Notification>>>, AppError> {
+    let notifications = state
+        .notification_service()
+        .get_notifications(user_id.0)
+        .await?;
+    Ok(Json(ApiResponse::success(notifications, None)))
+}
+
+pub async fn mark_notification_read(
+    State(state): State<AppState>,
+    user_id: UserId,
+    path: axum::extract::Path<uuid::Uuid>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    state
+        .notification_service()
+        .mark_read(path.0, user_id.0)
         .await?;
     Ok(Json(ApiResponse::success(
-        "Transfer successful".to_string(),
+        "Notification marked as read".to_string(),
         None,
     )))
 }
@@ -0,0 +1,174 @@
+use axum::{
+    body::Body,
+    extract::{FromRequestParts, Request, State},
+    http::{HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+use http_body_util::BodyExt;
+use sha2::{Digest, Sha256};
+
+use crate::AppState;
+use crate::auth::UserId;
+use crate::error::AppError;
+use crate::repository::IdempotencyRepository;
+
+pub const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+const REPLAYED_HEADER: &str = "idempotent-replayed";
+const MAX_KEY_LENGTH: usize = 255;
+/// Largest request body buffered for hashing, matching axum's default body limit
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+/// How long a claimed key waits for its response before another request may take it over,
+/// so a crash mid-request doesn't block the key until it expires
+const PROCESSING_LEASE_SECONDS: i64 = 120;
+
+/// Replays the stored response when a mutating request is retried with the same
+/// `Idempotency-Key`. Keys are scoped to the authenticated user; requests without a key or
+/// without a valid token pass straight through.
+pub async fn idempotency(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    if !matches!(
+        *request.method(),
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    ) {
+        return next.run(request).await;
+    }
+
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_HEADER)
+        .map(|v| v.to_str().map(|s| s.trim().to_string()))
+    else {
+        return next.run(request).await;
+    };
+
+    let result = match key {
+        Ok(key) if !key.is_empty() && key.len() <= MAX_KEY_LENGTH => {
+            handle(state, key, request, next).await
+        }
+        _ => Err(AppError::ValidationError(format!(
+            "Idempotency-Key must be 1-{} visible ASCII characters",
+            MAX_KEY_LENGTH
+        ))),
+    };
+    result.unwrap_or_else(IntoResponse::into_response)
+}
+
+async fn handle(
+    state: AppState,
+    key: String,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let (mut parts, body) = request.into_parts();
+    let Ok(UserId(user_id)) = UserId::from_request_parts(&mut parts, &state).await else {
+        // Let the handler reject it (or serve it, for the public auth endpoints)
+        return Ok(next.run(Request::from_parts(parts, body)).await);
+    };
+
+    let bytes = axum::body::to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|_| {
+            AppError::PayloadTooLargeError(format!(
+                "Request body must not exceed {} bytes",
+                MAX_BODY_BYTES
+            ))
+        })?;
+    let request_hash = hash_request(
+        &parts.method,
+        parts.uri.path_and_query().map_or("", |p| p.as_str()),
+        &bytes,
+    );
+
+    let repo = IdempotencyRepository::new(state.db.clone());
+    let now = Utc::now();
+    let expires_at = now + Duration::hours(state.idempotency_ttl_hours);
+    let lease_expired_before = now - Duration::seconds(PROCESSING_LEASE_SECONDS);
+
+    if !repo
+        .claim(
+            user_id,
+            &key,
+            &request_hash,
+            expires_at,
+            lease_expired_before,
+        )
+        .await?
+    {
+        let record = repo
+            .find(user_id, &key)
+            .await?
+            .ok_or(AppError::ConflictError(
+                "A request with this Idempotency-Key is still being processed".to_string(),
+            ))?;
+        if record.request_hash != request_hash {
+            return Err(AppError::ValidationError(
+                "Idempotency-Key was already used for a different request".to_string(),
+            ));
+        }
+        return match (record.response_status, record.response_body) {
+            (Some(status), Some(body)) => Ok(replay(status, body)),
+            _ => Err(AppError::ConflictError(
+                "A request with this Idempotency-Key is still being processed".to_string(),
+            )),
+        };
+    }
+
+    let response = next
+        .run(Request::from_parts(parts, Body::from(bytes)))
+        .await;
+    let (parts, body) = response.into_parts();
+    let bytes = match body.collect().await {
+        Ok(collected) => collected.to_bytes(),
+        Err(_) => {
+            release(&repo, user_id, &key).await;
+            return Err(AppError::InternalServerError(
+                "Failed to read response body".to_string(),
+            ));
+        }
+    };
+
+    // Server errors are not stored so the client can retry with the same key
+    if parts.status.is_server_error() {
+        release(&repo, user_id, &key).await;
+    } else if let Err(e) = repo
+        .complete(user_id, &key, parts.status.as_u16() as i32, &bytes)
+        .await
+    {
+        tracing::error!("Failed to store idempotent response: {:?}", e);
+    }
+
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+async fn release(repo: &IdempotencyRepository, user_id: uuid::Uuid, key: &str) {
+    if let Err(e) = repo.release(user_id, key).await {
+        tracing::error!("Failed to release idempotency key: {:?}", e);
+    }
+}
+
+fn replay(status: i32, body: Vec<u8>) -> Response {
+    let status = u16::try_from(status)
+        .ok()
+        .and_then(|s| StatusCode::from_u16(s).ok())
+        .unwrap_or(StatusCode::OK);
+    let mut response = (status, Body::from(body)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
+
+/// Fingerprint of the method, path and body so a key can't be reused for another request
+fn hash_request(method: &Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(path.as_bytes());
+    hasher.update(b"\n");
+    hasher.update(body);
+    hex::encode(hasher.finalize())
+}
@@ -1,4 +1,5 @@
 use crate::error::AppError;
+use chrono::NaiveDate;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::FromPrimitive; // Required for from_f64
 use serde::Deserialize;
@@ -209,7 +210,7 @@ pub async fn fetch_coingecko_icon(
 
     let resp = client.get(&url).send().await.map_err(|e| {
         tracing::warn!("CoinGecko icon API connection failed for {}: {}", id, e);
-        return AppError::ValidationError(format!("CoinGecko icon API connection failed: {}", e));
+        AppError::ValidationError(format!("CoinGecko icon API connection failed: {}", e))
     })?;
 
     if !resp.status().is_success() {
@@ -243,12 +244,84 @@ pub async fn fetch_exchange_rate(
     client: &reqwest::Client,
     from: &str,
     to: &str,
+) -> Result<Decimal, AppError> {
+    fetch_frankfurter_rate(client, "latest", from, to).await
+}
+
+/// Rate published for `date` (Frankfurter falls back to the previous working day)
+pub async fn fetch_exchange_rate_on(
+    client: &reqwest::Client,
+    from: &str,
+    to: &str,
+    date: NaiveDate,
+) -> Result<Decimal, AppError> {
+    fetch_frankfurter_rate(client, &date.format("%Y-%m-%d").to_string(), from, to).await
+}
+
+#[derive(Deserialize, Debug)]
+struct FrankfurterSeriesResponse {
+    rates: std::collections::BTreeMap<NaiveDate, std::collections::HashMap<String, f64>>,
+}
+
+/// Rates published for each working day from `start` to `end`, in one request. Frankfurter
+/// starts the series at the last working day on or before `start`.
+pub async fn fetch_exchange_rates_between(
+    client: &reqwest::Client,
+    from: &str,
+    to: &str,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<Vec<(NaiveDate, Decimal)>, AppError> {
+    let url = format!(
+        "https://api.frankfurter.app/{}..{}?from={}&to={}",
+        start.format("%Y-%m-%d"),
+        end.format("%Y-%m-%d"),
+        from,
+        to
+    );
+
+    let resp = client.get(&url).send().await.map_err(|e| {
+        AppError::ValidationError(format!("Frankfurter API connection failed: {}", e))
+    })?;
+
+    if !resp.status().is_success() {
+        return Err(AppError::ValidationError(format!(
+            "Frankfurter API returned error: {}",
+            resp.status()
+        )));
+    }
+
+    let data: FrankfurterSeriesResponse = resp.json().await.map_err(|e| {
+        AppError::ValidationError(format!("Failed to parse Frankfurter response: {}", e))
+    })?;
+
+    data.rates
+        .into_iter()
+        .filter_map(|(day, rates)| rates.get(to).map(|rate| (day, *rate)))
+        .map(|(day, rate)| {
+            Decimal::from_f64(rate)
+                .map(|rate| (day, rate))
+                .ok_or_else(|| {
+                    AppError::ValidationError("Failed to parse exchange rate".to_string())
+                })
+        })
+        .collect()
+}
+
+async fn fetch_frankfurter_rate(
+    client: &reqwest::Client,
+    endpoint: &str,
+    from: &str,
+    to: &str,
 ) -> Result<Decimal, AppError> {
     if from == to {
         return Ok(Decimal::new(1, 0));
     }
 
-    let url = format!("https://api.frankfurter.app/latest?from={}&to={}", from, to);
+    let url = format!(
+        "https://api.frankfurter.app/{}?from={}&to={}",
+        endpoint, from, to
+    );
 
     let resp = client.get(&url).send().await.map_err(|e| {
         AppError::ValidationError(format!("Frankfurter API connection failed: {}", e))
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use crate::AppState;
+use crate::repository::IdempotencyRepository;
+
+/// How often expired transactions are purged from the trash
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically hard-delete transactions that have been in the trash longer than the
+/// configured retention period
+pub fn spawn_trash_purge(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TRASH_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match state
+                .transaction_service()
+                .purge_expired_trash(state.trash_retention_days)
+                .await
+            {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!("Purged {} expired transactions from trash", purged),
+                Err(e) => tracing::error!("Trash purge failed: {:?}", e),
+            }
+        }
+    });
+}
+
+/// How often expired idempotency keys are removed
+const IDEMPOTENCY_CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically delete idempotency keys whose replay window has passed
+pub fn spawn_idempotency_cleanup(state: AppState) {
+    tokio::spawn(async move {
+        let repo = IdempotencyRepository::new(state.db.clone());
+        let mut interval = tokio::time::interval(IDEMPOTENCY_CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match repo.purge_expired().await {
+                Ok(0) => {}
+                Ok(purged) => tracing::info!("Removed {} expired idempotency keys", purged),
+                Err(e) => tracing::error!("Idempotency key cleanup failed: {:?}", e),
+            }
+        }
+    });
+}
+
+/// How often unpaid bills are checked for missed due dates
+const BILL_REMINDER_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically notify users about bill occurrences that passed their due date unpaid
+pub fn spawn_bill_reminders(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(BILL_REMINDER_INTERVAL);
+        loop {
+            interval.tick().await;
+            match state
+                .bill_service()
+                .send_overdue_reminders(&state.notifier())
+                .await
+            {
+                Ok(0) => {}
+                Ok(sent) => tracing::info!("Sent {} overdue bill reminders", sent),
+                Err(e) => tracing::error!("Bill reminders failed: {:?}", e),
+            }
+        }
+    });
+}
+
+/// How often users are checked for a missing net-worth snapshot
+const NET_WORTH_SNAPSHOT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Periodically record each user's net worth at the end of the previous day, refreshing
+/// investment prices first
+pub fn spawn_net_worth_snapshots(state: AppState) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(NET_WORTH_SNAPSHOT_INTERVAL);
+        loop {
+            interval.tick().await;
+            let day = chrono::Utc::now().date_naive() - chrono::Duration::days(1);
+            let service = state.net_worth_service();
+            let users = match service.users_without_snapshot(day).await {
+                Ok(users) => users,
+                Err(e) => {
+                    tracing::error!("Net-worth snapshots failed: {:?}", e);
+                    continue;
+                }
+            };
+            let mut recorded = 0;
+            for user_id in users {
+                if let Err(e) = state.finance_service().refresh_portfolio(user_id).await {
+                    tracing::error!("Price refresh for {} failed: {:?}", user_id, e);
+                }
+                match service.record_snapshot(user_id, day).await {
+                    Ok(written) => recorded += written,
+                    Err(e) => tracing::error!("Net-worth snapshot for {} failed: {:?}", user_id, e),
+                }
+            }
+            if recorded > 0 {
+                tracing::info!("Recorded {} net-worth snapshots for {}", recorded, day);
+            }
+        }
+    });
+}
@@ -1,12 +1,29 @@
+mod amortization;
 mod auth;
+mod bills;
+mod budgets;
+mod cash_flow;
+mod comparison;
+mod credit_cards;
+mod duplicates;
+mod envelopes;
 mod error;
+mod goals;
 mod handlers;
+mod idempotency;
 mod investments;
+mod jobs;
+mod net_worth;
+mod notifications;
+mod payees;
+mod pockets;
 mod portfolio;
 mod repository;
 mod response;
+mod rules;
 mod schemas;
 mod services;
+mod statements;
 
 use axum::{
     Router,
@@ -67,6 +84,8 @@ pub struct AppState {
     pub price_cache: moka::future::Cache<String, rust_decimal::Decimal>,
     pub exchange_rate_cache: moka::future::Cache<String, rust_decimal::Decimal>,
     pub http_client: reqwest::Client,
+    pub trash_retention_days: i64,
+    pub idempotency_ttl_hours: i64,
 }
 
 impl AppState {
@@ -84,6 +103,20 @@ impl AppState {
             repository::TransactionRepository::new(self.db.clone()),
             repository::PocketRepository::new(self.db.clone()),
             repository::SettingsRepository::new(self.db.clone()),
+            services::TransactionEnrichment {
+                rule_repo: repository::RuleRepository::new(self.db.clone()),
+                payee_repo: repository::PayeeRepository::new(self.db.clone()),
+                duplicate_repo: repository::DuplicateRepository::new(self.db.clone()),
+            },
+            self.exchange_rate_service(),
+            self.bill_service(),
+        )
+    }
+
+    pub fn exchange_rate_service(&self) -> services::ExchangeRateService {
+        services::ExchangeRateService::new(
+            repository::ExchangeRateRepository::new(self.db.clone()),
+            self.exchange_rate_cache.clone(),
             self.http_client.clone(),
         )
     }
@@ -93,6 +126,7 @@ impl AppState {
             repository::PortfolioRepository::new(self.db.clone()),
             repository::TransactionRepository::new(self.db.clone()),
             repository::SettingsRepository::new(self.db.clone()),
+            repository::LiabilityRepository::new(self.db.clone()),
             self.price_cache.clone(),
             self.exchange_rate_cache.clone(),
             self.http_client.clone(),
@@ -100,7 +134,89 @@ impl AppState {
     }
 
     pub fn pocket_service(&self) -> services::PocketService {
-        services::PocketService::new(repository::PocketRepository::new(self.db.clone()))
+        services::PocketService::new(
+            repository::PocketRepository::new(self.db.clone()),
+            repository::SettingsRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn bill_service(&self) -> services::BillService {
+        services::BillService::new(
+            repository::BillRepository::new(self.db.clone()),
+            repository::PayeeRepository::new(self.db.clone()),
+            repository::PocketRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn liability_service(&self) -> services::LiabilityService {
+        services::LiabilityService::new(
+            repository::LiabilityRepository::new(self.db.clone()),
+            repository::SettingsRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn net_worth_service(&self) -> services::NetWorthService {
+        services::NetWorthService::new(
+            repository::NetWorthRepository::new(self.db.clone()),
+            repository::PortfolioRepository::new(self.db.clone()),
+            repository::LiabilityRepository::new(self.db.clone()),
+            repository::SettingsRepository::new(self.db.clone()),
+            self.exchange_rate_service(),
+        )
+    }
+
+    pub fn reconciliation_service(&self) -> services::ReconciliationService {
+        services::ReconciliationService::new(
+            repository::ReconciliationRepository::new(self.db.clone()),
+            repository::PocketRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn notification_service(&self) -> services::NotificationService {
+        services::NotificationService::new(repository::NotificationRepository::new(self.db.clone()))
+    }
+
+    pub fn notifier(&self) -> notifications::InAppNotifier {
+        notifications::InAppNotifier::new(repository::NotificationRepository::new(self.db.clone()))
+    }
+
+    pub fn budget_service(&self) -> services::BudgetService {
+        services::BudgetService::new(
+            repository::BudgetRepository::new(self.db.clone()),
+            repository::TransactionRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn envelope_service(&self) -> services::EnvelopeService {
+        services::EnvelopeService::new(
+            repository::EnvelopeRepository::new(self.db.clone()),
+            repository::SettingsRepository::new(self.db.clone()),
+            repository::PocketRepository::new(self.db.clone()),
+            repository::TransactionRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn goal_service(&self) -> services::GoalService {
+        services::GoalService::new(
+            repository::GoalRepository::new(self.db.clone()),
+            repository::PocketRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn payee_service(&self) -> services::PayeeService {
+        services::PayeeService::new(
+            repository::PayeeRepository::new(self.db.clone()),
+            repository::TransactionRepository::new(self.db.clone()),
+        )
+    }
+
+    pub fn rule_service(&self) -> services::RuleService {
+        services::RuleService::new(
+            repository::RuleRepository::new(self.db.clone()),
+            repository::TransactionRepository::new(self.db.clone()),
+            repository::PocketRepository::new(self.db.clone()),
+            repository::SettingsRepository::new(self.db.clone()),
+        )
     }
 }
 
@@ -154,13 +270,30 @@ async fn main() {
         .build()
         .expect("Failed to build HTTP client");
 
+    let trash_retention_days = std::env::var("TRASH_RETENTION_DAYS")
+        .ok()
+        .map(|v| v.parse::<i64>().expect("Invalid TRASH_RETENTION_DAYS"))
+        .unwrap_or(30);
+
+    let idempotency_ttl_hours = std::env::var("IDEMPOTENCY_TTL_HOURS")
+        .ok()
+        .map(|v| v.parse::<i64>().expect("Invalid IDEMPOTENCY_TTL_HOURS"))
+        .unwrap_or(24);
+
     let state = AppState {
         db: pool,
         price_cache: cache,
         exchange_rate_cache,
         http_client,
+        trash_retention_days,
+        idempotency_ttl_hours,
     };
 
+    jobs::spawn_trash_purge(state.clone());
+    jobs::spawn_idempotency_cleanup(state.clone());
+    jobs::spawn_bill_reminders(state.clone());
+    jobs::spawn_net_worth_snapshots(state.clone());
+
     let api_routes = Router::new()
         .route("/auth/register", post(handlers::register))
         .route("/auth/login", post(handlers::login))
@@ -169,24 +302,78 @@ async fn main() {
             "/transactions",
             post(handlers::create_transaction).get(handlers::get_transactions),
         )
+        .route("/transactions/batch", post(handlers::batch_transactions))
+        .route("/transactions/duplicates", get(handlers::get_duplicates))
+        .route(
+            "/transactions/duplicates/{id}/merge",
+            post(handlers::merge_duplicate),
+        )
+        .route(
+            "/transactions/duplicates/{id}/dismiss",
+            post(handlers::dismiss_duplicate),
+        )
+        .route(
+            "/transactions/trash",
+            get(handlers::get_trash).delete(handlers::empty_trash),
+        )
         .route(
             "/transactions/{id}",
             put(handlers::update_transaction)
                 .delete(handlers::delete_transaction)
                 .get(handlers::get_transaction),
         )
+        .route(
+            "/transactions/recompute-rates",
+            post(handlers::recompute_exchange_rates),
+        )
+        .route(
+            "/transactions/{id}/recompute-rate",
+            post(handlers::recompute_exchange_rate),
+        )
+        .route(
+            "/transactions/{id}/unlock",
+            post(handlers::unlock_transaction),
+        )
+        .route("/transactions/{id}/post", post(handlers::post_transaction))
+        .route("/transactions/{id}/void", post(handlers::void_transaction))
         .route(
             "/transactions/{id}/restore",
             post(handlers::restore_transaction),
         )
+        .route(
+            "/transactions/{id}/permanent",
+            delete(handlers::delete_transaction_permanently),
+        )
+        .route(
+            "/transactions/{id}/history",
+            get(handlers::get_transaction_history),
+        )
+        .route(
+            "/transactions/{id}/revert",
+            post(handlers::revert_transaction),
+        )
         .route("/settings/currency", put(handlers::update_base_currency))
+        .route(
+            "/settings/budgeting-mode",
+            put(handlers::update_budgeting_mode),
+        )
         .route(
             "/settings/currencies",
             get(handlers::get_available_currencies),
         )
         .route("/categories", get(handlers::get_categories))
         .route("/analysis/category", get(handlers::get_spending_analysis))
+        .route("/analysis/cash-flow", get(handlers::get_cash_flow))
+        .route("/analysis/payees", get(handlers::get_top_payees))
         .route("/analysis/net-worth", get(handlers::get_financial_health))
+        .route(
+            "/analysis/net-worth/history",
+            get(handlers::get_net_worth_history),
+        )
+        .route(
+            "/analysis/net-worth/backfill",
+            post(handlers::backfill_net_worth),
+        )
         .route("/portfolio/refresh", post(handlers::refresh_portfolio))
         .route(
             "/portfolio/{ticker}",
@@ -208,7 +395,142 @@ async fn main() {
                 .put(handlers::update_pocket)
                 .delete(handlers::delete_pocket),
         )
-        .route("/pockets/transfer", post(handlers::transfer_funds));
+        .route("/pockets/{id}/archive", post(handlers::archive_pocket))
+        .route("/pockets/{id}/unarchive", post(handlers::unarchive_pocket))
+        .route(
+            "/pockets/{id}/credit-card",
+            put(handlers::update_credit_card),
+        )
+        .route(
+            "/pockets/{id}/card-statement",
+            get(handlers::get_card_statement),
+        )
+        .route("/pockets/{id}/pay", post(handlers::pay_card))
+        .route(
+            "/pockets/{id}/reconciliations",
+            post(handlers::start_reconciliation).get(handlers::get_reconciliations),
+        )
+        .route(
+            "/reconciliations/{id}",
+            get(handlers::get_reconciliation).delete(handlers::delete_reconciliation),
+        )
+        .route(
+            "/reconciliations/{id}/transactions",
+            put(handlers::clear_transactions),
+        )
+        .route(
+            "/reconciliations/{id}/complete",
+            post(handlers::complete_reconciliation),
+        )
+        .route(
+            "/pockets/{id}/statement",
+            get(handlers::get_pocket_statement),
+        )
+        .route(
+            "/pockets/{id}/balance-history",
+            get(handlers::get_pocket_balance_history),
+        )
+        .route("/pockets/transfer", post(handlers::transfer_funds))
+        .route("/transfers", get(handlers::get_transfers))
+        .route(
+            "/transfers/{id}",
+            get(handlers::get_transfer)
+                .put(handlers::update_transfer)
+                .delete(handlers::delete_transfer),
+        )
+        .route(
+            "/bills",
+            post(handlers::create_bill).get(handlers::get_bills),
+        )
+        .route("/bills/upcoming", get(handlers::get_upcoming_bills))
+        .route(
+            "/bills/{id}",
+            get(handlers::get_bill)
+                .put(handlers::update_bill)
+                .delete(handlers::delete_bill),
+        )
+        .route(
+            "/liabilities",
+            post(handlers::create_liability).get(handlers::get_liabilities),
+        )
+        .route(
+            "/liabilities/{id}",
+            get(handlers::get_liability)
+                .put(handlers::update_liability)
+                .delete(handlers::delete_liability),
+        )
+        .route(
+            "/liabilities/{id}/schedule",
+            get(handlers::get_liability_schedule),
+        )
+        .route(
+            "/liabilities/{id}/payments",
+            post(handlers::record_liability_payment).get(handlers::get_liability_payments),
+        )
+        .route(
+            "/liabilities/{id}/payments/{transaction_id}",
+            delete(handlers::remove_liability_payment),
+        )
+        .route("/notifications", get(handlers::get_notifications))
+        .route(
+            "/notifications/{id}/read",
+            post(handlers::mark_notification_read),
+        )
+        .route(
+            "/budgets",
+            post(handlers::create_budget).get(handlers::get_budgets),
+        )
+        .route("/budgets/report", get(handlers::get_budget_report))
+        .route("/budgets/alerts", get(handlers::get_budget_alerts))
+        .route(
+            "/budgets/{id}",
+            get(handlers::get_budget)
+                .put(handlers::update_budget)
+                .delete(handlers::delete_budget),
+        )
+        .route(
+            "/envelopes",
+            get(handlers::get_envelopes).put(handlers::assign_envelope),
+        )
+        .route(
+            "/goals",
+            post(handlers::create_goal).get(handlers::get_goals),
+        )
+        .route(
+            "/goals/{id}",
+            get(handlers::get_goal)
+                .put(handlers::update_goal)
+                .delete(handlers::delete_goal),
+        )
+        .route(
+            "/rules",
+            post(handlers::create_rule).get(handlers::get_rules),
+        )
+        .route(
+            "/rules/{id}",
+            put(handlers::update_rule).delete(handlers::delete_rule),
+        )
+        .route("/rules/{id}/dry-run", get(handlers::dry_run_rule))
+        .route("/rules/{id}/apply", post(handlers::apply_rule))
+        .route(
+            "/payees",
+            post(handlers::create_payee).get(handlers::get_payees),
+        )
+        .route(
+            "/payees/{id}",
+            get(handlers::get_payee)
+                .put(handlers::update_payee)
+                .delete(handlers::delete_payee),
+        )
+        .route("/payees/{id}/aliases", post(handlers::add_payee_alias))
+        .route(
+            "/payees/{id}/aliases/{alias_id}",
+            delete(handlers::delete_payee_alias),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            idempotency::idempotency,
+        ));
 
     let app = Router::new()
         .route("/", get(health_check))
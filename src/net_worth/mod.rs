@@ -0,0 +1,176 @@
+mod tests;
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::amortization;
+use crate::schemas::{
+    LiabilityPaymentRow, LiabilityRow, NetWorthItem, NetWorthItemKind, NetWorthSnapshot,
+    PocketDayBalanceRow, PocketKind, PortfolioJoinedRow,
+};
+
+/// Something owned or owed at the end of a day, before conversion to base currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holding {
+    pub kind: NetWorthItemKind,
+    pub id: Option<Uuid>,
+    pub name: String,
+    pub currency: String,
+    pub amount: Decimal,
+}
+
+/// What is still owed on a liability at the end of `day`, counting the repayments made up to
+/// then. `None` before the liability existed: the earlier of its creation and first repayment.
+pub fn outstanding_on(
+    liability: &LiabilityRow,
+    payments: &[LiabilityPaymentRow],
+    day: NaiveDate,
+) -> Option<Decimal> {
+    let started = liability
+        .created_at
+        .map_or(liability.first_payment_on, |c| {
+            c.date_naive().min(liability.first_payment_on)
+        });
+    if day < started {
+        return None;
+    }
+    let paid: Vec<LiabilityPaymentRow> = payments
+        .iter()
+        .filter(|p| p.liability_id == liability.id && p.occurred_at.date_naive() <= day)
+        .cloned()
+        .collect();
+    Some(amortization::outstanding(liability, &paid))
+}
+
+/// Everything held at the end of `day`. Pockets with nothing in them are left out; a credit
+/// card in debt is a liability, one in credit counts as cash.
+pub fn holdings(
+    day: NaiveDate,
+    pockets: &[PocketDayBalanceRow],
+    assets: &[PortfolioJoinedRow],
+    liabilities: &[LiabilityRow],
+    payments: &[LiabilityPaymentRow],
+) -> Vec<Holding> {
+    let mut holdings = Vec::new();
+
+    for pocket in pockets.iter().filter(|p| !p.balance.is_zero()) {
+        let is_debt =
+            pocket.kind == PocketKind::CreditCard.as_str() && pocket.balance < Decimal::ZERO;
+        holdings.push(Holding {
+            kind: if is_debt {
+                NetWorthItemKind::Liability
+            } else {
+                NetWorthItemKind::Cash
+            },
+            id: Some(pocket.pocket_id),
+            name: pocket.name.clone(),
+            currency: pocket.currency.clone(),
+            amount: if is_debt {
+                -pocket.balance
+            } else {
+                pocket.balance
+            },
+        });
+    }
+
+    for asset in assets {
+        holdings.push(Holding {
+            kind: NetWorthItemKind::Investment,
+            id: None,
+            name: asset.ticker.clone(),
+            currency: asset.currency.clone().unwrap_or_else(|| "USD".to_string()),
+            amount: asset.quantity * asset.current_price,
+        });
+    }
+
+    for liability in liabilities {
+        if let Some(amount) = outstanding_on(liability, payments, day).filter(|a| !a.is_zero()) {
+            holdings.push(Holding {
+                kind: NetWorthItemKind::Liability,
+                id: Some(liability.id),
+                name: liability.name.clone(),
+                currency: liability.currency.clone(),
+                amount,
+            });
+        }
+    }
+
+    holdings
+}
+
+/// A rate for every day from `start` to `end`, given the rates published on working days
+/// (sorted by date). Weekends and holidays carry the last published rate forward; days before
+/// the first published rate are left out.
+pub fn daily_rates(
+    start: NaiveDate,
+    end: NaiveDate,
+    published: &[(NaiveDate, Decimal)],
+) -> Vec<(NaiveDate, Decimal)> {
+    let mut next = published.iter().peekable();
+    let mut last = None;
+    start
+        .iter_days()
+        .take_while(|day| *day <= end)
+        .filter_map(|day| {
+            while let Some((_, rate)) = next.next_if(|(published_on, _)| *published_on <= day) {
+                last = Some(*rate);
+            }
+            last.map(|rate| (day, rate))
+        })
+        .collect()
+}
+
+/// Convert holdings to base currency with `rates` (base-currency units per unit of each
+/// currency; a missing rate counts as 1) and total them. Totals add up the rounded item values.
+pub fn snapshot(
+    day: NaiveDate,
+    base_currency: &str,
+    holdings: Vec<Holding>,
+    rates: &HashMap<String, Decimal>,
+    is_backfill: bool,
+) -> NetWorthSnapshot {
+    let mut cash = Decimal::ZERO;
+    let mut investments = Decimal::ZERO;
+    let mut liabilities = Decimal::ZERO;
+
+    let items: Vec<NetWorthItem> = holdings
+        .into_iter()
+        .map(|h| {
+            let rate = if h.currency == base_currency {
+                Decimal::ONE
+            } else {
+                rates.get(&h.currency).copied().unwrap_or(Decimal::ONE)
+            };
+            let amount = h.amount.round_dp(2);
+            let value = (h.amount * rate).round_dp(2);
+            match h.kind {
+                NetWorthItemKind::Cash => cash += value,
+                NetWorthItemKind::Investment => investments += value,
+                NetWorthItemKind::Liability => liabilities += value,
+            }
+            NetWorthItem {
+                kind: h.kind,
+                id: h.id,
+                name: h.name,
+                currency: h.currency,
+                amount,
+                rate,
+                value,
+            }
+        })
+        .collect();
+
+    NetWorthSnapshot {
+        date: day,
+        base_currency: base_currency.to_string(),
+        cash_balance: cash,
+        investment_balance: investments,
+        liabilities_balance: liabilities,
+        total_net_worth: cash + investments - liabilities,
+        is_backfill,
+        items,
+    }
+}
@@ -0,0 +1,231 @@
+#![cfg(test)]
+
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{
+    LiabilityPaymentRow, LiabilityRow, NetWorthItemKind, PocketDayBalanceRow, PortfolioJoinedRow,
+};
+
+use super::{Holding, daily_rates, holdings, outstanding_on, snapshot};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn make_pocket(kind: &str, currency: &str, balance: Decimal) -> PocketDayBalanceRow {
+    PocketDayBalanceRow {
+        day: date(2026, 3, 1),
+        pocket_id: Uuid::new_v4(),
+        name: "Pocket".to_string(),
+        currency: currency.to_string(),
+        kind: kind.to_string(),
+        balance,
+    }
+}
+
+fn make_loan() -> LiabilityRow {
+    LiabilityRow {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        name: "Car loan".to_string(),
+        principal: dec!(1000),
+        annual_rate: Decimal::ZERO,
+        term_months: 10,
+        frequency: "monthly".to_string(),
+        first_payment_on: date(2026, 1, 31),
+        currency: "SGD".to_string(),
+        created_at: Some(Utc.with_ymd_and_hms(2026, 2, 10, 0, 0, 0).unwrap()),
+    }
+}
+
+fn make_payment(loan: &LiabilityRow, day: NaiveDate, amount: Decimal) -> LiabilityPaymentRow {
+    LiabilityPaymentRow {
+        liability_id: loan.id,
+        transaction_id: Uuid::new_v4(),
+        occurred_at: Utc.from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap()),
+        amount,
+    }
+}
+
+fn make_holding(kind: NetWorthItemKind, currency: &str, amount: Decimal) -> Holding {
+    Holding {
+        kind,
+        id: None,
+        name: "Item".to_string(),
+        currency: currency.to_string(),
+        amount,
+    }
+}
+
+// ============================================================================
+// Holdings Tests
+// ============================================================================
+
+mod holding {
+    use super::*;
+
+    #[test]
+    fn test_liability_counts_repayments_up_to_the_day() {
+        let loan = make_loan();
+        let payments = vec![
+            make_payment(&loan, date(2026, 1, 31), dec!(100)),
+            make_payment(&loan, date(2026, 2, 28), dec!(100)),
+        ];
+
+        // Created after its first repayment was due, so it counts from that date
+        assert_eq!(outstanding_on(&loan, &payments, date(2026, 1, 30)), None);
+        assert_eq!(
+            outstanding_on(&loan, &payments, date(2026, 1, 31)),
+            Some(dec!(900))
+        );
+        assert_eq!(
+            outstanding_on(&loan, &payments, date(2026, 2, 27)),
+            Some(dec!(900))
+        );
+        assert_eq!(
+            outstanding_on(&loan, &payments, date(2026, 3, 1)),
+            Some(dec!(800))
+        );
+    }
+
+    #[test]
+    fn test_card_debt_is_a_liability_and_empty_pockets_are_skipped() {
+        let pockets = vec![
+            make_pocket("cash", "SGD", dec!(500)),
+            make_pocket("cash", "SGD", Decimal::ZERO),
+            make_pocket("credit_card", "SGD", dec!(-120)),
+            make_pocket("credit_card", "SGD", dec!(15)),
+        ];
+
+        let items = holdings(date(2026, 3, 1), &pockets, &[], &[], &[]);
+
+        let kinds: Vec<_> = items.iter().map(|h| (h.kind, h.amount)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (NetWorthItemKind::Cash, dec!(500)),
+                (NetWorthItemKind::Liability, dec!(120)),
+                (NetWorthItemKind::Cash, dec!(15)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_assets_are_valued_at_current_price() {
+        let assets = vec![PortfolioJoinedRow {
+            ticker: "AAPL".to_string(),
+            name: "Apple".to_string(),
+            quantity: dec!(3),
+            avg_buy_price: dec!(100),
+            current_price: dec!(150),
+            source: None,
+            api_ticker: None,
+            currency: None,
+            icon_url: None,
+        }];
+
+        let items = holdings(date(2026, 3, 1), &[], &assets, &[], &[]);
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].kind, NetWorthItemKind::Investment);
+        assert_eq!(items[0].currency, "USD");
+        assert_eq!(items[0].amount, dec!(450));
+    }
+}
+
+// ============================================================================
+// Snapshot Tests
+// ============================================================================
+
+mod snapshots {
+    use super::*;
+
+    #[test]
+    fn test_converts_with_rates_and_totals() {
+        let rates = HashMap::from([("USD".to_string(), dec!(1.35))]);
+        let items = vec![
+            make_holding(NetWorthItemKind::Cash, "SGD", dec!(1000)),
+            make_holding(NetWorthItemKind::Cash, "USD", dec!(100)),
+            make_holding(NetWorthItemKind::Investment, "USD", dec!(200)),
+            make_holding(NetWorthItemKind::Liability, "SGD", dec!(300)),
+        ];
+
+        let snap = snapshot(date(2026, 3, 1), "SGD", items, &rates, false);
+
+        assert_eq!(snap.items[0].rate, Decimal::ONE);
+        assert_eq!(snap.items[1].rate, dec!(1.35));
+        assert_eq!(snap.items[1].value, dec!(135));
+        assert_eq!(snap.cash_balance, dec!(1135));
+        assert_eq!(snap.investment_balance, dec!(270));
+        assert_eq!(snap.liabilities_balance, dec!(300));
+        assert_eq!(snap.total_net_worth, dec!(1105));
+        assert!(!snap.is_backfill);
+    }
+
+    #[test]
+    fn test_totals_add_up_rounded_values() {
+        let rates = HashMap::from([("USD".to_string(), dec!(1.333))]);
+        let items = vec![
+            make_holding(NetWorthItemKind::Cash, "USD", dec!(1.005)),
+            make_holding(NetWorthItemKind::Cash, "USD", dec!(1.005)),
+        ];
+
+        let snap = snapshot(date(2026, 3, 1), "SGD", items, &rates, true);
+
+        let sum: Decimal = snap.items.iter().map(|i| i.value).sum();
+        assert_eq!(snap.cash_balance, sum);
+        assert_eq!(snap.items[0].amount, dec!(1.00));
+    }
+}
+
+// ============================================================================
+// Daily Rate Tests
+// ============================================================================
+
+mod rates {
+    use super::*;
+
+    #[test]
+    fn test_weekend_carries_friday_rate() {
+        // 2026-03-06 is a Friday
+        let published = [
+            (date(2026, 3, 6), dec!(1.30)),
+            (date(2026, 3, 9), dec!(1.31)),
+        ];
+
+        let rates = daily_rates(date(2026, 3, 7), date(2026, 3, 9), &published);
+
+        assert_eq!(
+            rates,
+            vec![
+                (date(2026, 3, 7), dec!(1.30)),
+                (date(2026, 3, 8), dec!(1.30)),
+                (date(2026, 3, 9), dec!(1.31)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_days_before_first_rate_are_left_out() {
+        let published = [(date(2026, 3, 3), dec!(1.30))];
+
+        let rates = daily_rates(date(2026, 3, 1), date(2026, 3, 4), &published);
+
+        assert_eq!(
+            rates,
+            vec![
+                (date(2026, 3, 3), dec!(1.30)),
+                (date(2026, 3, 4), dec!(1.30))
+            ]
+        );
+    }
+}
@@ -0,0 +1,31 @@
+use async_trait::async_trait;
+
+use crate::error::AppError;
+use crate::repository::NotificationRepository;
+use crate::schemas::NewNotification;
+
+/// Delivers notifications to users. Jobs depend on this trait rather than a channel so
+/// email or push delivery can be added without touching them.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: &NewNotification) -> Result<(), AppError>;
+}
+
+/// Stores notifications for the app to show (`GET /notifications`)
+pub struct InAppNotifier {
+    notification_repo: NotificationRepository,
+}
+
+impl InAppNotifier {
+    pub fn new(notification_repo: NotificationRepository) -> Self {
+        Self { notification_repo }
+    }
+}
+
+#[async_trait]
+impl Notifier for InAppNotifier {
+    async fn notify(&self, notification: &NewNotification) -> Result<(), AppError> {
+        self.notification_repo.create(notification).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+mod tests;
+
+use crate::schemas::PayeeAliasRow;
+
+/// Canonical form used for aliases and for matching descriptions:
+/// lowercase, punctuation collapsed to single spaces, and trailing store/reference
+/// numbers dropped ("STARBUCKS #123" -> "starbucks")
+pub fn normalize_name(input: &str) -> String {
+    let lowered: String = input
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    lowered
+        .split_whitespace()
+        .enumerate()
+        .filter(|(i, token)| *i == 0 || !token.chars().all(|c| c.is_ascii_digit()))
+        .map(|(_, token)| token)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Find the payee whose alias appears as a whole-word sequence in the description.
+/// The longest alias wins so "starbucks reserve" beats "starbucks".
+pub fn resolve<'a>(description: &str, aliases: &'a [PayeeAliasRow]) -> Option<&'a PayeeAliasRow> {
+    let normalized = normalize_name(description);
+    if normalized.is_empty() {
+        return None;
+    }
+    let haystack = format!(" {} ", normalized);
+
+    aliases
+        .iter()
+        .filter(|a| !a.alias.is_empty() && haystack.contains(&format!(" {} ", a.alias)))
+        .max_by_key(|a| a.alias.len())
+}
@@ -0,0 +1,74 @@
+#![cfg(test)]
+
+use uuid::Uuid;
+
+use crate::schemas::PayeeAliasRow;
+
+use super::{normalize_name, resolve};
+
+fn make_alias(alias: &str) -> PayeeAliasRow {
+    PayeeAliasRow {
+        payee_id: Uuid::new_v4(),
+        alias: alias.to_string(),
+        default_category_id: None,
+    }
+}
+
+// ============================================================================
+// Normalization Tests
+// ============================================================================
+
+mod normalization {
+    use super::*;
+
+    #[test]
+    fn variants_collapse_to_same_name() {
+        assert_eq!(normalize_name("Starbucks"), "starbucks");
+        assert_eq!(normalize_name("STARBUCKS #123"), "starbucks");
+        assert_eq!(normalize_name("  starbucks  "), "starbucks");
+    }
+
+    #[test]
+    fn punctuation_becomes_spaces() {
+        assert_eq!(normalize_name("GRAB*RIDE 8812"), "grab ride");
+        assert_eq!(normalize_name("McDonald's"), "mcdonald s");
+    }
+
+    #[test]
+    fn leading_number_is_kept() {
+        // Given: a brand that starts with a number
+        assert_eq!(normalize_name("7-Eleven 0042"), "7 eleven");
+    }
+}
+
+// ============================================================================
+// Resolution Tests
+// ============================================================================
+
+mod resolution {
+    use super::*;
+
+    #[test]
+    fn matches_whole_words_only() {
+        let aliases = vec![make_alias("grab")];
+
+        assert!(resolve("GRAB*RIDE 8812", &aliases).is_some());
+        assert!(resolve("Grabfood order", &aliases).is_none());
+    }
+
+    #[test]
+    fn longest_alias_wins() {
+        let aliases = vec![make_alias("starbucks"), make_alias("starbucks reserve")];
+
+        let resolved = resolve("STARBUCKS RESERVE #12", &aliases).unwrap();
+
+        assert_eq!(resolved.alias, "starbucks reserve");
+    }
+
+    #[test]
+    fn no_match_for_empty_description() {
+        let aliases = vec![make_alias("netflix")];
+
+        assert!(resolve("  #  ", &aliases).is_none());
+    }
+}
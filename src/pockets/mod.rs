@@ -0,0 +1,22 @@
+mod tests;
+
+use crate::schemas::Pocket;
+
+/// Why `target` can't take over the history of a deleted `pocket`, if it can't.
+/// Balances only carry over between pockets of the same currency and kind: a card balance
+/// is money owed, so merging it into cash (or the reverse) would flip its meaning.
+pub fn reassign_conflict(pocket: &Pocket, target: &Pocket) -> Option<String> {
+    if target.id == pocket.id {
+        return Some("Cannot reassign transactions to the pocket being deleted".to_string());
+    }
+    if target.currency != pocket.currency {
+        return Some(format!("Target pocket must be in {}", pocket.currency));
+    }
+    if target.kind != pocket.kind {
+        return Some(format!(
+            "Target pocket must be a {} pocket",
+            pocket.kind.replace('_', " ")
+        ));
+    }
+    None
+}
@@ -0,0 +1,77 @@
+#![cfg(test)]
+
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::schemas::Pocket;
+
+use super::reassign_conflict;
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn make_pocket(kind: &str, currency: &str) -> Pocket {
+    Pocket {
+        id: Uuid::new_v4(),
+        name: "Pocket".to_string(),
+        description: None,
+        icon: "account_balance_wallet".to_string(),
+        currency: currency.to_string(),
+        kind: kind.to_string(),
+        is_default: false,
+        archived_at: None,
+        created_at: None,
+        balance: Decimal::ZERO,
+        posted_balance: Decimal::ZERO,
+    }
+}
+
+// ============================================================================
+// Reassignment Tests
+// ============================================================================
+
+mod reassign {
+    use super::*;
+
+    #[test]
+    fn same_kind_and_currency_is_allowed() {
+        let pocket = make_pocket("cash", "SGD");
+        let target = make_pocket("cash", "SGD");
+
+        assert_eq!(reassign_conflict(&pocket, &target), None);
+    }
+
+    #[test]
+    fn card_cannot_merge_into_cash() {
+        let card = make_pocket("credit_card", "SGD");
+        let cash = make_pocket("cash", "SGD");
+
+        assert_eq!(
+            reassign_conflict(&card, &cash),
+            Some("Target pocket must be a credit card pocket".to_string())
+        );
+        assert_eq!(
+            reassign_conflict(&cash, &card),
+            Some("Target pocket must be a cash pocket".to_string())
+        );
+    }
+
+    #[test]
+    fn currency_must_match() {
+        let pocket = make_pocket("cash", "SGD");
+        let target = make_pocket("cash", "USD");
+
+        assert_eq!(
+            reassign_conflict(&pocket, &target),
+            Some("Target pocket must be in SGD".to_string())
+        );
+    }
+
+    #[test]
+    fn pocket_cannot_take_its_own_history() {
+        let pocket = make_pocket("cash", "SGD");
+
+        assert!(reassign_conflict(&pocket, &pocket).is_some());
+    }
+}
@@ -1,11 +1,22 @@
 use crate::error::AppError;
 use crate::schemas::{
-    Category, CategorySummary, CreatePortfolioItem, Pocket, PocketSummary, Transaction,
-    TransactionDetail, User, UserProfile,
+    BalanceInterval, BalancePoint, BillMatchRow, BillPaymentRow, BillRow, Budget, CardActivityRow,
+    CashFlowRow, CategorizationRule, Category, CategorySummary, CreateBill, CreateLiability,
+    CreatePortfolioItem, CreditCard, CurrencyBalanceRow, DeletePocketResponse, DeletedTransaction,
+    DuplicateCandidateRow, DuplicatePair, DuplicatePairRow, DuplicateSide, EnvelopeActivityRow,
+    EnvelopeAllocationRow, ForeignTransactionRow, GoalRow, IdempotencyRecord, LiabilityPaymentRow,
+    LiabilityRow, MonthlyFlowRow, NetWorthItem, NetWorthSnapshot, NewNotification, NewTransaction,
+    NewTransfer, Notification, Payee, PayeeAlias, PayeeAliasRow, PayeeSpending, PayeeSummary,
+    Pocket, PocketDayBalanceRow, PocketKind, PocketSummary, ReconciliationItem, ReconciliationRow,
+    RepaymentCandidateRow, RuleCandidateRow, RuleRequest, StartReconciliation, StatementRow,
+    Transaction, TransactionChanges, TransactionDetail, TransactionQueryParams,
+    TransactionSnapshot, TransactionStatus, TransactionVersionRow, Transfer, TransferRow,
+    UpdateBill, UpdateLiability, User, UserProfile,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool, types::Json};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 pub struct UserRepository {
@@ -200,75 +211,135 @@ impl TransactionRepository {
         Ok(category)
     }
 
-    pub async fn create(
+    /// Start a database transaction for multi-statement operations
+    pub async fn begin(&self) -> Result<sqlx::Transaction<'static, sqlx::Postgres>, AppError> {
+        Ok(self.pool.begin().await?)
+    }
+
+    pub async fn create(&self, user_id: Uuid, new: &NewTransaction) -> Result<Uuid, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        self.create_tx(&mut conn, user_id, new).await
+    }
+
+    pub async fn create_tx(
         &self,
+        conn: &mut PgConnection,
         user_id: Uuid,
-        amount: Decimal,
-        description: Option<String>,
-        category_id: i32,
-        occurred_at: DateTime<Utc>,
-        original_currency: Option<String>,
-        original_amount: Option<Decimal>,
-        exchange_rate: Option<Decimal>,
-        pocket_id: Uuid,
+        new: &NewTransaction,
     ) -> Result<Uuid, AppError> {
         let id = sqlx::query_scalar!(
             r#"
             INSERT INTO transactions (
                 amount, description, category_id, user_id, occurred_at,
-                original_currency, original_amount, exchange_rate, pocket_id
+                original_currency, original_amount, exchange_rate, pocket_id, tags, payee_id,
+                transfer_id, status
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
             RETURNING id
             "#,
-            amount,
-            description,
-            category_id,
+            new.amount,
+            new.description,
+            new.category_id,
             user_id,
-            occurred_at,
-            original_currency,
-            original_amount,
-            exchange_rate,
-            pocket_id
+            new.occurred_at,
+            new.original_currency,
+            new.original_amount,
+            new.exchange_rate,
+            new.pocket_id,
+            &new.tags,
+            new.payee_id,
+            new.transfer_id,
+            new.status.as_str()
         )
-        .fetch_one(&self.pool)
+        .fetch_one(conn)
         .await?;
         Ok(id)
     }
 
+    /// Returns the ids (out of `ids`) that belong to the user and are not deleted
+    pub async fn find_active_ids(
+        &self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let rows = sqlx::query_scalar!(
+            "SELECT id FROM transactions WHERE user_id = $1 AND id = ANY($2) AND deleted_at IS NULL",
+            user_id,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Active transactions with the fields categorization rules match on
+    pub async fn get_rule_candidates(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<RuleCandidateRow>, AppError> {
+        let rows = sqlx::query_as!(
+            RuleCandidateRow,
+            r#"
+            SELECT id, description, amount, original_amount, original_currency,
+                category_id, pocket_id, tags, occurred_at
+            FROM transactions
+            WHERE user_id = $1 AND deleted_at IS NULL AND transfer_id IS NULL
+            ORDER BY occurred_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn category_exists(&self, category_id: i32) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            "SELECT 1 as exists FROM categories WHERE id = $1",
+            category_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(result.is_some())
+    }
+
     pub async fn find_by_user_and_date(
         &self,
         user_id: Uuid,
-        start_date: Option<DateTime<Utc>>,
-        end_date: Option<DateTime<Utc>>,
-        pocket_id: Option<Uuid>,
+        filter: &TransactionQueryParams,
         limit: i64,
         offset: i64,
     ) -> Result<Vec<Transaction>, AppError> {
         let transactions = sqlx::query!(
             r#"
             SELECT 
-                t.id, t.amount, t.description, t.category_id, t.occurred_at, t.created_at,
+                t.id, t.amount, t.description, t.category_id, t.occurred_at, t.created_at, t.tags,
+                t.transfer_id, t.status,
                 c.name as "category_name?", c.icon as category_icon, COALESCE(c.is_income, FALSE) as "category_is_income!",
                 COALESCE(c.exclude_from_analysis, FALSE) as "category_exclude!",
-                p.id as "pocket_id?", p.name as "pocket_name?", p.icon as "pocket_icon?"
+                p.id as "pocket_id?", p.name as "pocket_name?", p.icon as "pocket_icon?",
+                p.currency as "pocket_currency?",
+                py.id as "payee_id?", py.name as "payee_name?"
             FROM transactions t
             LEFT JOIN categories c ON t.category_id = c.id
             LEFT JOIN pockets p ON t.pocket_id = p.id
+            LEFT JOIN payees py ON t.payee_id = py.id
             WHERE t.user_id = $3 
               AND t.deleted_at IS NULL
               AND ($1::timestamptz IS NULL OR t.occurred_at >= $1)
               AND ($2::timestamptz IS NULL OR t.occurred_at <= $2)
               AND ($4::uuid IS NULL OR t.pocket_id = $4)
+              AND ($7::text IS NULL OR t.status = $7)
             ORDER BY t.occurred_at DESC
             LIMIT $5 OFFSET $6
             "#,
-            start_date,
-            end_date,
+            filter.start_date,
+            filter.end_date,
             user_id,
-            pocket_id,
+            filter.pocket_id,
             limit,
-            offset
+            offset,
+            filter.status.map(|s| s.as_str())
         )
         .fetch_all(&self.pool)
         .await?
@@ -288,7 +359,15 @@ impl TransactionRepository {
                 id,
                 name: row.pocket_name.unwrap_or_default(),
                 icon: row.pocket_icon.unwrap_or_else(|| "account_balance_wallet".to_string()),
+                currency: row.pocket_currency.unwrap_or_default(),
+            }),
+            payee: row.payee_id.map(|id| PayeeSummary {
+                id,
+                name: row.payee_name.unwrap_or_default(),
             }),
+            transfer_id: row.transfer_id,
+            tags: row.tags,
+            status: TransactionStatus::from_db(&row.status),
             occurred_at: row.occurred_at,
             created_at: row.created_at,
         })
@@ -300,9 +379,7 @@ impl TransactionRepository {
     pub async fn count_by_user_and_date(
         &self,
         user_id: Uuid,
-        start_date: Option<DateTime<Utc>>,
-        end_date: Option<DateTime<Utc>>,
-        pocket_id: Option<Uuid>,
+        filter: &TransactionQueryParams,
     ) -> Result<i64, AppError> {
         let result = sqlx::query!(
             r#"
@@ -313,11 +390,13 @@ impl TransactionRepository {
               AND ($1::timestamptz IS NULL OR t.occurred_at >= $1)
               AND ($2::timestamptz IS NULL OR t.occurred_at <= $2)
               AND ($4::uuid IS NULL OR t.pocket_id = $4)
+              AND ($5::text IS NULL OR t.status = $5)
             "#,
-            start_date,
-            end_date,
+            filter.start_date,
+            filter.end_date,
             user_id,
-            pocket_id
+            filter.pocket_id,
+            filter.status.map(|s| s.as_str())
         )
         .fetch_one(&self.pool)
         .await?;
@@ -334,12 +413,18 @@ impl TransactionRepository {
             r#"
             SELECT 
                 t.id, t.amount, t.description, t.category_id, t.occurred_at, t.created_at,
-                t.original_currency, t.original_amount, t.exchange_rate,
+                t.original_currency, t.original_amount, t.exchange_rate, t.tags, t.transfer_id,
+                t.status,
                 c.name as "category_name?", c.icon as category_icon, 
                 COALESCE(c.is_income, FALSE) as "category_is_income!",
-                COALESCE(c.exclude_from_analysis, FALSE) as "category_exclude!"
+                COALESCE(c.exclude_from_analysis, FALSE) as "category_exclude!",
+                p.id as "pocket_id?", p.name as "pocket_name?", p.icon as "pocket_icon?",
+                p.currency as "pocket_currency?",
+                py.id as "payee_id?", py.name as "payee_name?"
             FROM transactions t
             LEFT JOIN categories c ON t.category_id = c.id
+            LEFT JOIN pockets p ON t.pocket_id = p.id
+            LEFT JOIN payees py ON t.payee_id = py.id
             WHERE t.id = $1 AND t.user_id = $2 AND t.deleted_at IS NULL
             "#,
             id,
@@ -362,6 +447,21 @@ impl TransactionRepository {
                     .unwrap_or_else(|| "help_outline".to_string()),
                 exclude_from_analysis: row.category_exclude,
             }),
+            pocket: row.pocket_id.map(|id| PocketSummary {
+                id,
+                name: row.pocket_name.unwrap_or_default(),
+                icon: row
+                    .pocket_icon
+                    .unwrap_or_else(|| "account_balance_wallet".to_string()),
+                currency: row.pocket_currency.unwrap_or_default(),
+            }),
+            payee: row.payee_id.map(|id| PayeeSummary {
+                id,
+                name: row.payee_name.unwrap_or_default(),
+            }),
+            transfer_id: row.transfer_id,
+            tags: row.tags,
+            status: TransactionStatus::from_db(&row.status),
             occurred_at: row.occurred_at,
             created_at: row.created_at,
             original_currency: row.original_currency,
@@ -380,6 +480,7 @@ impl TransactionRepository {
             CategorySummary,
             r#"
             SELECT 
+                c.id as category_id,
                 c.name as category, 
                 COALESCE(SUM(t.amount), 0) as "total!",
                 COALESCE(c.is_income, FALSE) as "is_income!",
@@ -389,9 +490,10 @@ impl TransactionRepository {
             WHERE t.user_id = $3 
               AND t.occurred_at BETWEEN $1 AND $2
               AND t.deleted_at IS NULL
+              AND t.status <> 'void'
               AND (c.exclude_from_analysis = FALSE OR c.exclude_from_analysis IS NULL)
-            GROUP BY c.name, c.is_income, c.icon
-            ORDER BY 2 DESC
+            GROUP BY c.id, c.name, c.is_income, c.icon
+            ORDER BY 3 DESC
             "#,
             start_date,
             end_date,
@@ -402,21 +504,74 @@ impl TransactionRepository {
         Ok(rows)
     }
 
+    /// Base-currency totals per bucket, category and pocket, for the cash-flow series
+    pub async fn get_cash_flow(
+        &self,
+        user_id: Uuid,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        interval: BalanceInterval,
+        pocket_id: Option<Uuid>,
+    ) -> Result<Vec<CashFlowRow>, AppError> {
+        let rows = sqlx::query_as!(
+            CashFlowRow,
+            r#"
+            SELECT
+                date_trunc($4, t.occurred_at AT TIME ZONE 'UTC')::date as "bucket!",
+                c.id as category_id,
+                c.name as category_name,
+                COALESCE(c.is_income, FALSE) as "is_income!",
+                p.id as "pocket_id?",
+                p.name as "pocket_name?",
+                SUM(t.amount) as "total!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            LEFT JOIN pockets p ON t.pocket_id = p.id
+            WHERE t.user_id = $1
+              AND t.occurred_at BETWEEN $2 AND $3
+              AND t.deleted_at IS NULL
+              AND t.status <> 'void'
+              AND (c.exclude_from_analysis = FALSE OR c.exclude_from_analysis IS NULL)
+              AND ($5::uuid IS NULL OR t.pocket_id = $5)
+            GROUP BY 1, c.id, c.name, c.is_income, p.id, p.name
+            "#,
+            user_id,
+            start_date,
+            end_date,
+            interval.as_str(),
+            pocket_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
     pub async fn update(
         &self,
         id: Uuid,
         user_id: Uuid,
-        amount: Option<Decimal>,
-        description: Option<String>,
-        category_id: Option<i32>,
-        occurred_at: Option<DateTime<Utc>>,
-        original_currency: Option<String>,
-        original_amount: Option<Decimal>,
-        exchange_rate: Option<Decimal>,
-    ) -> Result<(), AppError> {
-        // Build dynamic query
-        // simple way:
-        sqlx::query!(
+        changes: &TransactionChanges,
+    ) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let updated = self.update_tx(&mut tx, id, user_id, changes).await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    /// Apply a partial update and record the before/after snapshot as a new version
+    pub async fn update_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        changes: &TransactionChanges,
+    ) -> Result<u64, AppError> {
+        let Some(old) = self.lock_snapshot(&mut *conn, id, user_id).await? else {
+            return Ok(0);
+        };
+
+        let new = sqlx::query_as!(
+            TransactionSnapshot,
             r#"
             UPDATE transactions 
             SET 
@@ -424,483 +579,3757 @@ impl TransactionRepository {
                 description = COALESCE($4, description),
                 category_id = COALESCE($5, category_id),
                 occurred_at = COALESCE($6, occurred_at),
-                original_currency = COALESCE($7, original_currency),
-                original_amount = COALESCE($8, original_amount),
-                exchange_rate = COALESCE($9, exchange_rate)
+                original_currency = CASE WHEN $13 THEN NULL ELSE COALESCE($7, original_currency) END,
+                original_amount = CASE WHEN $13 THEN NULL ELSE COALESCE($8, original_amount) END,
+                exchange_rate = CASE WHEN $13 THEN NULL ELSE COALESCE($9, exchange_rate) END,
+                pocket_id = COALESCE($10, pocket_id),
+                tags = COALESCE($11, tags),
+                payee_id = CASE WHEN $14 THEN NULL ELSE COALESCE($12, payee_id) END
             WHERE id = $1 AND user_id = $2
+            RETURNING amount, description, category_id, occurred_at,
+                original_currency, original_amount, exchange_rate, pocket_id, tags, payee_id
             "#,
             id,
             user_id,
-            amount,
-            description,
-            category_id,
-            occurred_at,
-            original_currency,
-            original_amount,
-            exchange_rate
+            changes.amount,
+            changes.description,
+            changes.category_id,
+            changes.occurred_at,
+            changes.original_currency,
+            changes.original_amount,
+            changes.exchange_rate,
+            changes.pocket_id,
+            changes.tags.as_deref(),
+            changes.payee_id,
+            changes.clear_foreign_currency,
+            changes.clear_payee
         )
-        .execute(&self.pool)
+        .fetch_one(&mut *conn)
         .await?;
 
-        Ok(())
+        self.record_version(conn, id, user_id, &old, &new).await?;
+        Ok(1)
     }
 
-    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
-        let result = sqlx::query!(
-            "UPDATE transactions SET deleted_at = NOW() WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
+    /// Overwrite every editable field with `target` (used for reverts), recording a new version
+    pub async fn restore_snapshot(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        target: &TransactionSnapshot,
+    ) -> Result<u64, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let Some(old) = self.lock_snapshot(&mut tx, id, user_id).await? else {
+            return Ok(0);
+        };
+
+        let new = sqlx::query_as!(
+            TransactionSnapshot,
+            r#"
+            UPDATE transactions 
+            SET 
+                amount = $3,
+                description = $4,
+                category_id = $5,
+                occurred_at = $6,
+                original_currency = $7,
+                original_amount = $8,
+                exchange_rate = $9,
+                pocket_id = $10,
+                tags = $11,
+                payee_id = $12
+            WHERE id = $1 AND user_id = $2
+            RETURNING amount, description, category_id, occurred_at,
+                original_currency, original_amount, exchange_rate, pocket_id, tags, payee_id
+            "#,
             id,
-            user_id
+            user_id,
+            target.amount,
+            target.description,
+            target.category_id,
+            target.occurred_at,
+            target.original_currency,
+            target.original_amount,
+            target.exchange_rate,
+            target.pocket_id,
+            &target.tags,
+            target.payee_id
         )
-        .execute(&self.pool)
+        .fetch_one(&mut *tx)
         .await?;
-        Ok(result.rows_affected())
+
+        self.record_version(&mut tx, id, user_id, &old, &new)
+            .await?;
+        tx.commit().await?;
+        Ok(1)
     }
 
-    pub async fn restore(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
-        let result = sqlx::query!(
-            "UPDATE transactions SET deleted_at = NULL WHERE id = $1 AND user_id = $2",
+    /// Read the editable fields of an active transaction, locking the row until commit
+    async fn lock_snapshot(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<TransactionSnapshot>, AppError> {
+        let snapshot = sqlx::query_as!(
+            TransactionSnapshot,
+            r#"
+            SELECT amount, description, category_id, occurred_at,
+                original_currency, original_amount, exchange_rate, pocket_id, tags, payee_id
+            FROM transactions
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+            FOR UPDATE
+            "#,
             id,
             user_id
         )
-        .execute(&self.pool)
+        .fetch_optional(conn)
         .await?;
-        Ok(result.rows_affected())
+        Ok(snapshot)
     }
 
-    pub async fn get_net_cash(&self, user_id: Uuid) -> Result<Decimal, AppError> {
-        let result = sqlx::query!(
+    async fn record_version(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        old: &TransactionSnapshot,
+        new: &TransactionSnapshot,
+    ) -> Result<(), AppError> {
+        // No-op edits don't deserve a history entry
+        if old == new {
+            return Ok(());
+        }
+
+        sqlx::query!(
             r#"
-            SELECT 
-                COALESCE(SUM(
-                    CASE WHEN c.is_income THEN t.amount 
-                    ELSE -t.amount 
-                    END
-                ), 0) as "net_cash!"
-            FROM transactions t
-            JOIN categories c ON t.category_id = c.id
-            WHERE t.user_id = $1 AND t.deleted_at IS NULL
+            INSERT INTO transaction_versions (transaction_id, version, changed_by, old_values, new_values)
+            SELECT $1, COALESCE(MAX(version), 0) + 1, $2, $3, $4
+            FROM transaction_versions
+            WHERE transaction_id = $1
             "#,
-            user_id
+            id,
+            user_id,
+            Json(old) as _,
+            Json(new) as _
         )
-        .fetch_one(&self.pool)
+        .execute(conn)
         .await?;
-        Ok(result.net_cash)
+        Ok(())
     }
 
-    pub async fn get_pocket_balance(
+    pub async fn get_versions(
         &self,
+        id: Uuid,
         user_id: Uuid,
-        pocket_id: Uuid,
-    ) -> Result<Decimal, AppError> {
-        let result = sqlx::query!(
+    ) -> Result<Vec<TransactionVersionRow>, AppError> {
+        let rows = sqlx::query!(
             r#"
             SELECT 
-                COALESCE(SUM(
-                    CASE WHEN c.is_income THEN t.amount 
-                    ELSE -t.amount 
-                    END
-                ), 0) as "balance!"
-            FROM transactions t
-            JOIN categories c ON t.category_id = c.id
-            WHERE t.user_id = $1 AND t.pocket_id = $2 AND t.deleted_at IS NULL
+                v.id, v.version, v.changed_by, v.changed_at,
+                v.old_values as "old_values: Json<TransactionSnapshot>",
+                v.new_values as "new_values: Json<TransactionSnapshot>"
+            FROM transaction_versions v
+            JOIN transactions t ON v.transaction_id = t.id
+            WHERE v.transaction_id = $1 AND t.user_id = $2
+            ORDER BY v.version ASC
             "#,
-            user_id,
-            pocket_id
+            id,
+            user_id
         )
-        .fetch_one(&self.pool)
-        .await?;
-        Ok(result.balance)
-    }
-}
-
-pub struct PortfolioRepository {
-    pool: PgPool,
-}
-
-impl PortfolioRepository {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| TransactionVersionRow {
+            id: row.id,
+            version: row.version,
+            changed_by: row.changed_by,
+            changed_at: row.changed_at,
+            old_values: row.old_values.0,
+            new_values: row.new_values.0,
+        })
+        .collect();
+        Ok(rows)
     }
 
-    pub async fn get_total_invested(&self, user_id: Uuid) -> Result<Decimal, AppError> {
-        let result = sqlx::query!(
+    /// Active transactions recorded in a foreign currency
+    pub async fn get_foreign_currency(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<ForeignTransactionRow>, AppError> {
+        let rows = sqlx::query_as!(
+            ForeignTransactionRow,
             r#"
-            SELECT COALESCE(SUM(p.quantity * a.current_price), 0) as "total_invested!"
-            FROM portfolio p
-            JOIN assets a ON p.ticker = a.ticker
-            WHERE p.user_id = $1
+            SELECT id, original_currency as "original_currency!",
+                original_amount as "original_amount!", exchange_rate, occurred_at
+            FROM transactions
+            WHERE user_id = $1
+              AND deleted_at IS NULL
+              AND original_currency IS NOT NULL
+              AND original_amount IS NOT NULL
+            ORDER BY occurred_at ASC
             "#,
             user_id
         )
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(result.total_invested)
+        Ok(rows)
     }
 
-    pub async fn get_tickers(&self, user_id: Uuid) -> Result<Vec<String>, AppError> {
-        let rows = sqlx::query!(
-            "SELECT DISTINCT ticker FROM portfolio WHERE user_id = $1",
+    /// Ownership check that also covers soft-deleted transactions
+    pub async fn exists_for_user(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let result = sqlx::query!(
+            "SELECT 1 as exists FROM transactions WHERE id = $1 AND user_id = $2",
+            id,
             user_id
         )
-        .fetch_all(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(rows.into_iter().filter_map(|r| r.ticker).collect())
+        Ok(result.is_some())
     }
 
-    pub async fn update_asset_price(
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        self.delete_tx(&mut conn, id, user_id).await
+    }
+
+    /// Soft-delete a transaction. Deleting either leg of a transfer deletes both.
+    pub async fn delete_tx(
         &self,
-        ticker: &str,
-        price: Decimal,
-        currency: &str,
-    ) -> Result<(), AppError> {
-        sqlx::query!(
-            "UPDATE assets SET current_price = $1, currency = $2, last_updated = NOW() WHERE ticker = $3",
-            price,
-            currency,
-            ticker
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions SET deleted_at = NOW()
+            WHERE user_id = $2 AND deleted_at IS NULL
+              AND (id = $1 OR transfer_id = (
+                  SELECT transfer_id FROM transactions WHERE id = $1 AND user_id = $2
+              ))
+            "#,
+            id,
+            user_id
         )
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
-        Ok(())
+        Ok(result.rows_affected())
     }
 
-    pub async fn update_asset_icon(&self, ticker: &str, icon_url: &str) -> Result<(), AppError> {
-        sqlx::query!(
-            "UPDATE assets SET icon_url = $1 WHERE ticker = $2",
-            icon_url,
-            ticker
+    /// Restore a transaction from the trash, together with the other leg if it is a transfer
+    pub async fn restore(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions SET deleted_at = NULL
+            WHERE user_id = $2 AND deleted_at IS NOT NULL
+              AND (id = $1 OR transfer_id = (
+                  SELECT transfer_id FROM transactions WHERE id = $1 AND user_id = $2
+              ))
+            "#,
+            id,
+            user_id
         )
         .execute(&self.pool)
         .await?;
-        Ok(())
+        Ok(result.rows_affected())
     }
 
-    pub async fn get_all_assets(&self) -> Result<Vec<crate::schemas::Asset>, AppError> {
-        let rows = sqlx::query_as!(
-            crate::schemas::Asset,
+    pub async fn find_deleted(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<DeletedTransaction>, AppError> {
+        let transactions = sqlx::query!(
             r#"
             SELECT 
-                ticker, 
-                name, 
-                asset_type,
-                api_ticker,
-                source,
-                current_price,
-                currency,
-                icon_url
-            FROM assets
-            ORDER BY name
-            "#
+                t.id, t.amount, t.description, t.category_id, t.occurred_at, t.created_at, t.tags,
+                t.transfer_id, t.status, t.deleted_at as "deleted_at!",
+                c.name as "category_name?", c.icon as category_icon, COALESCE(c.is_income, FALSE) as "category_is_income!",
+                COALESCE(c.exclude_from_analysis, FALSE) as "category_exclude!",
+                p.id as "pocket_id?", p.name as "pocket_name?", p.icon as "pocket_icon?",
+                p.currency as "pocket_currency?",
+                py.id as "payee_id?", py.name as "payee_name?"
+            FROM transactions t
+            LEFT JOIN categories c ON t.category_id = c.id
+            LEFT JOIN pockets p ON t.pocket_id = p.id
+            LEFT JOIN payees py ON t.payee_id = py.id
+            WHERE t.user_id = $1 AND t.deleted_at IS NOT NULL
+            ORDER BY t.deleted_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| DeletedTransaction {
+            transaction: Transaction {
+                id: row.id,
+                amount: row.amount,
+                description: row.description,
+                category: row.category_id.map(|id| Category {
+                    id,
+                    name: row.category_name.unwrap_or_default(),
+                    is_income: row.category_is_income,
+                    icon: row.category_icon.unwrap_or_else(|| "help_outline".to_string()),
+                    exclude_from_analysis: row.category_exclude,
+                }),
+                pocket: row.pocket_id.map(|id| PocketSummary {
+                    id,
+                    name: row.pocket_name.unwrap_or_default(),
+                    icon: row.pocket_icon.unwrap_or_else(|| "account_balance_wallet".to_string()),
+                    currency: row.pocket_currency.unwrap_or_default(),
+                }),
+                payee: row.payee_id.map(|id| PayeeSummary {
+                    id,
+                    name: row.payee_name.unwrap_or_default(),
+                }),
+                transfer_id: row.transfer_id,
+                tags: row.tags,
+                status: TransactionStatus::from_db(&row.status),
+                occurred_at: row.occurred_at,
+                created_at: row.created_at,
+            },
+            deleted_at: row.deleted_at,
+            purge_at: None,
+        })
+        .collect();
+
+        Ok(transactions)
+    }
+
+    pub async fn count_deleted(&self, user_id: Uuid) -> Result<i64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM transactions
+            WHERE user_id = $1 AND deleted_at IS NOT NULL
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.count)
+    }
+
+    /// Permanently remove a transaction that is already in the trash. For a transfer leg
+    /// both legs and the transfer itself are removed.
+    pub async fn delete_permanently(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let deleted = sqlx::query_scalar!(
+            r#"
+            WITH legs AS (
+                DELETE FROM transactions
+                WHERE user_id = $2 AND deleted_at IS NOT NULL
+                  AND (id = $1 OR transfer_id = (
+                      SELECT transfer_id FROM transactions WHERE id = $1 AND user_id = $2
+                  ))
+                RETURNING transfer_id
+            ), transfers_deleted AS (
+                DELETE FROM transfers WHERE id IN (SELECT transfer_id FROM legs)
+            )
+            SELECT COUNT(*) as "count!" FROM legs
+            "#,
+            id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(deleted as u64)
+    }
+
+    pub async fn empty_trash(&self, user_id: Uuid) -> Result<u64, AppError> {
+        let deleted = sqlx::query_scalar!(
+            r#"
+            WITH legs AS (
+                DELETE FROM transactions WHERE user_id = $1 AND deleted_at IS NOT NULL
+                RETURNING transfer_id
+            ), transfers_deleted AS (
+                DELETE FROM transfers WHERE id IN (SELECT transfer_id FROM legs)
+            )
+            SELECT COUNT(*) as "count!" FROM legs
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(deleted as u64)
+    }
+
+    /// Permanently remove soft-deleted transactions (for all users) deleted before `cutoff`
+    pub async fn purge_deleted_before(&self, cutoff: DateTime<Utc>) -> Result<u64, AppError> {
+        let deleted = sqlx::query_scalar!(
+            r#"
+            WITH legs AS (
+                DELETE FROM transactions WHERE deleted_at IS NOT NULL AND deleted_at < $1
+                RETURNING transfer_id
+            ), transfers_deleted AS (
+                DELETE FROM transfers WHERE id IN (SELECT transfer_id FROM legs)
+            )
+            SELECT COUNT(*) as "count!" FROM legs
+            "#,
+            cutoff
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(deleted as u64)
+    }
+
+    /// Net cash per pocket currency, in that currency, leaving out credit cards. A transaction
+    /// counts with its original amount when it was entered in the pocket's currency, and with
+    /// its base amount otherwise.
+    pub async fn get_cash_by_currency(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<CurrencyBalanceRow>, AppError> {
+        let rows = sqlx::query_as!(
+            CurrencyBalanceRow,
+            r#"
+            SELECT 
+                p.currency,
+                COALESCE(SUM(l.amount) FILTER (WHERE l.available), 0) as "balance!",
+                COALESCE(SUM(l.amount) FILTER (WHERE l.posted), 0) as "posted_balance!"
+            FROM pocket_ledger l
+            JOIN pockets p ON l.pocket_id = p.id
+            WHERE l.user_id = $1 AND p.kind = 'cash'
+            GROUP BY p.currency
+            "#,
+            user_id
         )
         .fetch_all(&self.pool)
         .await?;
         Ok(rows)
     }
 
-    pub async fn add_item(&self, user_id: Uuid, item: CreatePortfolioItem) -> Result<(), AppError> {
-        // Ensure asset exists (in case user passes custom ticker not in DB)
-        // For MVP, if ticker doesn't exist, we error out or insert basic one.
-        // User wants "predetermined assets", so strict check is better,
-        // BUT for now let's leniently insert if missing (defaulting source to YAHOO) or error.
-        // Given the requirement "only allow user to use predetermined assets", we should probably Fail if not found.
-        // But to keep it simple and safe:
-        let asset_exists = sqlx::query!("SELECT ticker FROM assets WHERE ticker = $1", item.ticker)
-            .fetch_optional(&self.pool)
-            .await?
-            .is_some();
+    /// Ids among `ids` locked by a reconciliation: the transaction, or the other leg of its
+    /// transfer, has been reconciled
+    pub async fn find_locked_ids(
+        &self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let rows = sqlx::query_scalar!(
+            r#"
+            SELECT t.id
+            FROM transactions t
+            WHERE t.user_id = $1 AND t.id = ANY($2)
+              AND EXISTS (
+                  SELECT 1 FROM transactions r
+                  WHERE r.user_id = $1 AND r.reconciled_at IS NOT NULL
+                    AND (r.id = t.id OR r.transfer_id = t.transfer_id)
+              )
+            "#,
+            user_id,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
 
-        if !asset_exists {
-            return Err(AppError::ValidationError(format!(
-                "Asset '{}' not supported",
-                item.ticker
-            )));
-        }
+    /// Allow edits to a reconciled transaction (and the other leg of its transfer) again; they
+    /// stay cleared
+    pub async fn unlock(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions SET reconciled_at = NULL
+            WHERE user_id = $2 AND reconciled_at IS NOT NULL
+              AND (id = $1 OR transfer_id = (
+                  SELECT transfer_id FROM transactions WHERE id = $1 AND user_id = $2
+              ))
+            "#,
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Move a transaction between pending, posted and void
+    pub async fn set_status_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        status: TransactionStatus,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "UPDATE transactions SET status = $3 WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL",
+            id,
+            user_id,
+            status.as_str()
+        )
+        .execute(conn)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Signed balance of each credit card, in the card's currency
+    pub async fn get_card_balances(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<CurrencyBalanceRow>, AppError> {
+        let rows = sqlx::query_as!(
+            CurrencyBalanceRow,
+            r#"
+            SELECT
+                p.currency,
+                COALESCE(SUM(l.amount) FILTER (WHERE l.available), 0) as "balance!",
+                COALESCE(SUM(l.amount) FILTER (WHERE l.posted), 0) as "posted_balance!"
+            FROM pocket_ledger l
+            JOIN pockets p ON l.pocket_id = p.id
+            WHERE l.user_id = $1 AND p.kind = 'credit_card'
+            GROUP BY p.id, p.currency
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Balance of a pocket in the pocket's own currency
+    pub async fn get_pocket_balance_tx(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        pocket_id: Uuid,
+    ) -> Result<Decimal, AppError> {
+        let result = sqlx::query!(
+            r#"
+            SELECT COALESCE(SUM(amount) FILTER (WHERE available), 0) as "balance!"
+            FROM pocket_ledger
+            WHERE user_id = $1 AND pocket_id = $2
+            "#,
+            user_id,
+            pocket_id
+        )
+        .fetch_one(conn)
+        .await?;
+        Ok(result.balance)
+    }
+
+    /// Insert a transfer; the leg ids are filled in with `set_transfer_legs_tx`
+    pub async fn create_transfer_tx(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        new: &NewTransfer,
+    ) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO transfers (
+                user_id, source_pocket_id, destination_pocket_id, amount, destination_amount,
+                exchange_rate, fee, description, occurred_at
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id
+            "#,
+            user_id,
+            new.source_pocket_id,
+            new.destination_pocket_id,
+            new.amount,
+            new.destination_amount,
+            new.exchange_rate,
+            new.fee,
+            new.description,
+            new.occurred_at
+        )
+        .fetch_one(conn)
+        .await?;
+        Ok(id)
+    }
 
+    pub async fn set_transfer_legs_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        out_transaction_id: Uuid,
+        in_transaction_id: Uuid,
+    ) -> Result<(), AppError> {
         sqlx::query!(
-            "INSERT INTO portfolio (user_id, ticker, quantity, avg_buy_price) VALUES ($1, $2, $3, $4)",
+            "UPDATE transfers SET out_transaction_id = $2, in_transaction_id = $3 WHERE id = $1",
+            id,
+            out_transaction_id,
+            in_transaction_id
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Lock a transfer whose legs are not in the trash
+    pub async fn lock_transfer_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<TransferRow, AppError> {
+        let transfer = sqlx::query_as!(
+            TransferRow,
+            r#"
+            SELECT tr.id, tr.source_pocket_id, tr.destination_pocket_id, tr.amount,
+                tr.destination_amount, tr.exchange_rate, tr.fee, tr.description, tr.occurred_at,
+                tr.out_transaction_id as "out_transaction_id!",
+                tr.in_transaction_id as "in_transaction_id!"
+            FROM transfers tr
+            JOIN transactions o ON o.id = tr.out_transaction_id AND o.deleted_at IS NULL
+            WHERE tr.id = $1 AND tr.user_id = $2
+            FOR UPDATE OF tr
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::NotFoundError("Transfer not found".to_string()))?;
+        Ok(transfer)
+    }
+
+    pub async fn update_transfer_tx(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        transfer: &TransferRow,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE transfers
+            SET source_pocket_id = $3, destination_pocket_id = $4, amount = $5,
+                destination_amount = $6, exchange_rate = $7, fee = $8,
+                description = $9, occurred_at = $10
+            WHERE id = $1 AND user_id = $2
+            "#,
+            transfer.id,
             user_id,
-            item.ticker,
-            item.quantity,
-            item.avg_buy_price
+            transfer.source_pocket_id,
+            transfer.destination_pocket_id,
+            transfer.amount,
+            transfer.destination_amount,
+            transfer.exchange_rate,
+            transfer.fee,
+            transfer.description,
+            transfer.occurred_at
         )
-        .execute(&self.pool)
+        .execute(conn)
         .await?;
         Ok(())
     }
 
-    pub async fn get_all_joined(
+    /// Transfers whose legs are not in the trash, newest first
+    pub async fn get_transfers(
         &self,
         user_id: Uuid,
-    ) -> Result<Vec<crate::schemas::PortfolioJoinedRow>, AppError> {
-        // Explicitly define the record type to satisfy the compiler
-        struct Row {
-            ticker: Option<String>,
-            name: String,
-            quantity: Decimal,
-            avg_buy_price: Decimal,
-            current_price: Option<Decimal>,
-            source: Option<String>,
-            api_ticker: Option<String>,
-            currency: Option<String>,
-            icon_url: Option<String>,
-        }
+        id: Option<Uuid>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Transfer>, AppError> {
+        let transfers = sqlx::query!(
+            r#"
+            SELECT tr.id, tr.amount, tr.destination_amount, tr.exchange_rate, tr.fee,
+                tr.description, tr.occurred_at, tr.created_at,
+                tr.out_transaction_id as "out_transaction_id!",
+                tr.in_transaction_id as "in_transaction_id!",
+                sp.id as source_id, sp.name as source_name,
+                COALESCE(sp.icon, 'account_balance_wallet') as "source_icon!",
+                sp.currency as source_currency,
+                dp.id as destination_id, dp.name as destination_name,
+                COALESCE(dp.icon, 'account_balance_wallet') as "destination_icon!",
+                dp.currency as destination_currency
+            FROM transfers tr
+            JOIN transactions o ON o.id = tr.out_transaction_id AND o.deleted_at IS NULL
+            JOIN pockets sp ON sp.id = tr.source_pocket_id
+            JOIN pockets dp ON dp.id = tr.destination_pocket_id
+            WHERE tr.user_id = $1 AND ($2::uuid IS NULL OR tr.id = $2)
+            ORDER BY tr.occurred_at DESC, tr.created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+            user_id,
+            id,
+            limit,
+            offset
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| Transfer {
+            id: row.id,
+            source_pocket: PocketSummary {
+                id: row.source_id,
+                name: row.source_name,
+                icon: row.source_icon,
+                currency: row.source_currency,
+            },
+            destination_pocket: PocketSummary {
+                id: row.destination_id,
+                name: row.destination_name,
+                icon: row.destination_icon,
+                currency: row.destination_currency,
+            },
+            amount: row.amount,
+            destination_amount: row.destination_amount,
+            exchange_rate: row.exchange_rate,
+            fee: row.fee,
+            description: row.description,
+            occurred_at: row.occurred_at,
+            out_transaction_id: row.out_transaction_id,
+            in_transaction_id: row.in_transaction_id,
+            created_at: row.created_at,
+        })
+        .collect();
+        Ok(transfers)
+    }
 
-        let rows = sqlx::query_as!(
-            Row,
+    pub async fn count_transfers(&self, user_id: Uuid) -> Result<i64, AppError> {
+        let count = sqlx::query_scalar!(
             r#"
-            SELECT 
-                p.ticker, 
-                a.name, 
-                p.quantity, 
-                p.avg_buy_price, 
-                a.current_price,
-                a.source,
-                a.api_ticker,
-                a.currency,
-                a.icon_url
-            FROM portfolio p
-            LEFT JOIN assets a ON p.ticker = a.ticker
-            WHERE p.user_id = $1
+            SELECT COUNT(*) as "count!"
+            FROM transfers tr
+            JOIN transactions o ON o.id = tr.out_transaction_id AND o.deleted_at IS NULL
+            WHERE tr.user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(count)
+    }
+
+    pub async fn get_transfer(&self, id: Uuid, user_id: Uuid) -> Result<Transfer, AppError> {
+        self.get_transfers(user_id, Some(id), 1, 0)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(AppError::NotFoundError("Transfer not found".to_string()))
+    }
+
+    /// Returns the ids (out of `ids`) that are legs of a transfer
+    pub async fn find_transfer_leg_ids(
+        &self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<Uuid>, AppError> {
+        let rows = sqlx::query_scalar!(
+            "SELECT id FROM transactions WHERE user_id = $1 AND id = ANY($2) AND transfer_id IS NOT NULL",
+            user_id,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+}
+
+pub struct PortfolioRepository {
+    pool: PgPool,
+}
+
+impl PortfolioRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_total_invested(&self, user_id: Uuid) -> Result<Decimal, AppError> {
+        let result = sqlx::query!(
+            r#"
+            SELECT COALESCE(SUM(p.quantity * a.current_price), 0) as "total_invested!"
+            FROM portfolio p
+            JOIN assets a ON p.ticker = a.ticker
+            WHERE p.user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(result.total_invested)
+    }
+
+    pub async fn get_tickers(&self, user_id: Uuid) -> Result<Vec<String>, AppError> {
+        let rows = sqlx::query!(
+            "SELECT DISTINCT ticker FROM portfolio WHERE user_id = $1",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().filter_map(|r| r.ticker).collect())
+    }
+
+    pub async fn update_asset_price(
+        &self,
+        ticker: &str,
+        price: Decimal,
+        currency: &str,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE assets SET current_price = $1, currency = $2, last_updated = NOW() WHERE ticker = $3",
+            price,
+            currency,
+            ticker
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_asset_icon(&self, ticker: &str, icon_url: &str) -> Result<(), AppError> {
+        sqlx::query!(
+            "UPDATE assets SET icon_url = $1 WHERE ticker = $2",
+            icon_url,
+            ticker
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_all_assets(&self) -> Result<Vec<crate::schemas::Asset>, AppError> {
+        let rows = sqlx::query_as!(
+            crate::schemas::Asset,
+            r#"
+            SELECT 
+                ticker, 
+                name, 
+                asset_type,
+                api_ticker,
+                source,
+                current_price,
+                currency,
+                icon_url
+            FROM assets
+            ORDER BY name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn add_item(&self, user_id: Uuid, item: CreatePortfolioItem) -> Result<(), AppError> {
+        // Ensure asset exists (in case user passes custom ticker not in DB)
+        // For MVP, if ticker doesn't exist, we error out or insert basic one.
+        // User wants "predetermined assets", so strict check is better,
+        // BUT for now let's leniently insert if missing (defaulting source to YAHOO) or error.
+        // Given the requirement "only allow user to use predetermined assets", we should probably Fail if not found.
+        // But to keep it simple and safe:
+        let asset_exists = sqlx::query!("SELECT ticker FROM assets WHERE ticker = $1", item.ticker)
+            .fetch_optional(&self.pool)
+            .await?
+            .is_some();
+
+        if !asset_exists {
+            return Err(AppError::ValidationError(format!(
+                "Asset '{}' not supported",
+                item.ticker
+            )));
+        }
+
+        sqlx::query!(
+            "INSERT INTO portfolio (user_id, ticker, quantity, avg_buy_price) VALUES ($1, $2, $3, $4)",
+            user_id,
+            item.ticker,
+            item.quantity,
+            item.avg_buy_price
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_all_joined(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<crate::schemas::PortfolioJoinedRow>, AppError> {
+        // Explicitly define the record type to satisfy the compiler
+        struct Row {
+            ticker: Option<String>,
+            name: String,
+            quantity: Decimal,
+            avg_buy_price: Decimal,
+            current_price: Option<Decimal>,
+            source: Option<String>,
+            api_ticker: Option<String>,
+            currency: Option<String>,
+            icon_url: Option<String>,
+        }
+
+        let rows = sqlx::query_as!(
+            Row,
+            r#"
+            SELECT 
+                p.ticker, 
+                a.name, 
+                p.quantity, 
+                p.avg_buy_price, 
+                a.current_price,
+                a.source,
+                a.api_ticker,
+                a.currency,
+                a.icon_url
+            FROM portfolio p
+            LEFT JOIN assets a ON p.ticker = a.ticker
+            WHERE p.user_id = $1
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let result = rows
+            .into_iter()
+            .map(|r| crate::schemas::PortfolioJoinedRow {
+                ticker: r.ticker.unwrap_or_default(),
+                name: r.name,
+                quantity: r.quantity,
+                avg_buy_price: r.avg_buy_price,
+                current_price: r.current_price.unwrap_or(Decimal::ZERO),
+                source: r.source,
+                api_ticker: r.api_ticker,
+                currency: r.currency,
+                icon_url: r.icon_url,
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    pub async fn get_asset(&self, ticker: &str) -> Result<Option<crate::schemas::Asset>, AppError> {
+        let asset = sqlx::query_as!(
+            crate::schemas::Asset,
+            r#"
+            SELECT 
+                ticker, 
+                name, 
+                asset_type,
+                api_ticker,
+                source,
+                currency,
+                current_price,
+                icon_url
+            FROM assets
+            WHERE ticker = $1
+            "#,
+            ticker
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(asset)
+    }
+
+    pub async fn delete(&self, user_id: Uuid, ticker: &str) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM portfolio WHERE user_id = $1 AND ticker = $2",
+            user_id,
+            ticker
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        ticker: &str,
+        quantity: Option<Decimal>,
+        avg_buy_price: Option<Decimal>,
+    ) -> Result<(), AppError> {
+        // Simple dynamic query via COALESCE
+        // Since we're dealing with "if null dont change", COALESCE works if we pass NULL for None.
+        sqlx::query!(
+            r#"
+            UPDATE portfolio 
+            SET 
+                quantity = COALESCE($3, quantity), 
+                avg_buy_price = COALESCE($4, avg_buy_price)
+            WHERE user_id = $1 AND ticker = $2
+            "#,
+            user_id,
+            ticker,
+            quantity,
+            avg_buy_price
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct PocketRepository {
+    pool: PgPool,
+}
+
+impl PocketRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        description: Option<String>,
+        icon: Option<String>,
+        currency: &str,
+        credit_card: Option<&CreditCard>,
+    ) -> Result<Uuid, AppError> {
+        let icon = icon.unwrap_or_else(|| "account_balance_wallet".to_string());
+        let kind = if credit_card.is_some() {
+            PocketKind::CreditCard
+        } else {
+            PocketKind::Cash
+        };
+
+        let mut tx = self.pool.begin().await?;
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO pockets (user_id, name, description, icon, currency, kind)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+            user_id,
+            name,
+            description,
+            icon,
+            currency,
+            kind.as_str()
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if let Some(card) = credit_card {
+            Self::set_credit_card_tx(&mut tx, id, card).await?;
+        }
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    pub async fn get_credit_card(&self, pocket_id: Uuid) -> Result<CreditCard, AppError> {
+        sqlx::query_as!(
+            CreditCard,
+            r#"
+            SELECT statement_day, due_days, credit_limit, minimum_payment_percent, minimum_payment_floor
+            FROM credit_cards
+            WHERE pocket_id = $1
+            "#,
+            pocket_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError(
+            "Credit card settings not found".to_string(),
+        ))
+    }
+
+    pub async fn set_credit_card(
+        &self,
+        pocket_id: Uuid,
+        card: &CreditCard,
+    ) -> Result<(), AppError> {
+        let mut conn = self.pool.acquire().await?;
+        Self::set_credit_card_tx(&mut conn, pocket_id, card).await
+    }
+
+    async fn set_credit_card_tx(
+        conn: &mut PgConnection,
+        pocket_id: Uuid,
+        card: &CreditCard,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO credit_cards
+                (pocket_id, statement_day, due_days, credit_limit, minimum_payment_percent, minimum_payment_floor)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (pocket_id) DO UPDATE SET
+                statement_day = EXCLUDED.statement_day,
+                due_days = EXCLUDED.due_days,
+                credit_limit = EXCLUDED.credit_limit,
+                minimum_payment_percent = EXCLUDED.minimum_payment_percent,
+                minimum_payment_floor = EXCLUDED.minimum_payment_floor
+            "#,
+            pocket_id,
+            card.statement_day,
+            card.due_days,
+            card.credit_limit,
+            card.minimum_payment_percent,
+            card.minimum_payment_floor
+        )
+        .execute(&mut *conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Balance of a card at the end of `closing_date`, and what was credited to it afterwards
+    pub async fn get_card_activity(
+        &self,
+        pocket_id: Uuid,
+        closing_date: NaiveDate,
+    ) -> Result<CardActivityRow, AppError> {
+        let row = sqlx::query_as!(
+            CardActivityRow,
+            r#"
+            SELECT
+                COALESCE(SUM(
+                    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                    * CASE WHEN c.is_income THEN 1 ELSE -1 END
+                ) FILTER (WHERE t.occurred_at < $2::date + 1), 0) as "balance_at_closing!",
+                COALESCE(SUM(
+                    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                ) FILTER (WHERE c.is_income AND t.occurred_at >= $2::date + 1), 0) as "credits_since_closing!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            JOIN pockets p ON t.pocket_id = p.id
+            WHERE t.pocket_id = $1 AND t.deleted_at IS NULL AND t.status = 'posted'
+            "#,
+            pocket_id,
+            closing_date
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(row)
+    }
+
+    pub async fn create_default_for_user(
+        &self,
+        user_id: Uuid,
+        currency: &str,
+    ) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO pockets (user_id, name, is_default, currency)
+            VALUES ($1, 'Main', TRUE, $2)
+            RETURNING id
+            "#,
+            user_id,
+            currency
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn get_all(
+        &self,
+        user_id: Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<Pocket>, AppError> {
+        let pockets = sqlx::query_as!(
+            Pocket,
+            r#"
+            SELECT 
+                id, name, description, 
+                COALESCE(icon, 'account_balance_wallet') as "icon!",
+                currency,
+                kind,
+                COALESCE(is_default, FALSE) as "is_default!",
+                archived_at,
+                created_at,
+                b.balance as "balance!",
+                b.posted_balance as "posted_balance!"
+            FROM pockets p
+            CROSS JOIN LATERAL pocket_balances(p.id) b
+            WHERE user_id = $1 AND ($2 OR archived_at IS NULL)
+            ORDER BY is_default DESC, name ASC
+            "#,
+            user_id,
+            include_archived
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(pockets)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Pocket, AppError> {
+        let pocket = sqlx::query_as!(
+            Pocket,
+            r#"
+            SELECT 
+                id, name, description, 
+                COALESCE(icon, 'account_balance_wallet') as "icon!",
+                currency,
+                kind,
+                COALESCE(is_default, FALSE) as "is_default!",
+                archived_at,
+                created_at,
+                b.balance as "balance!",
+                b.posted_balance as "posted_balance!"
+            FROM pockets p
+            CROSS JOIN LATERAL pocket_balances(p.id) b
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Pocket not found".to_string()))?;
+        Ok(pocket)
+    }
+
+    pub async fn get_default(&self, user_id: Uuid) -> Result<Pocket, AppError> {
+        let pocket = sqlx::query_as!(
+            Pocket,
+            r#"
+            SELECT 
+                id, name, description, 
+                COALESCE(icon, 'account_balance_wallet') as "icon!",
+                currency,
+                kind,
+                COALESCE(is_default, FALSE) as "is_default!",
+                archived_at,
+                created_at,
+                b.balance as "balance!",
+                b.posted_balance as "posted_balance!"
+            FROM pockets p
+            CROSS JOIN LATERAL pocket_balances(p.id) b
+            WHERE user_id = $1 AND is_default = TRUE
+            "#,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError(
+            "Default pocket not found".to_string(),
+        ))?;
+        Ok(pocket)
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        name: Option<String>,
+        description: Option<String>,
+        icon: Option<String>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            UPDATE pockets 
+            SET 
+                name = COALESCE($3, name),
+                description = COALESCE($4, description),
+                icon = COALESCE($5, icon)
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            name,
+            description,
+            icon
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        // First check if this is a default pocket
+        let pocket = self.get_by_id(id, user_id).await?;
+        if pocket.is_default {
+            return Err(AppError::ValidationError(
+                "Cannot delete the default pocket".to_string(),
+            ));
+        }
+
+        let result = sqlx::query!(
+            "DELETE FROM pockets WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Whether any transaction, including ones in the trash, still points at the pocket
+    pub async fn has_transactions(&self, id: Uuid, user_id: Uuid) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM transactions WHERE pocket_id = $1 AND user_id = $2) as "exists!""#,
+            id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(exists)
+    }
+
+    /// Whether reassigning pocket `id` to `target_id` would touch a reconciled transaction:
+    /// one in the pocket itself, or a leg of a transfer between the two pockets
+    pub async fn has_reconciled_for_reassign(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(
+                SELECT 1 FROM transactions t
+                LEFT JOIN transfers tr ON tr.id = t.transfer_id
+                WHERE t.user_id = $3
+                  AND t.reconciled_at IS NOT NULL
+                  AND (t.pocket_id = $1
+                    OR (tr.source_pocket_id = $1 AND tr.destination_pocket_id = $2)
+                    OR (tr.source_pocket_id = $2 AND tr.destination_pocket_id = $1))
+            ) as "exists!"
+            "#,
+            id,
+            target_id,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(exists)
+    }
+
+    /// Move every transaction, transfer, rule, bill, goal, envelope allocation and card setting
+    /// of pocket `id` to `target_id`, then delete the pocket, all in one SQL transaction.
+    /// Transfers between the two pockets would become transfers to itself, so they are removed
+    /// together with their legs; the pocket's reconciliations are removed with it. Fails when
+    /// both pockets have a goal. Returns None when the pocket doesn't exist.
+    pub async fn reassign_and_delete(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        target_id: Uuid,
+    ) -> Result<Option<DeletePocketResponse>, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let removed_transfers = sqlx::query!(
+            r#"
+            WITH internal AS (
+                SELECT id FROM transfers
+                WHERE user_id = $3
+                  AND ((source_pocket_id = $1 AND destination_pocket_id = $2)
+                    OR (source_pocket_id = $2 AND destination_pocket_id = $1))
+            ), legs AS (
+                DELETE FROM transactions WHERE transfer_id IN (SELECT id FROM internal)
+            )
+            DELETE FROM transfers WHERE id IN (SELECT id FROM internal)
+            "#,
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        sqlx::query!(
+            "UPDATE transactions SET pocket_id = $2 WHERE pocket_id = $1 AND user_id = $3",
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE transfers
+            SET source_pocket_id = CASE WHEN source_pocket_id = $1 THEN $2 ELSE source_pocket_id END,
+                destination_pocket_id = CASE WHEN destination_pocket_id = $1 THEN $2 ELSE destination_pocket_id END
+            WHERE user_id = $3 AND (source_pocket_id = $1 OR destination_pocket_id = $1)
+            "#,
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE categorization_rules
+            SET pocket_id = CASE WHEN pocket_id = $1 THEN $2 ELSE pocket_id END,
+                set_pocket_id = CASE WHEN set_pocket_id = $1 THEN $2 ELSE set_pocket_id END
+            WHERE user_id = $3 AND (pocket_id = $1 OR set_pocket_id = $1)
+            "#,
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE bills SET pocket_id = $2 WHERE pocket_id = $1 AND user_id = $3",
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // A pocket carries at most one goal, so the target must not have its own
+        let moved_goals = sqlx::query!(
+            "UPDATE goals SET pocket_id = $2 WHERE pocket_id = $1 AND user_id = $3",
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| unique_violation(e, "Both pockets have a goal; delete one of them first"))?
+        .rows_affected();
+
+        let merged_allocations = sqlx::query!(
+            r#"
+            WITH moved AS (
+                DELETE FROM envelope_allocations
+                WHERE pocket_id = $1 AND user_id = $3
+                RETURNING user_id, month, amount
+            )
+            INSERT INTO envelope_allocations (user_id, month, pocket_id, amount)
+            SELECT user_id, month, $2, amount FROM moved
+            ON CONFLICT (user_id, month, pocket_id) WHERE pocket_id IS NOT NULL
+            DO UPDATE SET amount = envelope_allocations.amount + EXCLUDED.amount
+            "#,
+            id,
+            target_id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let moved_card_settings = sqlx::query!(
+            r#"
+            UPDATE credit_cards SET pocket_id = $2
+            WHERE pocket_id = $1
+              AND NOT EXISTS (SELECT 1 FROM credit_cards WHERE pocket_id = $2)
+            "#,
+            id,
+            target_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let removed_card_settings =
+            sqlx::query!("DELETE FROM credit_cards WHERE pocket_id = $1", id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+        let removed_reconciliations = sqlx::query!(
+            "DELETE FROM reconciliations WHERE pocket_id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        let result = sqlx::query!(
+            "DELETE FROM pockets WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        tx.commit().await?;
+        Ok(Some(DeletePocketResponse {
+            removed_transfers,
+            removed_reconciliations,
+            moved_goals,
+            merged_allocations,
+            moved_card_settings,
+            removed_card_settings,
+        }))
+    }
+
+    /// Archive (`archived = true`) or unarchive a pocket
+    pub async fn set_archived(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        archived: bool,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE pockets
+            SET archived_at = CASE WHEN $3 THEN COALESCE(archived_at, NOW()) ELSE NULL END
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            archived
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Closing available balance (in the pocket's currency) of every day or month bucket between
+    /// `start_date` and `end_date`, including buckets without transactions. Pending income is
+    /// left out, as in the pocket's `balance`.
+    pub async fn get_balance_history(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        interval: BalanceInterval,
+    ) -> Result<Vec<BalancePoint>, AppError> {
+        let points = sqlx::query_as!(
+            BalancePoint,
+            r#"
+            WITH deltas AS (
+                SELECT
+                    date_trunc($5, occurred_at AT TIME ZONE 'UTC')::date as bucket,
+                    SUM(amount) as delta
+                FROM pocket_ledger
+                WHERE pocket_id = $1 AND user_id = $2 AND available
+                GROUP BY 1
+            ), buckets AS (
+                SELECT generate_series(
+                    date_trunc($5, $3::timestamptz AT TIME ZONE 'UTC'),
+                    date_trunc($5, $4::timestamptz AT TIME ZONE 'UTC'),
+                    ('1 ' || $5)::interval
+                )::date as bucket
+            )
+            SELECT
+                b.bucket as "date!",
+                COALESCE((SELECT SUM(d.delta) FROM deltas d WHERE d.bucket <= b.bucket), 0) as "balance!"
+            FROM buckets b
+            ORDER BY b.bucket
+            "#,
+            id,
+            user_id,
+            start_date,
+            end_date,
+            interval.as_str()
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(points)
+    }
+
+    /// Available balance of a pocket, in its currency, from transactions that occurred before
+    /// `before`. Like the pocket's `balance`, pending income doesn't count yet.
+    pub async fn get_balance_before(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        before: DateTime<Utc>,
+    ) -> Result<Decimal, AppError> {
+        let balance = sqlx::query_scalar!(
+            r#"
+            SELECT COALESCE(SUM(amount), 0) as "balance!"
+            FROM pocket_ledger
+            WHERE pocket_id = $1 AND user_id = $2 AND available AND occurred_at < $3
+            "#,
+            id,
+            user_id,
+            before
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(balance)
+    }
+
+    /// Per-category totals of a pocket's transactions within the period. Transfer legs,
+    /// including ones recorded before transfers were linked, are flagged separately. Pending
+    /// income is left out so the totals lead from one available balance to the next.
+    pub async fn get_statement_rows(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> Result<Vec<StatementRow>, AppError> {
+        let rows = sqlx::query_as!(
+            StatementRow,
+            r#"
+            SELECT 
+                c.id as category_id,
+                c.name as category_name,
+                COALESCE(c.is_income, FALSE) as "is_income!",
+                (t.transfer_id IS NOT NULL OR c.name IN ('Transfer In', 'Transfer Out')) as "is_transfer!",
+                SUM(
+                    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                ) as "total!",
+                COUNT(*) as "transaction_count!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            JOIN pockets p ON t.pocket_id = p.id
+            WHERE t.pocket_id = $1 AND t.user_id = $2 AND t.deleted_at IS NULL AND t.status <> 'void'
+              AND (t.status = 'posted' OR NOT c.is_income)
+              AND t.occurred_at >= $3 AND t.occurred_at <= $4
+            GROUP BY c.id, c.name, c.is_income, 4
+            ORDER BY 5 DESC
+            "#,
+            id,
+            user_id,
+            start_date,
+            end_date
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Lock a pocket row so balance checks against it are serialized
+    pub async fn lock_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query_scalar!(
+            "SELECT id FROM pockets WHERE id = $1 AND user_id = $2 FOR UPDATE",
+            id,
+            user_id
+        )
+        .fetch_optional(conn)
+        .await?
+        .ok_or(AppError::NotFoundError("Pocket not found".to_string()))?;
+        Ok(())
+    }
+}
+
+pub struct RuleRepository {
+    pool: PgPool,
+}
+
+impl RuleRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// All of a user's rules in evaluation order
+    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<CategorizationRule>, AppError> {
+        let rules = sqlx::query_as!(
+            CategorizationRule,
+            r#"
+            SELECT 
+                id, name, priority, is_active,
+                description_contains, description_regex, min_amount, max_amount,
+                pocket_id, currency, set_category_id, set_pocket_id, add_tags,
+                created_at
+            FROM categorization_rules
+            WHERE user_id = $1
+            ORDER BY priority ASC, created_at ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rules)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<CategorizationRule, AppError> {
+        let rule = sqlx::query_as!(
+            CategorizationRule,
+            r#"
+            SELECT 
+                id, name, priority, is_active,
+                description_contains, description_regex, min_amount, max_amount,
+                pocket_id, currency, set_category_id, set_pocket_id, add_tags,
+                created_at
+            FROM categorization_rules
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Rule not found".to_string()))?;
+        Ok(rule)
+    }
+
+    pub async fn create(&self, user_id: Uuid, rule: &RuleRequest) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO categorization_rules (
+                user_id, name, priority, is_active,
+                description_contains, description_regex, min_amount, max_amount,
+                pocket_id, currency, set_category_id, set_pocket_id, add_tags
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id
+            "#,
+            user_id,
+            rule.name,
+            rule.priority,
+            rule.is_active,
+            rule.description_contains,
+            rule.description_regex,
+            rule.min_amount,
+            rule.max_amount,
+            rule.pocket_id,
+            rule.currency,
+            rule.set_category_id,
+            rule.set_pocket_id,
+            &rule.add_tags
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// Full replacement of a rule's definition
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        rule: &RuleRequest,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE categorization_rules
+            SET 
+                name = $3,
+                priority = $4,
+                is_active = $5,
+                description_contains = $6,
+                description_regex = $7,
+                min_amount = $8,
+                max_amount = $9,
+                pocket_id = $10,
+                currency = $11,
+                set_category_id = $12,
+                set_pocket_id = $13,
+                add_tags = $14
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            rule.name,
+            rule.priority,
+            rule.is_active,
+            rule.description_contains,
+            rule.description_regex,
+            rule.min_amount,
+            rule.max_amount,
+            rule.pocket_id,
+            rule.currency,
+            rule.set_category_id,
+            rule.set_pocket_id,
+            &rule.add_tags
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM categorization_rules WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct DuplicateRepository {
+    pool: PgPool,
+}
+
+impl DuplicateRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Active transactions in the same pocket, time window and direction (income vs expense)
+    /// as `new` that it is compared with
+    pub async fn find_candidates_tx(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        exclude_id: Uuid,
+        new: &NewTransaction,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<DuplicateCandidateRow>, AppError> {
+        // A refund and the purchase it reverses share amount, date and description,
+        // so only transactions whose category points the same way can be duplicates
+        let rows = sqlx::query_as!(
+            DuplicateCandidateRow,
+            r#"
+            SELECT t.id, t.amount, t.description, t.occurred_at, t.pocket_id
+            FROM transactions t
+            JOIN categories c ON c.id = t.category_id
+            WHERE t.user_id = $1
+              AND t.id <> $2
+              AND t.pocket_id = $3
+              AND t.occurred_at BETWEEN $4 AND $5
+              AND t.deleted_at IS NULL
+              AND t.status <> 'void'
+              AND t.transfer_id IS NULL
+              AND COALESCE(c.is_income, FALSE) = (
+                  SELECT COALESCE(is_income, FALSE) FROM categories WHERE id = $6
+              )
+            "#,
+            user_id,
+            exclude_id,
+            new.pocket_id,
+            from,
+            to,
+            new.category_id
+        )
+        .fetch_all(conn)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn flag_tx(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        transaction_id: Uuid,
+        duplicate_of_id: Uuid,
+        score: i32,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO transaction_duplicates (user_id, transaction_id, duplicate_of_id, score)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (transaction_id, duplicate_of_id) DO NOTHING
+            "#,
+            user_id,
+            transaction_id,
+            duplicate_of_id,
+            score
+        )
+        .execute(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Pending pairs where neither side has been deleted, most likely first
+    pub async fn get_pending(&self, user_id: Uuid) -> Result<Vec<DuplicatePair>, AppError> {
+        let pairs = sqlx::query!(
+            r#"
+            SELECT 
+                d.id, d.score, d.created_at,
+                t.id as t_id, t.amount as t_amount, t.description as t_description,
+                t.category_id as t_category_id, t.occurred_at as t_occurred_at,
+                t.created_at as t_created_at, t.pocket_id,
+                o.id as o_id, o.amount as o_amount, o.description as o_description,
+                o.category_id as o_category_id, o.occurred_at as o_occurred_at,
+                o.created_at as o_created_at
+            FROM transaction_duplicates d
+            JOIN transactions t ON d.transaction_id = t.id
+            JOIN transactions o ON d.duplicate_of_id = o.id
+            WHERE d.user_id = $1
+              AND d.status = 'pending'
+              AND t.deleted_at IS NULL
+              AND o.deleted_at IS NULL
+            ORDER BY d.score DESC, d.created_at DESC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| DuplicatePair {
+            id: row.id,
+            score: row.score,
+            pocket_id: row.pocket_id,
+            transaction: DuplicateSide {
+                id: row.t_id,
+                amount: row.t_amount,
+                description: row.t_description,
+                category_id: row.t_category_id,
+                occurred_at: row.t_occurred_at,
+                created_at: row.t_created_at,
+            },
+            duplicate_of: DuplicateSide {
+                id: row.o_id,
+                amount: row.o_amount,
+                description: row.o_description,
+                category_id: row.o_category_id,
+                occurred_at: row.o_occurred_at,
+                created_at: row.o_created_at,
+            },
+            created_at: row.created_at,
+        })
+        .collect();
+        Ok(pairs)
+    }
+
+    pub async fn get_pending_pair(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<DuplicatePairRow, AppError> {
+        let pair = sqlx::query_as!(
+            DuplicatePairRow,
+            r#"
+            SELECT id, transaction_id, duplicate_of_id
+            FROM transaction_duplicates
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError(
+            "Duplicate pair not found".to_string(),
+        ))?;
+        Ok(pair)
+    }
+
+    /// Mark a pending pair as `dismissed` or `merged`
+    pub async fn resolve_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        status: &str,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE transaction_duplicates
+            SET status = $3, resolved_at = NOW()
+            WHERE id = $1 AND user_id = $2 AND status = 'pending'
+            "#,
+            id,
+            user_id,
+            status
+        )
+        .execute(conn)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn resolve(&self, id: Uuid, user_id: Uuid, status: &str) -> Result<u64, AppError> {
+        let mut conn = self.pool.acquire().await?;
+        self.resolve_tx(&mut conn, id, user_id, status).await
+    }
+}
+
+pub struct GoalRepository {
+    pool: PgPool,
+}
+
+impl GoalRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<GoalRow>, AppError> {
+        let goals = sqlx::query_as!(
+            GoalRow,
+            r#"
+            SELECT
+                g.id, g.pocket_id,
+                p.name as pocket_name,
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon!",
+                p.currency as pocket_currency,
+                g.name, g.target_amount, g.target_date, g.monthly_contribution,
+                b.balance as "balance!",
+                g.created_at
+            FROM goals g
+            JOIN pockets p ON g.pocket_id = p.id
+            CROSS JOIN LATERAL pocket_balances(p.id) b
+            WHERE g.user_id = $1
+            ORDER BY g.target_date ASC NULLS LAST, g.name ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(goals)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<GoalRow, AppError> {
+        sqlx::query_as!(
+            GoalRow,
+            r#"
+            SELECT
+                g.id, g.pocket_id,
+                p.name as pocket_name,
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon!",
+                p.currency as pocket_currency,
+                g.name, g.target_amount, g.target_date, g.monthly_contribution,
+                b.balance as "balance!",
+                g.created_at
+            FROM goals g
+            JOIN pockets p ON g.pocket_id = p.id
+            CROSS JOIN LATERAL pocket_balances(p.id) b
+            WHERE g.id = $1 AND g.user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Goal not found".to_string()))
+    }
+
+    /// Net flow per pocket and calendar month (UTC) for months in `[since, until)`,
+    /// in each pocket's currency. Months without activity are omitted.
+    pub async fn get_monthly_flows(
+        &self,
+        user_id: Uuid,
+        pocket_ids: &[Uuid],
+        since: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<MonthlyFlowRow>, AppError> {
+        let flows = sqlx::query_as!(
+            MonthlyFlowRow,
+            r#"
+            SELECT
+                t.pocket_id as "pocket_id!",
+                date_trunc('month', t.occurred_at AT TIME ZONE 'UTC')::date as "month!",
+                SUM(
+                    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                    * CASE WHEN c.is_income THEN 1 ELSE -1 END
+                ) as "net!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            JOIN pockets p ON t.pocket_id = p.id
+            WHERE t.user_id = $1 AND t.pocket_id = ANY($2) AND t.deleted_at IS NULL AND t.status <> 'void'
+              AND t.occurred_at AT TIME ZONE 'UTC' >= $3::date
+              AND t.occurred_at AT TIME ZONE 'UTC' < $4::date
+            GROUP BY 1, 2
+            ORDER BY 2
+            "#,
+            user_id,
+            pocket_ids,
+            since,
+            until
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(flows)
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        pocket_id: Uuid,
+        name: &str,
+        target_amount: Decimal,
+        target_date: Option<NaiveDate>,
+        monthly_contribution: Option<Decimal>,
+    ) -> Result<Uuid, AppError> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO goals (user_id, pocket_id, name, target_amount, target_date, monthly_contribution)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+            user_id,
+            pocket_id,
+            name,
+            target_amount,
+            target_date,
+            monthly_contribution
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| unique_violation(e, "This pocket already has a goal"))
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        name: Option<String>,
+        target_amount: Option<Decimal>,
+        target_date: Option<NaiveDate>,
+        monthly_contribution: Option<Decimal>,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE goals
+            SET
+                name = COALESCE($3, name),
+                target_amount = COALESCE($4, target_amount),
+                target_date = COALESCE($5, target_date),
+                monthly_contribution = COALESCE($6, monthly_contribution)
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            name,
+            target_amount,
+            target_date,
+            monthly_contribution
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM goals WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct BudgetRepository {
+    pool: PgPool,
+}
+
+impl BudgetRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<Budget>, AppError> {
+        let budgets = sqlx::query_as!(
+            Budget,
+            r#"
+            SELECT
+                b.id, b.category_id,
+                c.name as category_name,
+                COALESCE(c.icon, 'help_outline') as "category_icon!",
+                b.amount, b.rollover, b.starts_on, b.created_at
+            FROM budgets b
+            JOIN categories c ON b.category_id = c.id
+            WHERE b.user_id = $1
+            ORDER BY c.name ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(budgets)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Budget, AppError> {
+        sqlx::query_as!(
+            Budget,
+            r#"
+            SELECT
+                b.id, b.category_id,
+                c.name as category_name,
+                COALESCE(c.icon, 'help_outline') as "category_icon!",
+                b.amount, b.rollover, b.starts_on, b.created_at
+            FROM budgets b
+            JOIN categories c ON b.category_id = c.id
+            WHERE b.id = $1 AND b.user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Budget not found".to_string()))
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        category_id: i32,
+        amount: Decimal,
+        rollover: bool,
+        starts_on: NaiveDate,
+    ) -> Result<Uuid, AppError> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO budgets (user_id, category_id, amount, rollover, starts_on)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING id
+            "#,
+            user_id,
+            category_id,
+            amount,
+            rollover,
+            starts_on
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| unique_violation(e, "This category already has a budget"))
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        amount: Option<Decimal>,
+        rollover: Option<bool>,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE budgets
+            SET
+                amount = COALESCE($3, amount),
+                rollover = COALESCE($4, rollover)
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            amount,
+            rollover
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM budgets WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct EnvelopeRepository {
+    pool: PgPool,
+}
+
+impl EnvelopeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Assignments for months in `[since, until]`
+    pub async fn get_allocations(
+        &self,
+        user_id: Uuid,
+        since: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<EnvelopeAllocationRow>, AppError> {
+        let rows = sqlx::query_as!(
+            EnvelopeAllocationRow,
+            r#"
+            SELECT
+                a.month, a.category_id, a.pocket_id,
+                COALESCE(p.name, c.name) as "name!",
+                a.amount
+            FROM envelope_allocations a
+            LEFT JOIN categories c ON a.category_id = c.id
+            LEFT JOIN pockets p ON a.pocket_id = p.id
+            WHERE a.user_id = $1 AND a.month BETWEEN $2 AND $3
+            "#,
+            user_id,
+            since,
+            until
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Income and spending per month, category and envelope pocket for transactions in
+    /// `[since, until)`, in base currency. Transfers are moves between pockets, not
+    /// income or spending, so they are left out.
+    pub async fn get_activity(
+        &self,
+        user_id: Uuid,
+        since: NaiveDate,
+        until: NaiveDate,
+    ) -> Result<Vec<EnvelopeActivityRow>, AppError> {
+        let rows = sqlx::query_as!(
+            EnvelopeActivityRow,
+            r#"
+            WITH envelope_pockets AS (
+                SELECT DISTINCT pocket_id FROM envelope_allocations
+                WHERE user_id = $1 AND pocket_id IS NOT NULL AND month >= $2
+            )
+            SELECT
+                date_trunc('month', t.occurred_at AT TIME ZONE 'UTC')::date as "month!",
+                c.id as category_id,
+                ep.pocket_id as "pocket_id?",
+                COALESCE(p.name, c.name) as "name!",
+                COALESCE(c.is_income, FALSE) as "is_income!",
+                SUM(t.amount) as "total!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            LEFT JOIN envelope_pockets ep
+                ON ep.pocket_id = t.pocket_id AND NOT COALESCE(c.is_income, FALSE)
+            LEFT JOIN pockets p ON p.id = ep.pocket_id
+            WHERE t.user_id = $1 AND t.deleted_at IS NULL AND t.status <> 'void' AND t.transfer_id IS NULL
+              AND (c.exclude_from_analysis = FALSE OR c.exclude_from_analysis IS NULL)
+              AND t.occurred_at AT TIME ZONE 'UTC' >= $2::date
+              AND t.occurred_at AT TIME ZONE 'UTC' < $3::date
+            GROUP BY 1, 2, 3, 4, 5
+            "#,
+            user_id,
+            since,
+            until
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn assign_category(
+        &self,
+        user_id: Uuid,
+        month: NaiveDate,
+        category_id: i32,
+        amount: Decimal,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO envelope_allocations (user_id, month, category_id, amount)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, month, category_id) WHERE category_id IS NOT NULL
+            DO UPDATE SET amount = EXCLUDED.amount
+            "#,
+            user_id,
+            month,
+            category_id,
+            amount
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn assign_pocket(
+        &self,
+        user_id: Uuid,
+        month: NaiveDate,
+        pocket_id: Uuid,
+        amount: Decimal,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO envelope_allocations (user_id, month, pocket_id, amount)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, month, pocket_id) WHERE pocket_id IS NOT NULL
+            DO UPDATE SET amount = EXCLUDED.amount
+            "#,
+            user_id,
+            month,
+            pocket_id,
+            amount
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct BillRepository {
+    pool: PgPool,
+}
+
+impl BillRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<BillRow>, AppError> {
+        let bills = sqlx::query_as!(
+            BillRow,
+            r#"
+            SELECT
+                b.id, b.user_id, b.name, b.payee_id,
+                py.name as payee_name,
+                b.expected_amount, b.due_day, b.pocket_id,
+                p.name as "pocket_name?",
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon?",
+                p.currency as "pocket_currency?",
+                b.starts_on, b.created_at
+            FROM bills b
+            JOIN payees py ON b.payee_id = py.id
+            LEFT JOIN pockets p ON b.pocket_id = p.id
+            WHERE b.user_id = $1
+            ORDER BY b.due_day ASC, b.name ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(bills)
+    }
+
+    /// Bills of every user, for the overdue reminder job
+    pub async fn get_all_users(&self) -> Result<Vec<BillRow>, AppError> {
+        let bills = sqlx::query_as!(
+            BillRow,
+            r#"
+            SELECT
+                b.id, b.user_id, b.name, b.payee_id,
+                py.name as payee_name,
+                b.expected_amount, b.due_day, b.pocket_id,
+                p.name as "pocket_name?",
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon?",
+                p.currency as "pocket_currency?",
+                b.starts_on, b.created_at
+            FROM bills b
+            JOIN payees py ON b.payee_id = py.id
+            LEFT JOIN pockets p ON b.pocket_id = p.id
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(bills)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<BillRow, AppError> {
+        sqlx::query_as!(
+            BillRow,
+            r#"
+            SELECT
+                b.id, b.user_id, b.name, b.payee_id,
+                py.name as payee_name,
+                b.expected_amount, b.due_day, b.pocket_id,
+                p.name as "pocket_name?",
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon?",
+                p.currency as "pocket_currency?",
+                b.starts_on, b.created_at
+            FROM bills b
+            JOIN payees py ON b.payee_id = py.id
+            LEFT JOIN pockets p ON b.pocket_id = p.id
+            WHERE b.id = $1 AND b.user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Bill not found".to_string()))
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        req: &CreateBill,
+        name: &str,
+        starts_on: NaiveDate,
+    ) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO bills (user_id, name, payee_id, expected_amount, due_day, pocket_id, starts_on)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id
+            "#,
+            user_id,
+            name,
+            req.payee_id,
+            req.expected_amount,
+            req.due_day,
+            req.pocket_id,
+            starts_on
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn update(&self, id: Uuid, user_id: Uuid, req: &UpdateBill) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE bills
+            SET
+                name = COALESCE($3, name),
+                expected_amount = COALESCE($4, expected_amount),
+                due_day = COALESCE($5, due_day),
+                pocket_id = COALESCE($6, pocket_id)
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            req.name.as_deref().map(str::trim),
+            req.expected_amount,
+            req.due_day,
+            req.pocket_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM bills WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Payments of occurrences due on or after `since`. Payments whose transaction is in the
+    /// trash do not count.
+    pub async fn get_payments(
+        &self,
+        bill_ids: &[Uuid],
+        since: NaiveDate,
+    ) -> Result<Vec<BillPaymentRow>, AppError> {
+        let payments = sqlx::query_as!(
+            BillPaymentRow,
+            r#"
+            SELECT bp.bill_id, bp.due_date, bp.transaction_id
+            FROM bill_payments bp
+            JOIN transactions t ON bp.transaction_id = t.id
+            WHERE bp.bill_id = ANY($1) AND bp.due_date >= $2 AND t.deleted_at IS NULL AND t.status <> 'void'
+            "#,
+            bill_ids,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(payments)
+    }
+
+    /// Record that a transaction paid an occurrence. A link to a transaction that has since
+    /// been deleted or voided no longer counts and is replaced. Returns false when the
+    /// occurrence or the transaction is already linked.
+    pub async fn record_payment(
+        &self,
+        bill_id: Uuid,
+        due_date: NaiveDate,
+        transaction_id: Uuid,
+    ) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM bill_payments bp
+            USING transactions t
+            WHERE bp.bill_id = $1 AND bp.due_date = $2 AND bp.transaction_id = t.id
+              AND (t.deleted_at IS NOT NULL OR t.status = 'void')
+            "#,
+            bill_id,
+            due_date
+        )
+        .execute(&mut *tx)
+        .await?;
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO bill_payments (bill_id, due_date, transaction_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING
+            "#,
+            bill_id,
+            due_date,
+            transaction_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Active transactions among `ids`, in the shape bill matching needs
+    pub async fn get_match_candidates(
+        &self,
+        user_id: Uuid,
+        ids: &[Uuid],
+    ) -> Result<Vec<BillMatchRow>, AppError> {
+        let rows = sqlx::query_as!(
+            BillMatchRow,
+            r#"
+            SELECT
+                t.id, t.payee_id, t.pocket_id,
+                COALESCE(t.original_amount, t.amount) as "amount!",
+                t.occurred_at,
+                COALESCE(c.is_income, FALSE) as "is_income!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            WHERE t.user_id = $1 AND t.id = ANY($2)
+              AND t.deleted_at IS NULL AND t.status <> 'void' AND t.transfer_id IS NULL AND t.payee_id IS NOT NULL
+            "#,
+            user_id,
+            ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Occurrences due on or after `since` that already had an overdue reminder
+    pub async fn get_reminders(
+        &self,
+        bill_ids: &[Uuid],
+        since: NaiveDate,
+    ) -> Result<Vec<(Uuid, NaiveDate)>, AppError> {
+        let rows = sqlx::query!(
+            "SELECT bill_id, due_date FROM bill_reminders WHERE bill_id = ANY($1) AND due_date >= $2",
+            bill_ids,
+            since
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(|r| (r.bill_id, r.due_date)).collect())
+    }
+
+    pub async fn record_reminder(
+        &self,
+        bill_id: Uuid,
+        due_date: NaiveDate,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "INSERT INTO bill_reminders (bill_id, due_date) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            bill_id,
+            due_date
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct NotificationRepository {
+    pool: PgPool,
+}
+
+impl NotificationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(&self, notification: &NewNotification) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO notifications (user_id, kind, title, body)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            notification.user_id,
+            notification.kind,
+            notification.title,
+            notification.body
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    /// Most recent notifications first
+    pub async fn get_recent(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+    ) -> Result<Vec<Notification>, AppError> {
+        let notifications = sqlx::query_as!(
+            Notification,
+            r#"
+            SELECT id, kind, title, body, read_at, created_at
+            FROM notifications
+            WHERE user_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2
+            "#,
+            user_id,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(notifications)
+    }
+
+    pub async fn mark_read(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE notifications SET read_at = COALESCE(read_at, NOW())
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct LiabilityRepository {
+    pool: PgPool,
+}
+
+impl LiabilityRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<LiabilityRow>, AppError> {
+        let liabilities = sqlx::query_as!(
+            LiabilityRow,
+            r#"
+            SELECT
+                id, user_id, name, principal, annual_rate, term_months, frequency,
+                first_payment_on, currency, created_at
+            FROM liabilities
+            WHERE user_id = $1
+            ORDER BY created_at ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(liabilities)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<LiabilityRow, AppError> {
+        sqlx::query_as!(
+            LiabilityRow,
+            r#"
+            SELECT
+                id, user_id, name, principal, annual_rate, term_months, frequency,
+                first_payment_on, currency, created_at
+            FROM liabilities
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Liability not found".to_string()))
+    }
+
+    /// Repayments of the user's liabilities (or of one liability), oldest first
+    pub async fn get_payments(
+        &self,
+        user_id: Uuid,
+        liability_id: Option<Uuid>,
+    ) -> Result<Vec<LiabilityPaymentRow>, AppError> {
+        let rows = sqlx::query_as!(
+            LiabilityPaymentRow,
+            r#"
+            SELECT
+                lp.liability_id,
+                t.id as transaction_id,
+                t.occurred_at,
+                CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END as "amount!"
+            FROM liability_payments lp
+            JOIN liabilities l ON lp.liability_id = l.id
+            JOIN transactions t ON lp.transaction_id = t.id
+            JOIN pockets p ON t.pocket_id = p.id
+            WHERE l.user_id = $1 AND ($2::uuid IS NULL OR l.id = $2) AND t.deleted_at IS NULL AND t.status <> 'void'
+            ORDER BY t.occurred_at ASC, t.id ASC
+            "#,
+            user_id,
+            liability_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Currency and kind of a transaction about to be recorded as a repayment
+    pub async fn get_repayment_candidate(
+        &self,
+        transaction_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<RepaymentCandidateRow, AppError> {
+        sqlx::query_as!(
+            RepaymentCandidateRow,
+            r#"
+            SELECT
+                p.currency,
+                COALESCE(c.is_income, FALSE) as "is_income!",
+                (t.transfer_id IS NOT NULL) as "is_transfer!"
+            FROM transactions t
+            JOIN pockets p ON t.pocket_id = p.id
+            LEFT JOIN categories c ON t.category_id = c.id
+            WHERE t.id = $1 AND t.user_id = $2 AND t.deleted_at IS NULL AND t.status <> 'void'
+            "#,
+            transaction_id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Transaction not found".to_string()))
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        req: &CreateLiability,
+        name: &str,
+        currency: &str,
+    ) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO liabilities
+                (user_id, name, principal, annual_rate, term_months, frequency, first_payment_on, currency)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING id
+            "#,
+            user_id,
+            name,
+            req.principal,
+            req.annual_rate,
+            req.term_months,
+            req.frequency.as_str(),
+            req.first_payment_on,
+            currency
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(id)
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: &UpdateLiability,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE liabilities
+            SET
+                name = COALESCE($3, name),
+                principal = COALESCE($4, principal),
+                annual_rate = COALESCE($5, annual_rate),
+                term_months = COALESCE($6, term_months),
+                frequency = COALESCE($7, frequency),
+                first_payment_on = COALESCE($8, first_payment_on)
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            req.name.as_deref().map(str::trim),
+            req.principal,
+            req.annual_rate,
+            req.term_months,
+            req.frequency.map(|f| f.as_str()),
+            req.first_payment_on
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM liabilities WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn add_payment(
+        &self,
+        liability_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            "INSERT INTO liability_payments (liability_id, transaction_id) VALUES ($1, $2)",
+            liability_id,
+            transaction_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| unique_violation(e, "This transaction is already recorded as a repayment"))?;
+        Ok(())
+    }
+
+    pub async fn remove_payment(
+        &self,
+        liability_id: Uuid,
+        user_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM liability_payments lp
+            USING liabilities l
+            WHERE lp.liability_id = l.id
+              AND lp.liability_id = $1 AND l.user_id = $2 AND lp.transaction_id = $3
+            "#,
+            liability_id,
+            user_id,
+            transaction_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct ReconciliationRepository {
+    pool: PgPool,
+}
+
+impl ReconciliationRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_all(
+        &self,
+        user_id: Uuid,
+        pocket_id: Uuid,
+    ) -> Result<Vec<ReconciliationRow>, AppError> {
+        let rows = sqlx::query_as!(
+            ReconciliationRow,
+            r#"
+            SELECT
+                r.id, r.pocket_id,
+                p.name as pocket_name,
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon!",
+                p.currency as pocket_currency,
+                r.statement_date, r.ending_balance,
+                COALESCE(b.balance, 0) as "cleared_balance!",
+                r.completed_at, r.created_at
+            FROM reconciliations r
+            JOIN pockets p ON r.pocket_id = p.id
+            LEFT JOIN LATERAL (
+                SELECT SUM(
+                    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                    * CASE WHEN c.is_income THEN 1 ELSE -1 END
+                ) as balance
+                FROM transactions t
+                JOIN categories c ON t.category_id = c.id
+                WHERE t.pocket_id = r.pocket_id AND t.deleted_at IS NULL AND t.status = 'posted'
+                  AND t.cleared_at IS NOT NULL AND t.occurred_at < r.statement_date + 1
+            ) b ON TRUE
+            WHERE r.user_id = $1 AND r.pocket_id = $2
+            ORDER BY r.statement_date DESC, r.created_at DESC
+            "#,
+            user_id,
+            pocket_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<ReconciliationRow, AppError> {
+        sqlx::query_as!(
+            ReconciliationRow,
+            r#"
+            SELECT
+                r.id, r.pocket_id,
+                p.name as pocket_name,
+                COALESCE(p.icon, 'account_balance_wallet') as "pocket_icon!",
+                p.currency as pocket_currency,
+                r.statement_date, r.ending_balance,
+                COALESCE(b.balance, 0) as "cleared_balance!",
+                r.completed_at, r.created_at
+            FROM reconciliations r
+            JOIN pockets p ON r.pocket_id = p.id
+            LEFT JOIN LATERAL (
+                SELECT SUM(
+                    CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                    * CASE WHEN c.is_income THEN 1 ELSE -1 END
+                ) as balance
+                FROM transactions t
+                JOIN categories c ON t.category_id = c.id
+                WHERE t.pocket_id = r.pocket_id AND t.deleted_at IS NULL AND t.status = 'posted'
+                  AND t.cleared_at IS NOT NULL AND t.occurred_at < r.statement_date + 1
+            ) b ON TRUE
+            WHERE r.id = $1 AND r.user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError(
+            "Reconciliation not found".to_string(),
+        ))
+    }
+
+    /// Unreconciled transactions of the pocket up to the end of `statement_date`
+    pub async fn get_items(
+        &self,
+        pocket_id: Uuid,
+        statement_date: NaiveDate,
+    ) -> Result<Vec<ReconciliationItem>, AppError> {
+        let items = sqlx::query_as!(
+            ReconciliationItem,
+            r#"
+            SELECT
+                t.id, t.description,
+                CASE WHEN t.original_currency = p.currency THEN t.original_amount ELSE t.amount END
+                    * CASE WHEN c.is_income THEN 1 ELSE -1 END as "amount!",
+                t.occurred_at,
+                (t.cleared_at IS NOT NULL) as "cleared!"
+            FROM transactions t
+            JOIN categories c ON t.category_id = c.id
+            JOIN pockets p ON t.pocket_id = p.id
+            WHERE t.pocket_id = $1 AND t.deleted_at IS NULL AND t.status = 'posted'
+              AND t.reconciled_at IS NULL
+              AND t.occurred_at < $2::date + 1
+            ORDER BY t.occurred_at ASC, t.id ASC
+            "#,
+            pocket_id,
+            statement_date
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(items)
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        pocket_id: Uuid,
+        req: &StartReconciliation,
+    ) -> Result<Uuid, AppError> {
+        sqlx::query_scalar!(
+            r#"
+            INSERT INTO reconciliations (user_id, pocket_id, statement_date, ending_balance)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id
+            "#,
+            user_id,
+            pocket_id,
+            req.statement_date,
+            req.ending_balance
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| unique_violation(e, "This pocket already has a reconciliation in progress"))
+    }
+
+    /// Tick transactions off (or untick them). Either all of them are in the statement period
+    /// and still unreconciled, or none is changed.
+    pub async fn set_cleared(
+        &self,
+        pocket_id: Uuid,
+        statement_date: NaiveDate,
+        ids: &[Uuid],
+        cleared: bool,
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET cleared_at = CASE WHEN $4 THEN COALESCE(cleared_at, NOW()) END
+            WHERE id = ANY($3) AND pocket_id = $1 AND deleted_at IS NULL AND status = 'posted'
+              AND reconciled_at IS NULL AND occurred_at < $2::date + 1
+            "#,
+            pocket_id,
+            statement_date,
+            ids,
+            cleared
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let unique: HashSet<&Uuid> = ids.iter().collect();
+        if result.rows_affected() != unique.len() as u64 {
+            return Err(AppError::ValidationError(
+                "Only posted, unreconciled transactions of this pocket up to the statement date can be ticked off"
+                    .to_string(),
+            ));
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Lock every cleared transaction up to the statement date and close the reconciliation
+    pub async fn complete(
+        &self,
+        id: Uuid,
+        pocket_id: Uuid,
+        statement_date: NaiveDate,
+    ) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query!(
+            r#"
+            UPDATE transactions SET reconciled_at = NOW()
+            WHERE pocket_id = $1 AND deleted_at IS NULL AND status = 'posted' AND cleared_at IS NOT NULL
+              AND reconciled_at IS NULL AND occurred_at < $2::date + 1
+            "#,
+            pocket_id,
+            statement_date
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!(
+            "UPDATE reconciliations SET completed_at = NOW() WHERE id = $1",
+            id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Discard a reconciliation still in progress; ticked transactions stay cleared
+    pub async fn delete_open(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM reconciliations WHERE id = $1 AND user_id = $2 AND completed_at IS NULL",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct NetWorthRepository {
+    pool: PgPool,
+}
+
+impl NetWorthRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_history(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<NetWorthSnapshot>, AppError> {
+        let snapshots = sqlx::query!(
+            r#"
+            SELECT snapshot_date, base_currency, cash_balance, investment_balance,
+                liabilities_balance, total_net_worth, is_backfill,
+                items as "items: Json<Vec<NetWorthItem>>"
+            FROM net_worth_snapshots
+            WHERE user_id = $1 AND snapshot_date BETWEEN $2 AND $3
+            ORDER BY snapshot_date
+            "#,
+            user_id,
+            start_date,
+            end_date
+        )
+        .fetch_all(&self.pool)
+        .await?
+        .into_iter()
+        .map(|row| NetWorthSnapshot {
+            date: row.snapshot_date,
+            base_currency: row.base_currency,
+            cash_balance: row.cash_balance,
+            investment_balance: row.investment_balance,
+            liabilities_balance: row.liabilities_balance,
+            total_net_worth: row.total_net_worth,
+            is_backfill: row.is_backfill,
+            items: row.items.0,
+        })
+        .collect();
+        Ok(snapshots)
+    }
+
+    /// Days in the range that already have a snapshot
+    pub async fn get_recorded_days(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<NaiveDate>, AppError> {
+        let days = sqlx::query_scalar!(
+            r#"
+            SELECT snapshot_date FROM net_worth_snapshots
+            WHERE user_id = $1 AND snapshot_date BETWEEN $2 AND $3
+            "#,
+            user_id,
+            start_date,
+            end_date
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(days)
+    }
+
+    /// Users who had joined by `day` but have no snapshot for it yet
+    pub async fn get_users_without_snapshot(&self, day: NaiveDate) -> Result<Vec<Uuid>, AppError> {
+        let users = sqlx::query_scalar!(
+            r#"
+            SELECT u.id FROM users u
+            WHERE u.created_at < $1::date + 1
+              AND NOT EXISTS (
+                  SELECT 1 FROM net_worth_snapshots s
+                  WHERE s.user_id = u.id AND s.snapshot_date = $1
+              )
+            "#,
+            day
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(users)
+    }
+
+    /// Day of the user's earliest transaction
+    pub async fn get_first_activity(&self, user_id: Uuid) -> Result<Option<NaiveDate>, AppError> {
+        let day = sqlx::query_scalar!(
+            r#"
+            SELECT MIN(occurred_at AT TIME ZONE 'UTC')::date
+            FROM transactions
+            WHERE user_id = $1 AND deleted_at IS NULL
+            "#,
+            user_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(day)
+    }
+
+    /// Every pocket's balance at the end of each day in the range, in the pocket's currency.
+    /// Pending income is left out as in the pocket's available balance.
+    pub async fn get_pocket_balances(
+        &self,
+        user_id: Uuid,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> Result<Vec<PocketDayBalanceRow>, AppError> {
+        let rows = sqlx::query_as!(
+            PocketDayBalanceRow,
+            r#"
+            SELECT
+                d.day::date as "day!",
+                p.id as pocket_id,
+                p.name,
+                p.currency,
+                p.kind,
+                COALESCE(SUM(l.amount), 0) as "balance!"
+            FROM generate_series($2::date, $3::date, '1 day') AS d(day)
+            CROSS JOIN pockets p
+            LEFT JOIN pocket_ledger l
+                ON l.pocket_id = p.id
+                AND l.available
+                AND l.occurred_at < d.day::date + 1
+            WHERE p.user_id = $1
+            GROUP BY d.day, p.id
+            ORDER BY d.day, p.created_at
+            "#,
+            user_id,
+            start_date,
+            end_date
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows)
+    }
+
+    /// Start a database transaction so a range of snapshots is written all at once
+    pub async fn begin(&self) -> Result<sqlx::Transaction<'static, sqlx::Postgres>, AppError> {
+        Ok(self.pool.begin().await?)
+    }
+
+    /// Store a snapshot unless the day already has one; returns the number of rows written
+    pub async fn insert_tx(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        snapshot: &NetWorthSnapshot,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO net_worth_snapshots (
+                user_id, snapshot_date, base_currency, cash_balance, investment_balance,
+                liabilities_balance, total_net_worth, items, is_backfill
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (user_id, snapshot_date) DO NOTHING
+            "#,
+            user_id,
+            snapshot.date,
+            snapshot.base_currency,
+            snapshot.cash_balance,
+            snapshot.investment_balance,
+            snapshot.liabilities_balance,
+            snapshot.total_net_worth,
+            Json(&snapshot.items) as _,
+            snapshot.is_backfill
+        )
+        .execute(conn)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+pub struct PayeeRepository {
+    pool: PgPool,
+}
+
+/// Turn a unique-constraint violation into a client-facing validation error
+fn unique_violation(err: sqlx::Error, message: &str) -> AppError {
+    match err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => {
+            AppError::ValidationError(message.to_string())
+        }
+        err => AppError::DatabaseError(err),
+    }
+}
+
+impl PayeeRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<Payee>, AppError> {
+        let payees = sqlx::query!(
+            r#"
+            SELECT id, name, default_category_id, created_at
+            FROM payees
+            WHERE user_id = $1
+            ORDER BY name ASC
+            "#,
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let aliases = sqlx::query!(
+            "SELECT id, payee_id, alias FROM payee_aliases WHERE user_id = $1 ORDER BY alias ASC",
+            user_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(payees
+            .into_iter()
+            .map(|row| Payee {
+                id: row.id,
+                name: row.name,
+                default_category_id: row.default_category_id,
+                aliases: aliases
+                    .iter()
+                    .filter(|a| a.payee_id == row.id)
+                    .map(|a| PayeeAlias {
+                        id: a.id,
+                        alias: a.alias.clone(),
+                    })
+                    .collect(),
+                created_at: row.created_at,
+            })
+            .collect())
+    }
+
+    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Payee, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, name, default_category_id, created_at
+            FROM payees
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or(AppError::NotFoundError("Payee not found".to_string()))?;
+
+        let aliases = sqlx::query_as!(
+            PayeeAlias,
+            "SELECT id, alias FROM payee_aliases WHERE payee_id = $1 ORDER BY alias ASC",
+            id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(Payee {
+            id: row.id,
+            name: row.name,
+            default_category_id: row.default_category_id,
+            aliases,
+            created_at: row.created_at,
+        })
+    }
+
+    /// Create a payee together with its (already normalized) aliases
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        name: &str,
+        default_category_id: Option<i32>,
+        aliases: &[String],
+    ) -> Result<Uuid, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO payees (user_id, name, default_category_id)
+            VALUES ($1, $2, $3)
+            RETURNING id
+            "#,
+            user_id,
+            name,
+            default_category_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| unique_violation(e, "A payee with this name already exists"))?;
+
+        for alias in aliases {
+            sqlx::query!(
+                "INSERT INTO payee_aliases (user_id, payee_id, alias) VALUES ($1, $2, $3)",
+                user_id,
+                id,
+                alias
+            )
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                unique_violation(e, &format!("Alias '{}' already belongs to a payee", alias))
+            })?;
+        }
+
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    pub async fn update(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        name: Option<String>,
+        default_category_id: Option<i32>,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE payees 
+            SET 
+                name = COALESCE($3, name),
+                default_category_id = COALESCE($4, default_category_id)
+            WHERE id = $1 AND user_id = $2
+            "#,
+            id,
+            user_id,
+            name,
+            default_category_id
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| unique_violation(e, "A payee with this name already exists"))?;
+        Ok(result.rows_affected())
+    }
+
+    /// Transactions keep their data; their payee link is cleared by the foreign key
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM payees WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    pub async fn add_alias(
+        &self,
+        user_id: Uuid,
+        payee_id: Uuid,
+        alias: &str,
+    ) -> Result<Uuid, AppError> {
+        let id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO payee_aliases (user_id, payee_id, alias)
+            VALUES ($1, $2, $3)
+            RETURNING id
             "#,
+            user_id,
+            payee_id,
+            alias
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| {
+            unique_violation(e, &format!("Alias '{}' already belongs to a payee", alias))
+        })?;
+        Ok(id)
+    }
+
+    pub async fn delete_alias(
+        &self,
+        alias_id: Uuid,
+        payee_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<u64, AppError> {
+        let result = sqlx::query!(
+            "DELETE FROM payee_aliases WHERE id = $1 AND payee_id = $2 AND user_id = $3",
+            alias_id,
+            payee_id,
             user_id
         )
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
-
-        let result = rows
-            .into_iter()
-            .map(|r| crate::schemas::PortfolioJoinedRow {
-                ticker: r.ticker.unwrap_or_default(),
-                name: r.name,
-                quantity: r.quantity,
-                avg_buy_price: r.avg_buy_price,
-                current_price: r.current_price.unwrap_or(Decimal::ZERO),
-                source: r.source,
-                api_ticker: r.api_ticker,
-                currency: r.currency,
-                icon_url: r.icon_url,
-            })
-            .collect();
-
-        Ok(result)
+        Ok(result.rows_affected())
     }
 
-    pub async fn get_asset(&self, ticker: &str) -> Result<Option<crate::schemas::Asset>, AppError> {
-        let asset = sqlx::query_as!(
-            crate::schemas::Asset,
+    /// Every alias of the user's payees, used to resolve descriptions
+    pub async fn get_alias_index(&self, user_id: Uuid) -> Result<Vec<PayeeAliasRow>, AppError> {
+        let rows = sqlx::query_as!(
+            PayeeAliasRow,
             r#"
-            SELECT 
-                ticker, 
-                name, 
-                asset_type,
-                api_ticker,
-                source,
-                currency,
-                current_price,
-                icon_url
-            FROM assets
-            WHERE ticker = $1
+            SELECT a.payee_id, a.alias, p.default_category_id
+            FROM payee_aliases a
+            JOIN payees p ON a.payee_id = p.id
+            WHERE a.user_id = $1
             "#,
-            ticker
+            user_id
         )
-        .fetch_optional(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(asset)
+        Ok(rows)
     }
 
-    pub async fn delete(&self, user_id: Uuid, ticker: &str) -> Result<u64, AppError> {
-        let result = sqlx::query!(
-            "DELETE FROM portfolio WHERE user_id = $1 AND ticker = $2",
+    /// Returns the ids (out of `ids`) that belong to the user
+    pub async fn find_owned_ids(&self, user_id: Uuid, ids: &[Uuid]) -> Result<Vec<Uuid>, AppError> {
+        let rows = sqlx::query_scalar!(
+            "SELECT id FROM payees WHERE user_id = $1 AND id = ANY($2)",
             user_id,
-            ticker
+            ids
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(result.rows_affected())
+        Ok(rows)
     }
 
-    pub async fn update(
+    /// Payees ranked by spending, skipping income and categories excluded from analysis
+    pub async fn get_top_payees(
         &self,
         user_id: Uuid,
-        ticker: &str,
-        quantity: Option<Decimal>,
-        avg_buy_price: Option<Decimal>,
-    ) -> Result<(), AppError> {
-        // Simple dynamic query via COALESCE
-        // Since we're dealing with "if null dont change", COALESCE works if we pass NULL for None.
-        sqlx::query!(
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PayeeSpending>, AppError> {
+        let rows = sqlx::query_as!(
+            PayeeSpending,
             r#"
-            UPDATE portfolio 
-            SET 
-                quantity = COALESCE($3, quantity), 
-                avg_buy_price = COALESCE($4, avg_buy_price)
-            WHERE user_id = $1 AND ticker = $2
+            SELECT 
+                py.id as payee_id,
+                py.name,
+                COALESCE(SUM(t.amount), 0) as "total!",
+                COUNT(t.id) as "transaction_count!",
+                MAX(t.occurred_at) as "last_occurred_at!"
+            FROM transactions t
+            JOIN payees py ON t.payee_id = py.id
+            JOIN categories c ON t.category_id = c.id
+            WHERE t.user_id = $1
+              AND t.occurred_at BETWEEN $2 AND $3
+              AND t.deleted_at IS NULL
+              AND t.status <> 'void'
+              AND COALESCE(c.is_income, FALSE) = FALSE
+              AND (c.exclude_from_analysis = FALSE OR c.exclude_from_analysis IS NULL)
+            GROUP BY py.id, py.name
+            ORDER BY 3 DESC
+            LIMIT $4
             "#,
             user_id,
-            ticker,
-            quantity,
-            avg_buy_price
+            start_date,
+            end_date,
+            limit
         )
-        .execute(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(())
+        Ok(rows)
     }
 }
 
-pub struct PocketRepository {
+pub struct ExchangeRateRepository {
     pool: PgPool,
 }
 
-impl PocketRepository {
+impl ExchangeRateRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
 
-    pub async fn create(
+    pub async fn get(
         &self,
-        user_id: Uuid,
-        name: &str,
-        description: Option<String>,
-        icon: Option<String>,
-    ) -> Result<Uuid, AppError> {
-        let icon = icon.unwrap_or_else(|| "account_balance_wallet".to_string());
-        let id = sqlx::query_scalar!(
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Option<Decimal>, AppError> {
+        let rate = sqlx::query_scalar!(
             r#"
-            INSERT INTO pockets (user_id, name, description, icon)
-            VALUES ($1, $2, $3, $4)
-            RETURNING id
+            SELECT rate FROM exchange_rates
+            WHERE from_currency = $1 AND to_currency = $2 AND rate_date = $3
             "#,
-            user_id,
-            name,
-            description,
-            icon
+            from,
+            to,
+            date
         )
-        .fetch_one(&self.pool)
+        .fetch_optional(&self.pool)
         .await?;
-        Ok(id)
+        Ok(rate)
     }
 
-    pub async fn create_default_for_user(&self, user_id: Uuid) -> Result<Uuid, AppError> {
-        let id = sqlx::query_scalar!(
+    /// Stored rates from `start` to `end`, by date
+    pub async fn get_range(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<Vec<(NaiveDate, Decimal)>, AppError> {
+        let rows = sqlx::query!(
             r#"
-            INSERT INTO pockets (user_id, name, is_default)
-            VALUES ($1, 'Main', TRUE)
-            RETURNING id
+            SELECT rate_date, rate FROM exchange_rates
+            WHERE from_currency = $1 AND to_currency = $2 AND rate_date BETWEEN $3 AND $4
+            ORDER BY rate_date
             "#,
-            user_id
+            from,
+            to,
+            start,
+            end
         )
-        .fetch_one(&self.pool)
+        .fetch_all(&self.pool)
         .await?;
-        Ok(id)
+        Ok(rows.into_iter().map(|r| (r.rate_date, r.rate)).collect())
     }
 
-    pub async fn get_all(&self, user_id: Uuid) -> Result<Vec<Pocket>, AppError> {
-        let pockets = sqlx::query_as!(
-            Pocket,
+    /// Store one rate per day in a single statement, keeping rates already stored
+    pub async fn insert_many(
+        &self,
+        from: &str,
+        to: &str,
+        rates: &[(NaiveDate, Decimal)],
+    ) -> Result<(), AppError> {
+        let (dates, values): (Vec<NaiveDate>, Vec<Decimal>) = rates.iter().copied().unzip();
+        sqlx::query!(
             r#"
-            SELECT 
-                id, name, description, 
-                COALESCE(icon, 'account_balance_wallet') as "icon!",
-                COALESCE(is_default, FALSE) as "is_default!",
-                created_at
-            FROM pockets
-            WHERE user_id = $1
-            ORDER BY is_default DESC, name ASC
+            INSERT INTO exchange_rates (from_currency, to_currency, rate_date, rate)
+            SELECT $1, $2, d, r FROM UNNEST($3::date[], $4::numeric[]) AS u(d, r)
+            ON CONFLICT (from_currency, to_currency, rate_date) DO NOTHING
             "#,
-            user_id
+            from,
+            to,
+            &dates,
+            &values
         )
-        .fetch_all(&self.pool)
+        .execute(&self.pool)
         .await?;
-        Ok(pockets)
+        Ok(())
     }
 
-    pub async fn get_by_id(&self, id: Uuid, user_id: Uuid) -> Result<Pocket, AppError> {
-        let pocket = sqlx::query_as!(
-            Pocket,
+    pub async fn insert(
+        &self,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+        rate: Decimal,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
             r#"
-            SELECT 
-                id, name, description, 
-                COALESCE(icon, 'account_balance_wallet') as "icon!",
-                COALESCE(is_default, FALSE) as "is_default!",
-                created_at
-            FROM pockets
-            WHERE id = $1 AND user_id = $2
+            INSERT INTO exchange_rates (from_currency, to_currency, rate_date, rate)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (from_currency, to_currency, rate_date) DO NOTHING
             "#,
-            id,
-            user_id
+            from,
+            to,
+            date,
+            rate
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
+
+pub struct IdempotencyRepository {
+    pool: PgPool,
+}
+
+impl IdempotencyRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Reserve the key for this request. Returns false when a live record already exists;
+    /// an expired record, or one claimed before `lease_expired_before` that never got a
+    /// response, is taken over.
+    pub async fn claim(
+        &self,
+        user_id: Uuid,
+        key: &str,
+        request_hash: &str,
+        expires_at: DateTime<Utc>,
+        lease_expired_before: DateTime<Utc>,
+    ) -> Result<bool, AppError> {
+        let claimed = sqlx::query_scalar!(
+            r#"
+            INSERT INTO idempotency_keys (user_id, key, request_hash, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, key) DO UPDATE
+            SET request_hash = EXCLUDED.request_hash,
+                response_status = NULL,
+                response_body = NULL,
+                created_at = NOW(),
+                expires_at = EXCLUDED.expires_at
+            WHERE idempotency_keys.expires_at < NOW()
+               OR (idempotency_keys.response_status IS NULL
+                   AND idempotency_keys.created_at < $5)
+            RETURNING key
+            "#,
+            user_id,
+            key,
+            request_hash,
+            expires_at,
+            lease_expired_before
         )
         .fetch_optional(&self.pool)
-        .await?
-        .ok_or(AppError::NotFoundError("Pocket not found".to_string()))?;
-        Ok(pocket)
+        .await?;
+        Ok(claimed.is_some())
     }
 
-    pub async fn get_default(&self, user_id: Uuid) -> Result<Pocket, AppError> {
-        let pocket = sqlx::query_as!(
-            Pocket,
+    pub async fn find(
+        &self,
+        user_id: Uuid,
+        key: &str,
+    ) -> Result<Option<IdempotencyRecord>, AppError> {
+        let record = sqlx::query_as!(
+            IdempotencyRecord,
             r#"
-            SELECT 
-                id, name, description, 
-                COALESCE(icon, 'account_balance_wallet') as "icon!",
-                COALESCE(is_default, FALSE) as "is_default!",
-                created_at
-            FROM pockets
-            WHERE user_id = $1 AND is_default = TRUE
+            SELECT request_hash, response_status, response_body
+            FROM idempotency_keys
+            WHERE user_id = $1 AND key = $2
             "#,
-            user_id
+            user_id,
+            key
         )
         .fetch_optional(&self.pool)
-        .await?
-        .ok_or(AppError::NotFoundError(
-            "Default pocket not found".to_string(),
-        ))?;
-        Ok(pocket)
+        .await?;
+        Ok(record)
     }
 
-    pub async fn update(
+    pub async fn complete(
         &self,
-        id: Uuid,
         user_id: Uuid,
-        name: Option<String>,
-        description: Option<String>,
-        icon: Option<String>,
+        key: &str,
+        status: i32,
+        body: &[u8],
     ) -> Result<(), AppError> {
         sqlx::query!(
             r#"
-            UPDATE pockets 
-            SET 
-                name = COALESCE($3, name),
-                description = COALESCE($4, description),
-                icon = COALESCE($5, icon)
-            WHERE id = $1 AND user_id = $2
+            UPDATE idempotency_keys
+            SET response_status = $3, response_body = $4
+            WHERE user_id = $1 AND key = $2
             "#,
-            id,
             user_id,
-            name,
-            description,
-            icon
+            key,
+            status,
+            body
         )
         .execute(&self.pool)
         .await?;
         Ok(())
     }
 
-    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<u64, AppError> {
-        // First check if this is a default pocket
-        let pocket = self.get_by_id(id, user_id).await?;
-        if pocket.is_default {
-            return Err(AppError::ValidationError(
-                "Cannot delete the default pocket".to_string(),
-            ));
-        }
-
-        let result = sqlx::query!(
-            "DELETE FROM pockets WHERE id = $1 AND user_id = $2",
-            id,
-            user_id
+    /// Forget a key whose request failed so it can be retried
+    pub async fn release(&self, user_id: Uuid, key: &str) -> Result<(), AppError> {
+        sqlx::query!(
+            "DELETE FROM idempotency_keys WHERE user_id = $1 AND key = $2",
+            user_id,
+            key
         )
         .execute(&self.pool)
         .await?;
+        Ok(())
+    }
+
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        let result = sqlx::query!("DELETE FROM idempotency_keys WHERE expires_at < NOW()")
+            .execute(&self.pool)
+            .await?;
         Ok(result.rows_affected())
     }
 }
@@ -942,6 +4371,37 @@ impl SettingsRepository {
         Ok(())
     }
 
+    /// First month counted by zero-based budgeting; None while the mode is off
+    pub async fn get_zero_based_since(&self, user_id: Uuid) -> Result<Option<NaiveDate>, AppError> {
+        let since = sqlx::query_scalar!(
+            "SELECT zero_based_since FROM user_settings WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(since.flatten())
+    }
+
+    pub async fn set_zero_based_since(
+        &self,
+        user_id: Uuid,
+        since: Option<NaiveDate>,
+    ) -> Result<(), AppError> {
+        sqlx::query!(
+            r#"
+            INSERT INTO user_settings (user_id, zero_based_since)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id)
+            DO UPDATE SET zero_based_since = EXCLUDED.zero_based_since
+            "#,
+            user_id,
+            since
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn validate_currency(&self, code: &str) -> Result<bool, AppError> {
         let result = sqlx::query!("SELECT 1 as exists FROM currencies WHERE code = $1", code)
             .fetch_optional(&self.pool)
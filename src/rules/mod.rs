@@ -0,0 +1,111 @@
+mod tests;
+
+use regex::{Regex, RegexBuilder};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::schemas::CategorizationRule;
+
+/// The transaction attributes a rule can match on
+pub struct RuleInput<'a> {
+    pub description: Option<&'a str>,
+    /// Amount as entered by the user, in `currency`
+    pub amount: Decimal,
+    pub currency: &'a str,
+    pub pocket_id: Uuid,
+}
+
+/// A rule with its description regex compiled once up front
+pub struct CompiledRule {
+    pub rule: CategorizationRule,
+    regex: Option<Regex>,
+}
+
+impl CompiledRule {
+    pub fn compile(rule: CategorizationRule) -> Result<Self, regex::Error> {
+        let regex = rule
+            .description_regex
+            .as_deref()
+            .map(build_regex)
+            .transpose()?;
+        Ok(Self { rule, regex })
+    }
+
+    /// True when every condition set on the rule matches
+    pub fn matches(&self, input: &RuleInput) -> bool {
+        let rule = &self.rule;
+
+        if let Some(needle) = &rule.description_contains {
+            match input.description {
+                Some(desc) if desc.to_lowercase().contains(&needle.to_lowercase()) => {}
+                _ => return false,
+            }
+        }
+
+        if let Some(regex) = &self.regex {
+            match input.description {
+                Some(desc) if regex.is_match(desc) => {}
+                _ => return false,
+            }
+        }
+
+        if rule.min_amount.is_some_and(|min| input.amount < min)
+            || rule.max_amount.is_some_and(|max| input.amount > max)
+        {
+            return false;
+        }
+
+        if rule.pocket_id.is_some_and(|id| id != input.pocket_id) {
+            return false;
+        }
+
+        if let Some(currency) = &rule.currency
+            && !currency.eq_ignore_ascii_case(input.currency)
+        {
+            return false;
+        }
+
+        true
+    }
+}
+
+/// Description regexes are case-insensitive, like `description_contains`
+pub fn build_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(1 << 20)
+        .build()
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RuleOutcome {
+    pub category_id: Option<i32>,
+    pub pocket_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub matched_rule_ids: Vec<Uuid>,
+}
+
+/// Evaluate active rules (already sorted by priority, lowest first).
+/// The first matching rule that sets a field wins for that field; tags from every
+/// matching rule are collected.
+pub fn evaluate(rules: &[CompiledRule], input: &RuleInput) -> RuleOutcome {
+    let mut outcome = RuleOutcome::default();
+
+    for compiled in rules.iter().filter(|r| r.rule.is_active) {
+        if !compiled.matches(input) {
+            continue;
+        }
+
+        let rule = &compiled.rule;
+        outcome.category_id = outcome.category_id.or(rule.set_category_id);
+        outcome.pocket_id = outcome.pocket_id.or(rule.set_pocket_id);
+        for tag in &rule.add_tags {
+            if !outcome.tags.contains(tag) {
+                outcome.tags.push(tag.clone());
+            }
+        }
+        outcome.matched_rule_ids.push(rule.id);
+    }
+
+    outcome
+}
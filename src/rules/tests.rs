@@ -0,0 +1,168 @@
+#![cfg(test)]
+
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::CategorizationRule;
+
+use super::{CompiledRule, RuleInput, evaluate};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn make_rule(priority: i32) -> CategorizationRule {
+    CategorizationRule {
+        id: Uuid::new_v4(),
+        name: format!("Rule {}", priority),
+        priority,
+        is_active: true,
+        description_contains: None,
+        description_regex: None,
+        min_amount: None,
+        max_amount: None,
+        pocket_id: None,
+        currency: None,
+        set_category_id: None,
+        set_pocket_id: None,
+        add_tags: vec![],
+        created_at: None,
+    }
+}
+
+fn make_input<'a>(description: Option<&'a str>, amount: Decimal, pocket_id: Uuid) -> RuleInput<'a> {
+    RuleInput {
+        description,
+        amount,
+        currency: "SGD",
+        pocket_id,
+    }
+}
+
+fn compile(rules: Vec<CategorizationRule>) -> Vec<CompiledRule> {
+    rules
+        .into_iter()
+        .map(|r| CompiledRule::compile(r).unwrap())
+        .collect()
+}
+
+// ============================================================================
+// Condition Matching Tests
+// ============================================================================
+
+mod matching {
+    use super::*;
+
+    #[test]
+    fn description_contains_is_case_insensitive() {
+        let mut rule = make_rule(0);
+        rule.description_contains = Some("netflix".to_string());
+        let rule = CompiledRule::compile(rule).unwrap();
+
+        let pocket = Uuid::new_v4();
+        assert!(rule.matches(&make_input(Some("NETFLIX.COM 1234"), dec!(15), pocket)));
+        assert!(!rule.matches(&make_input(Some("Spotify"), dec!(15), pocket)));
+        assert!(!rule.matches(&make_input(None, dec!(15), pocket)));
+    }
+
+    #[test]
+    fn regex_matches_prefix_patterns() {
+        // Given: "GRAB*" style merchant prefixes
+        let mut rule = make_rule(0);
+        rule.description_regex = Some(r"^grab\*".to_string());
+        let rule = CompiledRule::compile(rule).unwrap();
+
+        let pocket = Uuid::new_v4();
+        assert!(rule.matches(&make_input(Some("GRAB*RIDE 8812"), dec!(12), pocket)));
+        assert!(!rule.matches(&make_input(Some("Paid GRAB*RIDE"), dec!(12), pocket)));
+    }
+
+    #[test]
+    fn invalid_regex_fails_to_compile() {
+        let mut rule = make_rule(0);
+        rule.description_regex = Some("(unclosed".to_string());
+
+        assert!(CompiledRule::compile(rule).is_err());
+    }
+
+    #[test]
+    fn amount_range_is_inclusive() {
+        let mut rule = make_rule(0);
+        rule.min_amount = Some(dec!(10));
+        rule.max_amount = Some(dec!(20));
+        let rule = CompiledRule::compile(rule).unwrap();
+
+        let pocket = Uuid::new_v4();
+        assert!(rule.matches(&make_input(None, dec!(10), pocket)));
+        assert!(rule.matches(&make_input(None, dec!(20), pocket)));
+        assert!(!rule.matches(&make_input(None, dec!(9.99), pocket)));
+        assert!(!rule.matches(&make_input(None, dec!(20.01), pocket)));
+    }
+
+    #[test]
+    fn pocket_and_currency_must_all_match() {
+        let pocket = Uuid::new_v4();
+        let mut rule = make_rule(0);
+        rule.pocket_id = Some(pocket);
+        rule.currency = Some("usd".to_string());
+        let rule = CompiledRule::compile(rule).unwrap();
+
+        let mut input = make_input(None, dec!(5), pocket);
+        input.currency = "USD";
+        assert!(rule.matches(&input));
+
+        // Wrong currency
+        assert!(!rule.matches(&make_input(None, dec!(5), pocket)));
+
+        // Wrong pocket
+        input.pocket_id = Uuid::new_v4();
+        assert!(!rule.matches(&input));
+    }
+}
+
+// ============================================================================
+// Rule Evaluation Tests
+// ============================================================================
+
+mod evaluation {
+    use super::*;
+
+    #[test]
+    fn first_matching_rule_wins_per_field_and_tags_accumulate() {
+        // Given: two matching rules, the first only sets tags
+        let pocket = Uuid::new_v4();
+        let mut tagger = make_rule(0);
+        tagger.description_contains = Some("grab".to_string());
+        tagger.add_tags = vec!["ride".to_string()];
+        let mut transport = make_rule(1);
+        transport.description_contains = Some("grab".to_string());
+        transport.set_category_id = Some(2);
+        transport.add_tags = vec!["ride".to_string(), "work".to_string()];
+        let mut fallback = make_rule(2);
+        fallback.set_category_id = Some(99);
+
+        let rules = compile(vec![tagger, transport, fallback]);
+
+        // When
+        let outcome = evaluate(&rules, &make_input(Some("Grab ride"), dec!(8), pocket));
+
+        // Then: category from the first rule that sets one, deduplicated tags
+        assert_eq!(outcome.category_id, Some(2));
+        assert_eq!(outcome.pocket_id, None);
+        assert_eq!(outcome.tags, vec!["ride".to_string(), "work".to_string()]);
+        assert_eq!(outcome.matched_rule_ids.len(), 3);
+    }
+
+    #[test]
+    fn inactive_rules_are_skipped() {
+        let mut rule = make_rule(0);
+        rule.is_active = false;
+        rule.set_category_id = Some(1);
+        let rules = compile(vec![rule]);
+
+        let outcome = evaluate(&rules, &make_input(None, dec!(1), Uuid::new_v4()));
+
+        assert_eq!(outcome, Default::default());
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize, Serializer};
 use uuid::Uuid;
@@ -30,10 +30,47 @@ pub struct CreateTransaction {
     pub amount: Decimal,
     #[serde(default)]
     pub description: Option<String>,
-    pub category_id: i32,
+    /// Optional when a categorization rule can supply it
+    pub category_id: Option<i32>,
     pub occurred_at: DateTime<Utc>,
     pub currency_code: Option<String>,
     pub pocket_id: Option<Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Resolved from the description via payee aliases when omitted
+    pub payee_id: Option<Uuid>,
+    /// `pending` for card authorizations that have not posted yet
+    #[serde(default)]
+    pub status: TransactionStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    Pending,
+    #[default]
+    Posted,
+    Void,
+}
+
+impl TransactionStatus {
+    /// Value stored in `transactions.status`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Posted => "posted",
+            TransactionStatus::Void => "void",
+        }
+    }
+
+    /// Inverse of [`Self::as_str`]; the column's CHECK constraint only allows those values
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "pending" => TransactionStatus::Pending,
+            "void" => TransactionStatus::Void,
+            _ => TransactionStatus::Posted,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -57,6 +94,7 @@ pub struct TransactionQueryParams {
     pub start_date: Option<DateTime<Utc>>,
     pub end_date: Option<DateTime<Utc>>,
     pub pocket_id: Option<Uuid>,
+    pub status: Option<TransactionStatus>,
     #[serde(default = "default_page")]
     pub page: i64,
     #[serde(default = "default_limit")]
@@ -72,9 +110,28 @@ fn default_limit() -> i64 {
 }
 
 #[derive(Deserialize)]
-pub struct DateRangeParams {
+pub struct PaginationParams {
+    #[serde(default = "default_page")]
+    pub page: i64,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// Period the spending analysis is compared against
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonPeriod {
+    /// The same number of calendar months (or days) right before the range
+    Previous,
+    /// The same range one year earlier
+    LastYear,
+}
+
+#[derive(Deserialize)]
+pub struct SpendingAnalysisParams {
     pub start_date: DateTime<Utc>,
     pub end_date: DateTime<Utc>,
+    pub compare: Option<ComparisonPeriod>,
 }
 
 // --- Response DTOs ---
@@ -87,6 +144,11 @@ pub struct Transaction {
     pub description: Option<String>,
     pub category: Option<Category>,
     pub pocket: Option<PocketSummary>,
+    pub payee: Option<PayeeSummary>,
+    /// Set when this row is one leg of a pocket transfer
+    pub transfer_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub status: TransactionStatus,
     pub occurred_at: DateTime<Utc>,
     pub created_at: Option<DateTime<Utc>>,
 }
@@ -100,6 +162,25 @@ pub struct PaginatedTransactions {
     pub total_pages: i64,
 }
 
+/// A soft-deleted transaction as shown in the trash view
+#[derive(Serialize, Debug)]
+pub struct DeletedTransaction {
+    #[serde(flatten)]
+    pub transaction: Transaction,
+    pub deleted_at: DateTime<Utc>,
+    /// When the retention purge will permanently remove it
+    pub purge_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PaginatedDeletedTransactions {
+    pub transactions: Vec<DeletedTransaction>,
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+    pub total_pages: i64,
+}
+
 #[derive(Serialize, Debug)]
 pub struct TransactionDetail {
     pub id: Uuid,
@@ -107,6 +188,11 @@ pub struct TransactionDetail {
     pub amount: Decimal,
     pub description: Option<String>,
     pub category: Option<Category>,
+    pub pocket: Option<PocketSummary>,
+    pub payee: Option<PayeeSummary>,
+    pub transfer_id: Option<Uuid>,
+    pub tags: Vec<String>,
+    pub status: TransactionStatus,
     pub occurred_at: DateTime<Utc>,
     pub created_at: Option<DateTime<Utc>>,
     pub original_currency: Option<String>,
@@ -127,6 +213,12 @@ pub struct CreatePocket {
     pub name: String,
     pub description: Option<String>,
     pub icon: Option<String>,
+    /// Defaults to the user's base currency; cannot be changed later
+    pub currency: Option<String>,
+    #[serde(default)]
+    pub kind: PocketKind,
+    /// Required for credit card pockets
+    pub credit_card: Option<CreditCardSettings>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -142,8 +234,25 @@ pub struct Pocket {
     pub name: String,
     pub description: Option<String>,
     pub icon: String,
+    pub currency: String,
+    /// `cash` or `credit_card`
+    pub kind: String,
     pub is_default: bool,
+    /// Set while the pocket is archived
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: Option<DateTime<Utc>>,
+    /// Available balance in the pocket's currency: posted, less pending spending
+    #[serde(serialize_with = "round_currency")]
+    pub balance: Decimal,
+    /// Balance of posted transactions only
+    #[serde(serialize_with = "round_currency")]
+    pub posted_balance: Decimal,
+}
+
+impl Pocket {
+    pub fn is_credit_card(&self) -> bool {
+        self.kind == PocketKind::CreditCard.as_str()
+    }
 }
 
 /// Lightweight pocket info for embedding in transactions
@@ -152,182 +261,1354 @@ pub struct PocketSummary {
     pub id: Uuid,
     pub name: String,
     pub icon: String,
+    pub currency: String,
 }
 
-#[derive(Serialize)]
-pub struct PocketId {
-    pub id: Uuid,
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PocketKind {
+    #[default]
+    Cash,
+    CreditCard,
 }
 
-#[derive(Serialize, Debug)]
-pub struct CategorySummary {
-    pub category: String,
+impl PocketKind {
+    /// Value stored in `pockets.kind`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PocketKind::Cash => "cash",
+            PocketKind::CreditCard => "credit_card",
+        }
+    }
+}
+
+/// Statement cycle terms of a credit card pocket
+#[derive(Deserialize, Debug)]
+pub struct CreditCardSettings {
+    /// Day of the month the statement closes (1-31; short months use their last day)
+    pub statement_day: i16,
+    /// Days from closing to the payment due date (default 21)
+    pub due_days: Option<i16>,
+    pub credit_limit: Option<Decimal>,
+    /// Share of the statement balance due as a minimum payment, in percent (default 1)
+    pub minimum_payment_percent: Option<Decimal>,
+    /// Smallest minimum payment (default 0)
+    pub minimum_payment_floor: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CreditCard {
+    pub statement_day: i16,
+    pub due_days: i16,
+    #[serde(serialize_with = "round_currency_option")]
+    pub credit_limit: Option<Decimal>,
+    pub minimum_payment_percent: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub total: Decimal,
-    pub is_income: bool,
-    pub icon: String,
+    pub minimum_payment_floor: Decimal,
 }
 
+/// The last closed statement of a credit card pocket. Amounts owed are positive.
 #[derive(Serialize, Debug)]
-pub struct SpendingAnalysisResponse {
+pub struct CardStatement {
+    pub pocket_id: Uuid,
+    pub currency: String,
+    pub settings: CreditCard,
+    pub period_start: NaiveDate,
+    pub closing_date: NaiveDate,
+    pub due_date: NaiveDate,
     #[serde(serialize_with = "round_currency")]
-    pub total_income: Decimal,
+    pub statement_balance: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub total_spent: Decimal,
+    pub minimum_payment: Decimal,
+    /// Payments and refunds credited since the statement closed
     #[serde(serialize_with = "round_currency")]
-    pub net_income: Decimal,
-    pub categories: Vec<CategorySummary>,
-}
-
-#[derive(Serialize, Debug)]
-pub struct FinancialHealth {
+    pub paid_since_closing: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub cash_balance: Decimal,
+    pub remaining_statement_balance: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub investment_balance: Decimal,
+    pub remaining_minimum_payment: Decimal,
+    /// Owed right now, including spending after the statement closed
     #[serde(serialize_with = "round_currency")]
-    pub total_net_worth: Decimal,
+    pub current_balance: Decimal,
+    #[serde(serialize_with = "round_currency_option")]
+    pub available_credit: Option<Decimal>,
+    pub next_closing_date: NaiveDate,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PayCardRequest {
+    /// Cash pocket in the card's currency
+    pub source_pocket_id: Uuid,
+    /// Defaults to the remaining statement balance
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    /// Defaults to now
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct PocketQueryParams {
+    #[serde(default)]
+    pub include_archived: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct DeletePocketParams {
+    /// Pocket that receives the deleted pocket's transactions; required when it has any
+    pub reassign_to: Option<Uuid>,
+}
+
+/// What deleting a pocket moved to `reassign_to` and what it removed
+#[derive(Serialize, Debug, Default)]
+pub struct DeletePocketResponse {
+    /// Transfers between the deleted pocket and `reassign_to`, removed with both legs
+    pub removed_transfers: u64,
+    /// Reconciliations of the deleted pocket; its transactions move but these can't
+    pub removed_reconciliations: u64,
+    /// The deleted pocket's goal, now measured against `reassign_to`
+    pub moved_goals: u64,
+    /// Monthly envelope allocations added to `reassign_to`'s allocation for the same month
+    pub merged_allocations: u64,
+    /// Card statement settings moved to a `reassign_to` card that had none
+    pub moved_card_settings: u64,
+    /// Card statement settings dropped because `reassign_to` keeps its own
+    pub removed_card_settings: u64,
+}
+
+#[derive(Serialize)]
+pub struct PocketId {
+    pub id: Uuid,
+}
+
+/// Bucket size for time series; weeks start on Monday
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BalanceInterval {
+    #[default]
+    Day,
+    Week,
+    Month,
+}
+
+impl BalanceInterval {
+    /// Postgres `date_trunc` field name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BalanceInterval::Day => "day",
+            BalanceInterval::Week => "week",
+            BalanceInterval::Month => "month",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BalanceHistoryParams {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    #[serde(default)]
+    pub interval: BalanceInterval,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StatementFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct StatementParams {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    #[serde(default)]
+    pub format: StatementFormat,
 }
 
+/// Money in or out of a pocket for one category within a statement period
 #[derive(Serialize, Debug)]
-pub struct InvestmentSummary {
-    pub ticker: String,
-    pub name: String,
-    #[serde(serialize_with = "round_currency")]
-    pub quantity: Decimal,
+pub struct StatementLine {
+    pub category_id: i32,
+    pub category_name: String,
     #[serde(serialize_with = "round_currency")]
-    pub avg_buy_price: Decimal, // Native
+    pub total: Decimal,
+    pub transaction_count: i64,
+}
+
+/// Pocket activity over a period, in the pocket's currency.
+/// `closing_balance = opening_balance + total_inflows - total_outflows + transfers_in - transfers_out`
+#[derive(Serialize, Debug)]
+pub struct PocketStatement {
+    pub pocket: PocketSummary,
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
     #[serde(serialize_with = "round_currency")]
-    pub avg_buy_price_converted: Decimal, // Base
+    pub opening_balance: Decimal,
+    pub inflows: Vec<StatementLine>,
     #[serde(serialize_with = "round_currency")]
-    pub current_price: Decimal, // Native
+    pub total_inflows: Decimal,
+    pub outflows: Vec<StatementLine>,
     #[serde(serialize_with = "round_currency")]
-    pub current_price_converted: Decimal, // Base
+    pub total_outflows: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub total_value: Decimal, // Native
+    pub transfers_in: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub total_value_converted: Decimal, // Base
+    pub transfers_out: Decimal,
     #[serde(serialize_with = "round_currency")]
-    pub change_pct: Decimal,
-    pub currency: String,       // Base Currency
-    pub asset_currency: String, // Native Currency
-    pub icon_url: Option<String>,
+    pub closing_balance: Decimal,
 }
 
+/// Closing balance of a pocket at the end of a day or month
 #[derive(Serialize, Debug)]
-pub struct PortfolioResponse {
-    pub investments: Vec<InvestmentSummary>,
-    #[serde(serialize_with = "round_currency")]
-    pub total_cost: Decimal,
-    #[serde(serialize_with = "round_currency")]
-    pub absolute_change: Decimal,
+pub struct BalancePoint {
+    /// First day of the bucket
+    pub date: NaiveDate,
     #[serde(serialize_with = "round_currency")]
-    pub total_value: Decimal,
+    pub balance: Decimal,
 }
 
-// --- Auth DTOs ---
+// --- Goal DTOs ---
 
 #[derive(Deserialize, Debug)]
-pub struct RegisterRequest {
-    pub username: String,
-    pub email: String,
-    pub password: String,
-    pub base_currency: String,
+pub struct CreateGoal {
+    pub pocket_id: Uuid,
+    pub name: String,
+    /// In the pocket's currency
+    pub target_amount: Decimal,
+    pub target_date: Option<NaiveDate>,
+    /// Planned saving per month; projections fall back to the pocket's history without one
+    pub monthly_contribution: Option<Decimal>,
 }
 
 #[derive(Deserialize, Debug)]
-pub struct UpdateTransaction {
-    pub amount: Option<Decimal>,
-    pub description: Option<String>,
-    pub category_id: Option<i32>,
-    pub occurred_at: Option<DateTime<Utc>>,
-    pub original_currency: Option<String>,
-    pub original_amount: Option<Decimal>,
-    pub exchange_rate: Option<Decimal>,
+pub struct UpdateGoal {
+    pub name: Option<String>,
+    pub target_amount: Option<Decimal>,
+    pub target_date: Option<NaiveDate>,
+    pub monthly_contribution: Option<Decimal>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct UpdateCurrency {
-    pub base_currency: String,
+#[derive(Serialize, Debug)]
+pub struct Goal {
+    pub id: Uuid,
+    pub pocket: PocketSummary,
+    pub name: String,
+    #[serde(serialize_with = "round_currency")]
+    pub target_amount: Decimal,
+    pub target_date: Option<NaiveDate>,
+    #[serde(serialize_with = "round_currency_option")]
+    pub monthly_contribution: Option<Decimal>,
+    pub progress: GoalProgress,
+    pub created_at: Option<DateTime<Utc>>,
 }
 
-#[derive(Deserialize, Debug)]
-pub struct UpdateInvestment {
-    pub quantity: Option<Decimal>,
-    pub avg_buy_price: Option<Decimal>,
+/// Where a goal's projected completion comes from
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectionBasis {
+    /// The goal's `monthly_contribution`
+    Plan,
+    /// Average net monthly saving into the pocket over recent months
+    History,
 }
 
+/// Progress towards a goal, in the pocket's currency
 #[derive(Serialize, Debug)]
-pub struct UserProfile {
+pub struct GoalProgress {
+    #[serde(serialize_with = "round_currency")]
+    pub saved: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub remaining: Decimal,
+    /// 0-100, capped at 100 once the target is reached
+    #[serde(serialize_with = "round_currency")]
+    pub percent: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub average_monthly_saving: Decimal,
+    /// Saving needed each month to reach the target by `target_date`
+    #[serde(serialize_with = "round_currency_option")]
+    pub required_monthly_saving: Option<Decimal>,
+    pub projection_basis: ProjectionBasis,
+    /// None when the projected saving rate is not positive
+    pub projected_completion_date: Option<NaiveDate>,
+    /// Whether the projection reaches the target by `target_date`; None without a date
+    pub on_track: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct GoalId {
     pub id: Uuid,
-    pub username: String,
-    pub email: String,
-    pub base_currency: String,
-    pub joined_at: DateTime<Utc>,
 }
 
+// --- Budget DTOs ---
+
 #[derive(Deserialize, Debug)]
-pub struct LoginRequest {
-    pub email: String,
-    pub password: String,
+pub struct CreateBudget {
+    pub category_id: i32,
+    /// Monthly amount in base currency
+    pub amount: Decimal,
+    #[serde(default)]
+    pub rollover: bool,
+    /// Any day in the first month the budget applies to; defaults to the current month
+    pub starts_on: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateBudget {
+    pub amount: Option<Decimal>,
+    pub rollover: Option<bool>,
 }
 
 #[derive(Serialize, Debug)]
-pub struct AuthResponse {
-    pub token: String,
-    pub refresh_token: String,
-    pub message: String,
+pub struct Budget {
+    pub id: Uuid,
+    pub category_id: i32,
+    pub category_name: String,
+    pub category_icon: String,
+    #[serde(serialize_with = "round_currency")]
+    pub amount: Decimal,
+    pub rollover: bool,
+    pub starts_on: NaiveDate,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct BudgetId {
+    pub id: Uuid,
 }
 
 #[derive(Deserialize, Debug)]
-pub struct RefreshTokenRequest {
-    pub refresh_token: String,
+pub struct BudgetReportParams {
+    /// Any day in the month to report on; defaults to the current month
+    pub month: Option<NaiveDate>,
 }
 
-#[derive(Serialize, Debug, sqlx::FromRow)]
-pub struct RefreshTokenRow {
-    pub id: Uuid,
-    pub user_id: Uuid,
-    pub token_hash: String,
-    pub expires_at: DateTime<Utc>,
-    pub created_at: Option<DateTime<Utc>>,
-    pub replaced_by: Option<String>,
-    pub is_revoked: Option<bool>,
+/// Raised once spending reaches 80% (`warning`) or 100% (`exceeded`) of what is available
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetAlert {
+    Warning,
+    Exceeded,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct Asset {
-    pub ticker: String,
-    pub name: String,
-    pub asset_type: String,
-    pub api_ticker: Option<String>,
-    pub source: Option<String>,
+/// One category's budget against actual spending for a month, in base currency
+#[derive(Serialize, Debug)]
+pub struct BudgetLine {
+    pub budget_id: Uuid,
+    pub category_id: i32,
+    pub category_name: String,
+    pub category_icon: String,
+    #[serde(serialize_with = "round_currency")]
+    pub budgeted: Decimal,
+    /// Unspent (positive) or overspent (negative) amount rolled over from earlier months
+    #[serde(serialize_with = "round_currency")]
+    pub carried_over: Decimal,
+    /// `budgeted + carried_over`
+    #[serde(serialize_with = "round_currency")]
+    pub available: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub spent: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub remaining: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub percent_used: Decimal,
+    /// Month-end spending at the current pace; only set for the current month
     #[serde(serialize_with = "round_currency_option")]
-    pub current_price: Option<Decimal>,
-    pub currency: Option<String>,
-    pub icon_url: Option<String>,
+    pub projected_spend: Option<Decimal>,
+    pub alert: Option<BudgetAlert>,
 }
 
+#[derive(Serialize, Debug)]
+pub struct BudgetReport {
+    /// First day of the reported month
+    pub month: NaiveDate,
+    #[serde(serialize_with = "round_currency")]
+    pub total_available: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub total_spent: Decimal,
+    pub lines: Vec<BudgetLine>,
+    /// Expense categories with spending this month but no budget
+    pub unbudgeted: Vec<CategorySummary>,
+}
+
+// --- Envelope DTOs ---
+
+/// Set the amount assigned to one envelope for a month; give exactly one of
+/// `category_id` or `pocket_id`
 #[derive(Deserialize, Debug)]
-pub struct TransferRequest {
-    pub source_pocket_id: Uuid,
-    pub destination_pocket_id: Uuid,
+pub struct AssignEnvelope {
+    /// Any day in the month
+    pub month: NaiveDate,
+    pub category_id: Option<i32>,
+    pub pocket_id: Option<Uuid>,
     pub amount: Decimal,
-    pub description: Option<String>,
 }
 
-#[derive(Serialize, Debug, sqlx::FromRow)]
-pub struct Category {
-    pub id: i32,
-    pub name: String,
-    pub is_income: bool,
-    pub icon: String,
-    #[serde(default)]
-    pub exclude_from_analysis: bool,
+#[derive(Deserialize, Debug)]
+pub struct EnvelopeParams {
+    /// Any day in the month to report on; defaults to the current month
+    pub month: Option<NaiveDate>,
 }
 
-/// Internal struct for portfolio data joined with asset info (from repository)
-#[derive(Debug)]
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvelopeKind {
+    Pocket,
+    Category,
+}
+
+/// One envelope for a month, in base currency. Unspent money stays in the envelope,
+/// so `available` accumulates across months.
+#[derive(Serialize, Debug)]
+pub struct Envelope {
+    pub kind: EnvelopeKind,
+    pub category_id: Option<i32>,
+    pub pocket_id: Option<Uuid>,
+    pub name: String,
+    /// Assigned in this month
+    #[serde(serialize_with = "round_currency")]
+    pub assigned: Decimal,
+    /// Spent in this month
+    #[serde(serialize_with = "round_currency")]
+    pub spent: Decimal,
+    /// Everything assigned minus everything spent up to the end of this month
+    #[serde(serialize_with = "round_currency")]
+    pub available: Decimal,
+}
+
+#[derive(Serialize, Debug)]
+pub struct EnvelopeReport {
+    /// First day of the reported month
+    pub month: NaiveDate,
+    /// Income received in this month
+    #[serde(serialize_with = "round_currency")]
+    pub income: Decimal,
+    /// All income so far minus all assignments so far; negative when over-assigned
+    #[serde(serialize_with = "round_currency")]
+    pub to_be_assigned: Decimal,
+    pub envelopes: Vec<Envelope>,
+}
+
+// --- Bill DTOs ---
+
+#[derive(Deserialize, Debug)]
+pub struct CreateBill {
+    pub name: String,
+    pub payee_id: Uuid,
+    /// In the currency the bill is paid in
+    pub expected_amount: Decimal,
+    /// Day of the month the bill falls due (1-31; short months use their last day)
+    pub due_day: i16,
+    /// Only payments from this pocket count when set
+    pub pocket_id: Option<Uuid>,
+    /// Defaults to today
+    pub starts_on: Option<NaiveDate>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateBill {
+    pub name: Option<String>,
+    pub expected_amount: Option<Decimal>,
+    pub due_day: Option<i16>,
+    pub pocket_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Bill {
+    pub id: Uuid,
+    pub name: String,
+    pub payee: PayeeSummary,
+    #[serde(serialize_with = "round_currency")]
+    pub expected_amount: Decimal,
+    pub due_day: i16,
+    pub pocket: Option<PocketSummary>,
+    pub starts_on: NaiveDate,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct BillId {
+    pub id: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpcomingBillsParams {
+    /// How many days ahead to look (default 30)
+    pub days: Option<i64>,
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BillStatus {
+    Paid,
+    Upcoming,
+    Overdue,
+}
+
+/// One due date of a bill
+#[derive(Serialize, Debug)]
+pub struct BillOccurrence {
+    pub bill_id: Uuid,
+    pub name: String,
+    pub payee: PayeeSummary,
+    pub pocket: Option<PocketSummary>,
+    #[serde(serialize_with = "round_currency")]
+    pub expected_amount: Decimal,
+    pub due_date: NaiveDate,
+    pub status: BillStatus,
+    /// The transaction that paid this occurrence
+    pub transaction_id: Option<Uuid>,
+}
+
+// --- Notification DTOs ---
+
+#[derive(Serialize, Debug)]
+pub struct Notification {
+    pub id: Uuid,
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    pub read_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+// --- Liability DTOs ---
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentFrequency {
+    Weekly,
+    Biweekly,
+    Monthly,
+}
+
+impl PaymentFrequency {
+    /// Value stored in `liabilities.frequency`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PaymentFrequency::Weekly => "weekly",
+            PaymentFrequency::Biweekly => "biweekly",
+            PaymentFrequency::Monthly => "monthly",
+        }
+    }
+
+    /// Inverse of [`Self::as_str`]; the column's CHECK constraint only allows those values
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "weekly" => PaymentFrequency::Weekly,
+            "biweekly" => PaymentFrequency::Biweekly,
+            _ => PaymentFrequency::Monthly,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateLiability {
+    pub name: String,
+    pub principal: Decimal,
+    /// Nominal yearly interest rate in percent, e.g. 4.5
+    pub annual_rate: Decimal,
+    pub term_months: i32,
+    pub frequency: PaymentFrequency,
+    pub first_payment_on: NaiveDate,
+    /// Defaults to the base currency
+    pub currency: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateLiability {
+    pub name: Option<String>,
+    pub principal: Option<Decimal>,
+    pub annual_rate: Option<Decimal>,
+    pub term_months: Option<i32>,
+    pub frequency: Option<PaymentFrequency>,
+    pub first_payment_on: Option<NaiveDate>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Liability {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(serialize_with = "round_currency")]
+    pub principal: Decimal,
+    pub annual_rate: Decimal,
+    pub term_months: i32,
+    pub frequency: PaymentFrequency,
+    pub first_payment_on: NaiveDate,
+    pub currency: String,
+    /// Amount due each period under the original terms
+    #[serde(serialize_with = "round_currency")]
+    pub scheduled_payment: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub principal_paid: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub interest_paid: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub outstanding_balance: Decimal,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct LiabilityId {
+    pub id: Uuid,
+}
+
+/// One row of an amortization schedule
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AmortizationEntry {
+    pub number: u32,
+    pub due_date: NaiveDate,
+    #[serde(serialize_with = "round_currency")]
+    pub payment: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub principal: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub interest: Decimal,
+    /// Outstanding balance after this payment
+    #[serde(serialize_with = "round_currency")]
+    pub balance: Decimal,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RecordLiabilityPayment {
+    /// An expense paid from a pocket in the liability's currency
+    pub transaction_id: Uuid,
+}
+
+/// A recorded repayment split into principal and interest
+#[derive(Serialize, Debug)]
+pub struct LiabilityPayment {
+    pub transaction_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    #[serde(serialize_with = "round_currency")]
+    pub amount: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub principal: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub interest: Decimal,
+    /// Outstanding balance after this repayment
+    #[serde(serialize_with = "round_currency")]
+    pub balance: Decimal,
+}
+
+// --- Reconciliation DTOs ---
+
+#[derive(Deserialize, Debug)]
+pub struct StartReconciliation {
+    pub statement_date: NaiveDate,
+    /// Balance printed on the statement; negative for money owed
+    pub ending_balance: Decimal,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Reconciliation {
+    pub id: Uuid,
+    pub pocket: PocketSummary,
+    pub statement_date: NaiveDate,
+    #[serde(serialize_with = "round_currency")]
+    pub ending_balance: Decimal,
+    /// Cleared transactions up to the statement date, summed like the pocket balance
+    #[serde(serialize_with = "round_currency")]
+    pub cleared_balance: Decimal,
+    /// Statement balance minus cleared balance; zero when everything is ticked off
+    #[serde(serialize_with = "round_currency")]
+    pub difference: Decimal,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A transaction that can still be ticked off against the statement
+#[derive(Serialize, Debug)]
+pub struct ReconciliationItem {
+    pub id: Uuid,
+    pub description: Option<String>,
+    /// Signed like its effect on the pocket balance
+    #[serde(serialize_with = "round_currency")]
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+    pub cleared: bool,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ReconciliationDetail {
+    #[serde(flatten)]
+    pub reconciliation: Reconciliation,
+    /// Unreconciled transactions up to the statement date; empty once completed
+    pub transactions: Vec<ReconciliationItem>,
+}
+
+#[derive(Serialize)]
+pub struct ReconciliationId {
+    pub id: Uuid,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct ClearTransactions {
+    pub transaction_ids: Vec<Uuid>,
+    /// `false` unticks them again
+    pub cleared: bool,
+}
+
+// --- Net Worth DTOs ---
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NetWorthItemKind {
+    /// A pocket, or a credit card in credit
+    Cash,
+    Investment,
+    /// A liability, or a credit card's debt
+    Liability,
+}
+
+/// One pocket, asset or liability behind a net-worth snapshot
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NetWorthItem {
+    pub kind: NetWorthItemKind,
+    /// Pocket or liability id; none for assets
+    pub id: Option<Uuid>,
+    /// Pocket or liability name, or the asset's ticker
+    pub name: String,
+    pub currency: String,
+    /// In `currency`; what is owed for liabilities
+    pub amount: Decimal,
+    /// Base-currency units per unit of `currency`
+    pub rate: Decimal,
+    /// `amount * rate`, in base currency
+    pub value: Decimal,
+}
+
+/// Net worth at the end of a day, in the base currency of the time
+#[derive(Serialize, Debug)]
+pub struct NetWorthSnapshot {
+    pub date: NaiveDate,
+    pub base_currency: String,
+    #[serde(serialize_with = "round_currency")]
+    pub cash_balance: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub investment_balance: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub liabilities_balance: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub total_net_worth: Decimal,
+    /// Reconstructed from transaction history; investments are not included since past
+    /// holdings and prices are unknown
+    pub is_backfill: bool,
+    pub items: Vec<NetWorthItem>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct NetWorthHistoryParams {
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+}
+
+/// Days to reconstruct; defaults to the day of the first transaction through yesterday.
+/// Days that already have a snapshot are left alone.
+#[derive(Deserialize, Debug, Default)]
+pub struct BackfillNetWorth {
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+// --- Payee DTOs ---
+
+#[derive(Deserialize, Debug)]
+pub struct CreatePayee {
+    pub name: String,
+    pub default_category_id: Option<i32>,
+    /// Extra spellings to match; the payee name itself is always an alias
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdatePayee {
+    pub name: Option<String>,
+    pub default_category_id: Option<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct AddPayeeAlias {
+    pub alias: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct Payee {
+    pub id: Uuid,
+    pub name: String,
+    pub default_category_id: Option<i32>,
+    pub aliases: Vec<PayeeAlias>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct PayeeAlias {
+    pub id: Uuid,
+    pub alias: String,
+}
+
+/// Lightweight payee info for embedding in transactions
+#[derive(Serialize, Debug, Clone)]
+pub struct PayeeSummary {
+    pub id: Uuid,
+    pub name: String,
+}
+
+#[derive(Serialize)]
+pub struct PayeeId {
+    pub id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct TopPayeesParams {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+/// Spending at one payee within the requested period
+#[derive(Serialize, Debug)]
+pub struct PayeeSpending {
+    pub payee_id: Uuid,
+    pub name: String,
+    #[serde(serialize_with = "round_currency")]
+    pub total: Decimal,
+    pub transaction_count: i64,
+    pub last_occurred_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CategorySummary {
+    pub category_id: i32,
+    pub category: String,
+    #[serde(serialize_with = "round_currency")]
+    pub total: Decimal,
+    pub is_income: bool,
+    pub icon: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SpendingAnalysisResponse {
+    #[serde(serialize_with = "round_currency")]
+    pub total_income: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub total_spent: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub net_income: Decimal,
+    pub categories: Vec<CategorySummary>,
+    /// Present when a comparison period was requested
+    pub comparison: Option<SpendingComparison>,
+}
+
+/// A value in the analysed range next to the same value in the comparison period
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Delta {
+    #[serde(serialize_with = "round_currency")]
+    pub current: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub previous: Decimal,
+    /// `current - previous`
+    #[serde(serialize_with = "round_currency")]
+    pub change: Decimal,
+    /// Change relative to the previous value; null when the previous value was zero
+    #[serde(serialize_with = "round_currency_option")]
+    pub change_percent: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CategoryDelta {
+    pub category_id: i32,
+    pub category: String,
+    pub is_income: bool,
+    pub icon: String,
+    #[serde(flatten)]
+    pub delta: Delta,
+}
+
+#[derive(Serialize, Debug)]
+pub struct SpendingComparison {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    pub total_income: Delta,
+    pub total_spent: Delta,
+    pub net_income: Delta,
+    /// Every category with activity in either period
+    pub categories: Vec<CategoryDelta>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CashFlowBreakdown {
+    Category,
+    Pocket,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CashFlowParams {
+    pub start_date: DateTime<Utc>,
+    pub end_date: DateTime<Utc>,
+    #[serde(default)]
+    pub interval: BalanceInterval,
+    /// Split each bucket by category or by pocket
+    pub breakdown: Option<CashFlowBreakdown>,
+    pub pocket_id: Option<Uuid>,
+}
+
+/// Income and spending of one category or pocket within a cash-flow bucket
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CashFlowLine {
+    pub category_id: Option<i32>,
+    pub pocket_id: Option<Uuid>,
+    pub name: String,
+    #[serde(serialize_with = "round_currency")]
+    pub income: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub spending: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub net: Decimal,
+}
+
+/// Income and spending within one day, week or month, in base currency
+#[derive(Serialize, Debug)]
+pub struct CashFlowPoint {
+    /// First day of the bucket
+    pub date: NaiveDate,
+    #[serde(serialize_with = "round_currency")]
+    pub income: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub spending: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub net: Decimal,
+    /// Empty unless a breakdown was requested
+    pub breakdown: Vec<CashFlowLine>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct FinancialHealth {
+    /// Available cash: posted, less pending spending
+    #[serde(serialize_with = "round_currency")]
+    pub cash_balance: Decimal,
+    /// Cash counting posted transactions only
+    #[serde(serialize_with = "round_currency")]
+    pub posted_cash_balance: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub investment_balance: Decimal,
+    /// Outstanding balance of all liabilities, credit card debt included
+    #[serde(serialize_with = "round_currency")]
+    pub liabilities_balance: Decimal,
+    /// Cash plus investments minus liabilities
+    #[serde(serialize_with = "round_currency")]
+    pub total_net_worth: Decimal,
+}
+
+#[derive(Serialize, Debug)]
+pub struct InvestmentSummary {
+    pub ticker: String,
+    pub name: String,
+    #[serde(serialize_with = "round_currency")]
+    pub quantity: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub avg_buy_price: Decimal, // Native
+    #[serde(serialize_with = "round_currency")]
+    pub avg_buy_price_converted: Decimal, // Base
+    #[serde(serialize_with = "round_currency")]
+    pub current_price: Decimal, // Native
+    #[serde(serialize_with = "round_currency")]
+    pub current_price_converted: Decimal, // Base
+    #[serde(serialize_with = "round_currency")]
+    pub total_value: Decimal, // Native
+    #[serde(serialize_with = "round_currency")]
+    pub total_value_converted: Decimal, // Base
+    #[serde(serialize_with = "round_currency")]
+    pub change_pct: Decimal,
+    pub currency: String,       // Base Currency
+    pub asset_currency: String, // Native Currency
+    pub icon_url: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PortfolioResponse {
+    pub investments: Vec<InvestmentSummary>,
+    #[serde(serialize_with = "round_currency")]
+    pub total_cost: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub absolute_change: Decimal,
+    #[serde(serialize_with = "round_currency")]
+    pub total_value: Decimal,
+}
+
+// --- Auth DTOs ---
+
+#[derive(Deserialize, Debug)]
+pub struct RegisterRequest {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+    pub base_currency: String,
+}
+
+/// Partial transaction update. Amounts follow the same rules as `CreateTransaction`:
+/// `amount` is in `currency_code` (or the transaction's current currency when omitted) and the
+/// base-currency amount is recomputed server-side. Setting `currency_code` to the base currency
+/// clears the foreign-currency fields.
+#[derive(Deserialize, Debug)]
+pub struct UpdateTransaction {
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    pub category_id: Option<i32>,
+    pub occurred_at: Option<DateTime<Utc>>,
+    pub currency_code: Option<String>,
+    /// Overrides the looked-up rate (units of base currency per unit of `currency_code`)
+    pub exchange_rate: Option<Decimal>,
+    pub pocket_id: Option<Uuid>,
+    /// Replaces the existing tags when provided
+    pub tags: Option<Vec<String>>,
+    pub payee_id: Option<Uuid>,
+    /// Remove the payee; can't be combined with `payee_id`
+    #[serde(default)]
+    pub clear_payee: bool,
+}
+
+/// Settle a pending transaction, optionally for a different amount (in the transaction's
+/// currency) or on a different date
+#[derive(Deserialize, Debug, Default)]
+pub struct PostTransaction {
+    pub amount: Option<Decimal>,
+    pub occurred_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RevertTransactionRequest {
+    /// Version to restore; 0 is the state before the first recorded edit
+    pub version: i32,
+}
+
+/// Full set of editable transaction fields, stored as JSON in `transaction_versions`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TransactionSnapshot {
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub category_id: Option<i32>,
+    pub occurred_at: DateTime<Utc>,
+    pub original_currency: Option<String>,
+    pub original_amount: Option<Decimal>,
+    pub exchange_rate: Option<Decimal>,
+    pub pocket_id: Uuid,
+    // Older versions were recorded before tags and payees existed
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub payee_id: Option<Uuid>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct TransactionVersion {
+    pub id: Uuid,
+    pub version: i32,
+    pub changed_by: Uuid,
+    pub changed_at: DateTime<Utc>,
+    pub changed_fields: Vec<&'static str>,
+    pub old_values: TransactionSnapshot,
+    pub new_values: TransactionSnapshot,
+}
+
+// --- Duplicate Detection DTOs ---
+
+/// One side of a flagged duplicate pair
+#[derive(Serialize, Debug)]
+pub struct DuplicateSide {
+    pub id: Uuid,
+    #[serde(serialize_with = "round_currency")]
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub category_id: Option<i32>,
+    pub occurred_at: DateTime<Utc>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// A pending pair of likely duplicates; `transaction` is the newer entry
+#[derive(Serialize, Debug)]
+pub struct DuplicatePair {
+    pub id: Uuid,
+    pub score: i32,
+    pub pocket_id: Uuid,
+    pub transaction: DuplicateSide,
+    pub duplicate_of: DuplicateSide,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct MergeDuplicateRequest {
+    /// Which side of the pair to keep; defaults to the older entry
+    pub keep_id: Option<Uuid>,
+}
+
+// --- Batch DTOs ---
+
+/// A single operation inside a `POST /transactions/batch` request.
+/// Tagged by `op`, e.g. `{"op": "delete", "id": "..."}`.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Create(CreateTransaction),
+    Update {
+        id: Uuid,
+        #[serde(flatten)]
+        changes: UpdateTransaction,
+    },
+    Delete {
+        id: Uuid,
+    },
+    Recategorize {
+        id: Uuid,
+        category_id: i32,
+    },
+}
+
+impl BatchOperation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BatchOperation::Create(_) => "create",
+            BatchOperation::Update { .. } => "update",
+            BatchOperation::Delete { .. } => "delete",
+            BatchOperation::Recategorize { .. } => "recategorize",
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+pub struct BatchTransactionRequest {
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BatchOperationResult {
+    pub index: usize,
+    pub op: &'static str,
+    pub id: Uuid,
+}
+
+#[derive(Serialize, Debug)]
+pub struct BatchTransactionResponse {
+    pub results: Vec<BatchOperationResult>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateCurrency {
+    pub base_currency: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateBudgetingMode {
+    pub zero_based: bool,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateInvestment {
+    pub quantity: Option<Decimal>,
+    pub avg_buy_price: Option<Decimal>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct UserProfile {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub base_currency: String,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct LoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct AuthResponse {
+    pub token: String,
+    pub refresh_token: String,
+    pub message: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct RefreshTokenRow {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: Option<DateTime<Utc>>,
+    pub replaced_by: Option<String>,
+    pub is_revoked: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Asset {
+    pub ticker: String,
+    pub name: String,
+    pub asset_type: String,
+    pub api_ticker: Option<String>,
+    pub source: Option<String>,
+    #[serde(serialize_with = "round_currency_option")]
+    pub current_price: Option<Decimal>,
+    pub currency: Option<String>,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct TransferRequest {
+    pub source_pocket_id: Uuid,
+    pub destination_pocket_id: Uuid,
+    /// Leaves the source pocket, in its currency, fee included
+    pub amount: Decimal,
+    pub description: Option<String>,
+    /// Defaults to now
+    pub occurred_at: Option<DateTime<Utc>>,
+    /// Source-to-destination rate for cross-currency transfers; the market rate when omitted
+    pub exchange_rate: Option<Decimal>,
+    /// FX fee in the source currency, deducted before conversion
+    pub fee: Option<Decimal>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct UpdateTransfer {
+    pub source_pocket_id: Option<Uuid>,
+    pub destination_pocket_id: Option<Uuid>,
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    pub occurred_at: Option<DateTime<Utc>>,
+    pub exchange_rate: Option<Decimal>,
+    pub fee: Option<Decimal>,
+}
+
+/// A transfer between two pockets together with the ids of its two legs
+#[derive(Serialize, Debug)]
+pub struct Transfer {
+    pub id: Uuid,
+    pub source_pocket: PocketSummary,
+    pub destination_pocket: PocketSummary,
+    /// In the source pocket's currency
+    #[serde(serialize_with = "round_currency")]
+    pub amount: Decimal,
+    /// In the destination pocket's currency
+    #[serde(serialize_with = "round_currency")]
+    pub destination_amount: Decimal,
+    pub exchange_rate: Option<Decimal>,
+    #[serde(serialize_with = "round_currency")]
+    pub fee: Decimal,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub out_transaction_id: Uuid,
+    pub in_transaction_id: Uuid,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PaginatedTransfers {
+    pub transfers: Vec<Transfer>,
+    pub total: i64,
+    pub page: i64,
+    pub limit: i64,
+    pub total_pages: i64,
+}
+
+#[derive(Serialize)]
+pub struct TransferId {
+    pub id: Uuid,
+}
+
+// --- Categorization Rule DTOs ---
+
+/// Body for creating or replacing a rule. Every condition that is set must match.
+#[derive(Deserialize, Debug)]
+pub struct RuleRequest {
+    pub name: String,
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+    pub description_contains: Option<String>,
+    pub description_regex: Option<String>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub pocket_id: Option<Uuid>,
+    pub currency: Option<String>,
+    pub set_category_id: Option<i32>,
+    pub set_pocket_id: Option<Uuid>,
+    #[serde(default)]
+    pub add_tags: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub struct CategorizationRule {
+    pub id: Uuid,
+    pub name: String,
+    pub priority: i32,
+    pub is_active: bool,
+    pub description_contains: Option<String>,
+    pub description_regex: Option<String>,
+    #[serde(serialize_with = "round_currency_option")]
+    pub min_amount: Option<Decimal>,
+    #[serde(serialize_with = "round_currency_option")]
+    pub max_amount: Option<Decimal>,
+    pub pocket_id: Option<Uuid>,
+    pub currency: Option<String>,
+    pub set_category_id: Option<i32>,
+    pub set_pocket_id: Option<Uuid>,
+    pub add_tags: Vec<String>,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize)]
+pub struct RuleId {
+    pub id: Uuid,
+}
+
+/// A transaction the rule would change, with its current and proposed values
+#[derive(Serialize, Debug)]
+pub struct RuleDryRunItem {
+    pub transaction_id: Uuid,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub current_category_id: Option<i32>,
+    pub new_category_id: Option<i32>,
+    pub current_pocket_id: Uuid,
+    pub new_pocket_id: Option<Uuid>,
+    pub added_tags: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct RuleDryRunResponse {
+    pub rule_id: Uuid,
+    pub affected: usize,
+    pub changes: Vec<RuleDryRunItem>,
+    /// Matching transactions left in their pocket because their currency doesn't fit the
+    /// rule's target pocket
+    pub pocket_skipped: Vec<Uuid>,
+}
+
+#[derive(Serialize, Debug, sqlx::FromRow)]
+pub struct Category {
+    pub id: i32,
+    pub name: String,
+    pub is_income: bool,
+    pub icon: String,
+    #[serde(default)]
+    pub exclude_from_analysis: bool,
+}
+
+/// Internal struct for portfolio data joined with asset info (from repository)
+#[derive(Debug)]
 pub struct PortfolioJoinedRow {
     pub ticker: String,
     pub name: String,
@@ -339,3 +1620,334 @@ pub struct PortfolioJoinedRow {
     pub currency: Option<String>,
     pub icon_url: Option<String>,
 }
+
+/// Internal struct for inserting a transaction (amounts already converted to base currency)
+#[derive(Debug)]
+pub struct NewTransaction {
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub category_id: i32,
+    pub occurred_at: DateTime<Utc>,
+    pub original_currency: Option<String>,
+    pub original_amount: Option<Decimal>,
+    pub exchange_rate: Option<Decimal>,
+    pub pocket_id: Uuid,
+    pub tags: Vec<String>,
+    pub payee_id: Option<Uuid>,
+    pub transfer_id: Option<Uuid>,
+    pub status: TransactionStatus,
+}
+
+/// Internal struct for partial transaction updates (None leaves the column unchanged)
+#[derive(Debug, Default)]
+pub struct TransactionChanges {
+    pub amount: Option<Decimal>,
+    pub description: Option<String>,
+    pub category_id: Option<i32>,
+    pub occurred_at: Option<DateTime<Utc>>,
+    pub original_currency: Option<String>,
+    pub original_amount: Option<Decimal>,
+    pub exchange_rate: Option<Decimal>,
+    pub pocket_id: Option<Uuid>,
+    pub tags: Option<Vec<String>>,
+    pub payee_id: Option<Uuid>,
+    /// Set original_currency/original_amount/exchange_rate to NULL
+    pub clear_foreign_currency: bool,
+    /// Set payee_id to NULL
+    pub clear_payee: bool,
+}
+
+/// Internal struct for a stored transaction version (from repository)
+#[derive(Debug)]
+pub struct TransactionVersionRow {
+    pub id: Uuid,
+    pub version: i32,
+    pub changed_by: Uuid,
+    pub changed_at: DateTime<Utc>,
+    pub old_values: TransactionSnapshot,
+    pub new_values: TransactionSnapshot,
+}
+
+/// Internal struct for a transfer locked for editing (from repository)
+#[derive(Debug)]
+pub struct TransferRow {
+    pub id: Uuid,
+    pub source_pocket_id: Uuid,
+    pub destination_pocket_id: Uuid,
+    pub amount: Decimal,
+    pub destination_amount: Decimal,
+    pub exchange_rate: Option<Decimal>,
+    pub fee: Decimal,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub out_transaction_id: Uuid,
+    pub in_transaction_id: Uuid,
+}
+
+/// Internal struct for a transfer to insert (from service)
+#[derive(Debug)]
+pub struct NewTransfer {
+    pub source_pocket_id: Uuid,
+    pub destination_pocket_id: Uuid,
+    pub amount: Decimal,
+    pub destination_amount: Decimal,
+    pub exchange_rate: Option<Decimal>,
+    pub fee: Decimal,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Internal struct for a pocket's activity in one category (from repository).
+/// `total` is unsigned and in the pocket's currency.
+#[derive(Debug)]
+pub struct StatementRow {
+    pub category_id: i32,
+    pub category_name: String,
+    pub is_income: bool,
+    pub is_transfer: bool,
+    pub total: Decimal,
+    pub transaction_count: i64,
+}
+
+/// Internal struct for a goal with its pocket and current balance (from repository)
+#[derive(Debug)]
+pub struct GoalRow {
+    pub id: Uuid,
+    pub pocket_id: Uuid,
+    pub pocket_name: String,
+    pub pocket_icon: String,
+    pub pocket_currency: String,
+    pub name: String,
+    pub target_amount: Decimal,
+    pub target_date: Option<NaiveDate>,
+    pub monthly_contribution: Option<Decimal>,
+    pub balance: Decimal,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Internal struct for a pocket's net flow in one calendar month (from repository)
+#[derive(Debug, Clone)]
+pub struct MonthlyFlowRow {
+    pub pocket_id: Uuid,
+    /// First day of the month
+    pub month: NaiveDate,
+    pub net: Decimal,
+}
+
+/// Internal struct for an envelope assignment (from repository)
+#[derive(Debug)]
+pub struct EnvelopeAllocationRow {
+    pub month: NaiveDate,
+    pub category_id: Option<i32>,
+    pub pocket_id: Option<Uuid>,
+    pub name: String,
+    pub amount: Decimal,
+}
+
+/// Internal struct for income or spending per month and envelope (from repository).
+/// `pocket_id` is set when the money moved through a pocket that is itself an envelope;
+/// `name` is that pocket's name, otherwise the category's.
+#[derive(Debug)]
+pub struct EnvelopeActivityRow {
+    pub month: NaiveDate,
+    pub category_id: i32,
+    pub pocket_id: Option<Uuid>,
+    pub name: String,
+    pub is_income: bool,
+    pub total: Decimal,
+}
+
+/// Internal struct for a bill with its payee and pocket (from repository)
+#[derive(Debug, Clone)]
+pub struct BillRow {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub payee_id: Uuid,
+    pub payee_name: String,
+    pub expected_amount: Decimal,
+    pub due_day: i16,
+    pub pocket_id: Option<Uuid>,
+    pub pocket_name: Option<String>,
+    pub pocket_icon: Option<String>,
+    pub pocket_currency: Option<String>,
+    pub starts_on: NaiveDate,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Internal struct for the transaction that paid a bill occurrence (from repository)
+#[derive(Debug)]
+pub struct BillPaymentRow {
+    pub bill_id: Uuid,
+    pub due_date: NaiveDate,
+    pub transaction_id: Uuid,
+}
+
+/// Internal struct for a transaction checked against bills (from repository).
+/// `amount` is in the currency the transaction was made in.
+#[derive(Debug)]
+pub struct BillMatchRow {
+    pub id: Uuid,
+    pub payee_id: Option<Uuid>,
+    pub pocket_id: Option<Uuid>,
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+    pub is_income: bool,
+}
+
+/// Internal struct for a notification to deliver (to a `Notifier`)
+#[derive(Debug)]
+pub struct NewNotification {
+    pub user_id: Uuid,
+    pub kind: &'static str,
+    pub title: String,
+    pub body: String,
+}
+
+/// Internal struct for a liability (from repository)
+#[derive(Debug, Clone)]
+pub struct LiabilityRow {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub principal: Decimal,
+    pub annual_rate: Decimal,
+    pub term_months: i32,
+    pub frequency: String,
+    pub first_payment_on: NaiveDate,
+    pub currency: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Internal struct for a repayment of a liability (from repository).
+/// `amount` is in the currency of the pocket it was paid from.
+#[derive(Debug, Clone)]
+pub struct LiabilityPaymentRow {
+    pub liability_id: Uuid,
+    pub transaction_id: Uuid,
+    pub occurred_at: DateTime<Utc>,
+    pub amount: Decimal,
+}
+
+/// Internal struct for a transaction checked before it is recorded as a repayment (from repository)
+#[derive(Debug)]
+pub struct RepaymentCandidateRow {
+    pub currency: String,
+    pub is_income: bool,
+    pub is_transfer: bool,
+}
+
+/// Internal struct for a credit card's balance at its last statement closing and the
+/// credits since (from repository)
+#[derive(Debug)]
+pub struct CardActivityRow {
+    /// Signed pocket balance at the end of the closing date
+    pub balance_at_closing: Decimal,
+    pub credits_since_closing: Decimal,
+}
+
+/// Internal struct for a reconciliation with its pocket and cleared balance (from repository)
+#[derive(Debug)]
+pub struct ReconciliationRow {
+    pub id: Uuid,
+    pub pocket_id: Uuid,
+    pub pocket_name: String,
+    pub pocket_icon: String,
+    pub pocket_currency: String,
+    pub statement_date: NaiveDate,
+    pub ending_balance: Decimal,
+    pub cleared_balance: Decimal,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Internal struct for the base-currency total of one category and pocket within a cash-flow
+/// bucket (from repository)
+#[derive(Debug)]
+pub struct CashFlowRow {
+    pub bucket: NaiveDate,
+    pub category_id: i32,
+    pub category_name: String,
+    pub is_income: bool,
+    pub pocket_id: Option<Uuid>,
+    pub pocket_name: Option<String>,
+    pub total: Decimal,
+}
+
+/// Internal struct for a pocket's balance at the end of a day, in its currency (from repository)
+#[derive(Debug)]
+pub struct PocketDayBalanceRow {
+    pub day: NaiveDate,
+    pub pocket_id: Uuid,
+    pub name: String,
+    pub currency: String,
+    pub kind: String,
+    pub balance: Decimal,
+}
+
+/// Internal struct for the cash held in one currency (from repository)
+#[derive(Debug)]
+pub struct CurrencyBalanceRow {
+    pub currency: String,
+    /// Posted, less pending spending
+    pub balance: Decimal,
+    pub posted_balance: Decimal,
+}
+
+/// Internal struct for a stored idempotency key (from repository)
+#[derive(Debug)]
+pub struct IdempotencyRecord {
+    pub request_hash: String,
+    pub response_status: Option<i32>,
+    pub response_body: Option<Vec<u8>>,
+}
+
+/// Internal struct for a transaction entered in a foreign currency (from repository)
+#[derive(Debug)]
+pub struct ForeignTransactionRow {
+    pub id: Uuid,
+    pub original_currency: String,
+    pub original_amount: Decimal,
+    pub exchange_rate: Option<Decimal>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Internal struct with the fields categorization rules look at (from repository)
+#[derive(Debug)]
+pub struct RuleCandidateRow {
+    pub id: Uuid,
+    pub description: Option<String>,
+    pub amount: Decimal,
+    pub original_amount: Option<Decimal>,
+    pub original_currency: Option<String>,
+    pub category_id: Option<i32>,
+    pub pocket_id: Uuid,
+    pub tags: Vec<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// Internal struct mapping a normalized alias to its payee (from repository)
+#[derive(Debug, Clone)]
+pub struct PayeeAliasRow {
+    pub payee_id: Uuid,
+    pub alias: String,
+    pub default_category_id: Option<i32>,
+}
+
+/// Internal struct for an existing transaction compared against a new one (from repository)
+#[derive(Debug)]
+pub struct DuplicateCandidateRow {
+    pub id: Uuid,
+    pub amount: Decimal,
+    pub description: Option<String>,
+    pub occurred_at: DateTime<Utc>,
+    pub pocket_id: Uuid,
+}
+
+/// Internal struct for the two transactions of a pending pair (from repository)
+#[derive(Debug)]
+pub struct DuplicatePairRow {
+    pub id: Uuid,
+    pub transaction_id: Uuid,
+    pub duplicate_of_id: Uuid,
+}
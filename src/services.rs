@@ -1,19 +1,53 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Months, NaiveDate, Utc};
 use rust_decimal::Decimal;
+use sqlx::PgConnection;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use uuid::Uuid;
 
+use crate::amortization;
 use crate::auth::{Claims, get_keys, hash_password, verify_password};
-use crate::error::AppError;
+use crate::bills;
+use crate::budgets;
+use crate::cash_flow;
+use crate::comparison;
+use crate::credit_cards;
+use crate::duplicates::{self, DuplicateInput};
+use crate::envelopes;
+use crate::error::{AppError, BatchItemError};
+use crate::goals;
 use crate::investments;
+use crate::net_worth;
+use crate::notifications::Notifier;
+use crate::payees;
+use crate::pockets;
 use crate::repository::{
-    PocketRepository, PortfolioRepository, SettingsRepository, TransactionRepository,
+    BillRepository, BudgetRepository, DuplicateRepository, EnvelopeRepository,
+    ExchangeRateRepository, GoalRepository, LiabilityRepository, NetWorthRepository,
+    NotificationRepository, PayeeRepository, PocketRepository, PortfolioRepository,
+    ReconciliationRepository, RuleRepository, SettingsRepository, TransactionRepository,
     UserRepository,
 };
+use crate::rules::{self, CompiledRule, RuleInput};
 use crate::schemas::{
-    AuthResponse, Category, CreatePocket, CreatePortfolioItem, CreateTransaction, FinancialHealth,
-    LoginRequest, Pocket, RegisterRequest, TransactionDetail, UpdateInvestment, UpdatePocket,
-    UserProfile,
+    AmortizationEntry, AssignEnvelope, AuthResponse, BackfillNetWorth, BalanceHistoryParams,
+    BalancePoint, BatchOperation, BatchOperationResult, BatchTransactionRequest,
+    BatchTransactionResponse, Bill, BillOccurrence, BillRow, BillStatus, Budget, BudgetLine,
+    BudgetReport, CardStatement, CashFlowParams, CashFlowPoint, CategorizationRule, Category,
+    CategorySummary, ClearTransactions, CreateBill, CreateBudget, CreateGoal, CreateLiability,
+    CreatePayee, CreatePocket, CreatePortfolioItem, CreateTransaction, CreditCard,
+    CreditCardSettings, DeletePocketResponse, DuplicatePair, EnvelopeReport, FinancialHealth, Goal,
+    GoalRow, Liability, LiabilityPayment, LiabilityPaymentRow, LiabilityRow, LoginRequest,
+    MergeDuplicateRequest, MonthlyFlowRow, NetWorthHistoryParams, NetWorthSnapshot,
+    NewNotification, NewTransaction, NewTransfer, Notification, PaginatedTransfers, PayCardRequest,
+    Payee, PayeeAliasRow, PayeeSpending, PayeeSummary, PaymentFrequency, Pocket,
+    PocketDayBalanceRow, PocketKind, PocketStatement, PocketSummary, Reconciliation,
+    ReconciliationDetail, ReconciliationRow, RecordLiabilityPayment, RegisterRequest,
+    RuleDryRunItem, RuleDryRunResponse, RuleRequest, StartReconciliation, TransactionChanges,
+    TransactionDetail, TransactionSnapshot, TransactionStatus, TransactionVersion, Transfer,
+    TransferRequest, TransferRow, UpdateBill, UpdateBudget, UpdateGoal, UpdateInvestment,
+    UpdateLiability, UpdatePayee, UpdatePocket, UpdateTransfer, UserProfile,
 };
+use crate::statements;
 
 use jsonwebtoken::{Header, encode};
 
@@ -74,7 +108,9 @@ impl AuthService {
             .await?;
 
         // Create default pocket for the new user
-        self.pocket_repo.create_default_for_user(user_id).await?;
+        self.pocket_repo
+            .create_default_for_user(user_id, &req.base_currency)
+            .await?;
 
         // Auto-login (generate token)
         let (token, refresh_token) = self.generate_tokens(user_id).await?;
@@ -201,11 +237,56 @@ impl AuthService {
     }
 }
 
+/// Upper bound on operations accepted by a single batch request
+const MAX_BATCH_OPERATIONS: usize = 200;
+
+/// A batch operation that passed validation and is ready to be written
+enum PreparedOperation {
+    Create(NewTransaction),
+    Update(Uuid, TransactionChanges),
+    Delete(Uuid),
+}
+
+/// An amount converted to base currency, with the original values kept for foreign currencies
+struct ConvertedAmount {
+    amount: Decimal,
+    original_currency: Option<String>,
+    original_amount: Option<Decimal>,
+    exchange_rate: Option<Decimal>,
+}
+
+/// Per-user lookups needed to prepare new transactions, loaded once per request
+struct CreateContext {
+    rules: Vec<CompiledRule>,
+    payee_aliases: Vec<PayeeAliasRow>,
+    pockets: Vec<Pocket>,
+    base_currency: String,
+}
+
+impl CreateContext {
+    fn pocket(&self, id: Uuid) -> Result<&Pocket, AppError> {
+        self.pockets
+            .iter()
+            .find(|p| p.id == id)
+            .ok_or(AppError::ValidationError("Pocket not found".to_string()))
+    }
+}
+
+/// What a transaction is matched against as it is recorded: categorization rules, payees and
+/// earlier transactions it may duplicate
+pub struct TransactionEnrichment {
+    pub rule_repo: RuleRepository,
+    pub payee_repo: PayeeRepository,
+    pub duplicate_repo: DuplicateRepository,
+}
+
 pub struct TransactionService {
     transaction_repo: TransactionRepository,
     pocket_repo: PocketRepository,
     settings_repo: SettingsRepository,
-    http_client: reqwest::Client,
+    enrichment: TransactionEnrichment,
+    exchange_rates: ExchangeRateService,
+    bills: BillService,
 }
 
 impl TransactionService {
@@ -213,13 +294,17 @@ impl TransactionService {
         transaction_repo: TransactionRepository,
         pocket_repo: PocketRepository,
         settings_repo: SettingsRepository,
-        http_client: reqwest::Client,
+        enrichment: TransactionEnrichment,
+        exchange_rates: ExchangeRateService,
+        bills: BillService,
     ) -> Self {
         Self {
             transaction_repo,
             pocket_repo,
             settings_repo,
-            http_client,
+            enrichment,
+            exchange_rates,
+            bills,
         }
     }
 
@@ -232,87 +317,460 @@ impl TransactionService {
         user_id: Uuid,
         req: CreateTransaction,
     ) -> Result<Uuid, AppError> {
+        if let Some(payee_id) = req.payee_id
+            && self
+                .enrichment
+                .payee_repo
+                .find_owned_ids(user_id, &[payee_id])
+                .await?
+                .is_empty()
+        {
+            return Err(AppError::ValidationError("Payee not found".to_string()));
+        }
+        let ctx = self.load_create_context(user_id).await?;
+        let new = self.prepare_transaction(req, &ctx).await?;
+
+        let mut tx = self.transaction_repo.begin().await?;
+        let id = self
+            .transaction_repo
+            .create_tx(&mut tx, user_id, &new)
+            .await?;
+        self.flag_duplicates(&mut tx, user_id, id, &new).await?;
+        tx.commit().await?;
+        self.match_bills(user_id, &[id]).await;
+        Ok(id)
+    }
+
+    /// Bill matching is best effort: a failure is logged and never fails the request that
+    /// created the transactions
+    async fn match_bills(&self, user_id: Uuid, transaction_ids: &[Uuid]) {
+        if let Err(e) = self
+            .bills
+            .match_transactions(user_id, transaction_ids)
+            .await
+        {
+            tracing::warn!("Bill matching failed: {:?}", e);
+        }
+    }
+
+    /// Compare a freshly inserted transaction with its neighbours and flag likely duplicates
+    async fn flag_duplicates(
+        &self,
+        conn: &mut PgConnection,
+        user_id: Uuid,
+        id: Uuid,
+        new: &NewTransaction,
+    ) -> Result<(), AppError> {
+        let window = Duration::days(duplicates::WINDOW_DAYS);
+        let candidates = self
+            .enrichment
+            .duplicate_repo
+            .find_candidates_tx(
+                &mut *conn,
+                user_id,
+                id,
+                new,
+                new.occurred_at - window,
+                new.occurred_at + window,
+            )
+            .await?;
+
+        let input = DuplicateInput {
+            pocket_id: new.pocket_id,
+            amount: new.amount,
+            occurred_at: new.occurred_at,
+            description: new.description.as_deref(),
+        };
+        for candidate in candidates {
+            let other = DuplicateInput {
+                pocket_id: candidate.pocket_id,
+                amount: candidate.amount,
+                occurred_at: candidate.occurred_at,
+                description: candidate.description.as_deref(),
+            };
+            if let Some(score) = duplicates::score(&input, &other)
+                && duplicates::is_likely_duplicate(score)
+            {
+                self.enrichment
+                    .duplicate_repo
+                    .flag_tx(&mut *conn, user_id, id, candidate.id, score)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The user's compiled categorization rules (in priority order), payee aliases and pockets
+    async fn load_create_context(&self, user_id: Uuid) -> Result<CreateContext, AppError> {
+        let rules = self
+            .enrichment
+            .rule_repo
+            .get_all(user_id)
+            .await?
+            .into_iter()
+            .filter_map(|rule| {
+                let id = rule.id;
+                CompiledRule::compile(rule)
+                    .inspect_err(|e| {
+                        tracing::warn!("Skipping rule {} with invalid regex: {}", id, e)
+                    })
+                    .ok()
+            })
+            .collect();
+        let payee_aliases = self.enrichment.payee_repo.get_alias_index(user_id).await?;
+        let pockets = self.pocket_repo.get_all(user_id, true).await?;
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+
+        Ok(CreateContext {
+            rules,
+            payee_aliases,
+            pockets,
+            base_currency,
+        })
+    }
+
+    /// Validate a create request, resolve its payee, apply categorization rules and convert it
+    /// to base currency. Values sent by the client take precedence over rule actions, which in
+    /// turn take precedence over the payee's default category.
+    async fn prepare_transaction(
+        &self,
+        req: CreateTransaction,
+        ctx: &CreateContext,
+    ) -> Result<NewTransaction, AppError> {
         if req.amount <= Decimal::ZERO {
             return Err(AppError::ValidationError(
                 "Amount must be positive".to_string(),
             ));
         }
+        if req.status == TransactionStatus::Void {
+            return Err(AppError::ValidationError(
+                "New transactions cannot be void".to_string(),
+            ));
+        }
 
-        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
-        let (amount, original_currency, original_amount, exchange_rate) = if let Some(currency) =
-            &req.currency_code
-        {
-            if currency != &base_currency {
-                let rate =
-                    investments::fetch_exchange_rate(&self.http_client, currency, &base_currency)
-                        .await?;
-                let converted_amount = req.amount * rate;
-                (
-                    converted_amount,
-                    Some(currency.clone()),
-                    Some(req.amount),
-                    Some(rate),
-                )
-            } else {
-                (req.amount, None, None, None)
-            }
-        } else {
-            (req.amount, None, None, None)
+        let description = req.description.filter(|d| !d.trim().is_empty());
+        let currency_code = req.currency_code.map(|c| c.trim().to_uppercase());
+
+        // Get pocket: use provided one, or fall back to default pocket
+        let pocket = match req.pocket_id {
+            Some(id) => ctx.pocket(id)?,
+            None => ctx
+                .pockets
+                .iter()
+                .find(|p| p.is_default)
+                .ok_or(AppError::NotFoundError(
+                    "Default pocket not found".to_string(),
+                ))?,
         };
 
-        let description = req.description.filter(|d| !d.trim().is_empty());
+        let payee = match req.payee_id {
+            Some(id) => ctx.payee_aliases.iter().find(|a| a.payee_id == id),
+            None => description
+                .as_deref()
+                .and_then(|d| payees::resolve(d, &ctx.payee_aliases)),
+        };
+
+        let outcome = rules::evaluate(
+            &ctx.rules,
+            &RuleInput {
+                description: description.as_deref(),
+                amount: req.amount,
+                currency: currency_code.as_deref().unwrap_or(&pocket.currency),
+                pocket_id: pocket.id,
+            },
+        );
 
-        // Get pocket_id: use provided one, or fall back to default pocket
-        let pocket_id = match req.pocket_id {
-            Some(id) => id,
-            None => self.pocket_repo.get_default(user_id).await?.id,
+        let category_id = req
+            .category_id
+            .or(outcome.category_id)
+            .or(payee.and_then(|p| p.default_category_id))
+            .ok_or(AppError::ValidationError(
+                "category_id is required when no rule or payee default applies".to_string(),
+            ))?;
+        let pocket = match (req.pocket_id, outcome.pocket_id) {
+            (None, Some(id)) => ctx.pocket(id)?,
+            _ => pocket,
         };
 
-        self.transaction_repo
-            .create(
-                user_id,
-                amount,
-                description,
-                req.category_id,
+        // Transactions default to the pocket's currency
+        let currency = currency_code.unwrap_or_else(|| pocket.currency.clone());
+        check_pocket_active(pocket)?;
+        check_pocket_currency(pocket, &currency, &ctx.base_currency)?;
+        let converted = self
+            .convert_to_base(
+                req.amount,
+                &currency,
+                &ctx.base_currency,
                 req.occurred_at,
-                original_currency,
-                original_amount,
-                exchange_rate,
-                pocket_id,
+                None,
             )
-            .await
+            .await?;
+
+        Ok(NewTransaction {
+            amount: converted.amount,
+            description,
+            category_id,
+            occurred_at: req.occurred_at,
+            original_currency: converted.original_currency,
+            original_amount: converted.original_amount,
+            exchange_rate: converted.exchange_rate,
+            pocket_id: pocket.id,
+            tags: normalize_tags(req.tags.into_iter().chain(outcome.tags)),
+            payee_id: req.payee_id.or(payee.map(|p| p.payee_id)),
+            transfer_id: None,
+            status: req.status,
+        })
+    }
+
+    /// Validate every operation up front, then apply them all inside one SQL transaction.
+    /// Any failure rolls back the whole batch; validation failures are reported per operation.
+    pub async fn batch_transactions(
+        &self,
+        user_id: Uuid,
+        req: BatchTransactionRequest,
+    ) -> Result<BatchTransactionResponse, AppError> {
+        if req.operations.is_empty() {
+            return Err(AppError::ValidationError(
+                "Batch must contain at least one operation".to_string(),
+            ));
+        }
+        if req.operations.len() > MAX_BATCH_OPERATIONS {
+            return Err(AppError::ValidationError(format!(
+                "Batch cannot contain more than {} operations",
+                MAX_BATCH_OPERATIONS
+            )));
+        }
+
+        // Ownership lookups are done once for the whole batch
+        let target_ids: Vec<Uuid> = req
+            .operations
+            .iter()
+            .filter_map(|op| match op {
+                BatchOperation::Create(_) => None,
+                BatchOperation::Update { id, .. }
+                | BatchOperation::Delete { id }
+                | BatchOperation::Recategorize { id, .. } => Some(*id),
+            })
+            .collect();
+        let active_ids: HashSet<Uuid> = self
+            .transaction_repo
+            .find_active_ids(user_id, &target_ids)
+            .await?
+            .into_iter()
+            .collect();
+        let transfer_leg_ids: HashSet<Uuid> = self
+            .transaction_repo
+            .find_transfer_leg_ids(user_id, &target_ids)
+            .await?
+            .into_iter()
+            .collect();
+        let locked_ids: HashSet<Uuid> = self
+            .transaction_repo
+            .find_locked_ids(user_id, &target_ids)
+            .await?
+            .into_iter()
+            .collect();
+        let pocket_ids: HashSet<Uuid> = self
+            .pocket_repo
+            .get_all(user_id, true)
+            .await?
+            .into_iter()
+            .map(|p| p.id)
+            .collect();
+        let category_ids: HashSet<i32> = self
+            .transaction_repo
+            .get_all_categories()
+            .await?
+            .into_iter()
+            .map(|c| c.id)
+            .collect();
+        let requested_payees: Vec<Uuid> = req
+            .operations
+            .iter()
+            .filter_map(|op| match op {
+                BatchOperation::Create(create) => create.payee_id,
+                BatchOperation::Update { changes, .. } => changes.payee_id,
+                _ => None,
+            })
+            .collect();
+        let payee_ids: HashSet<Uuid> = self
+            .enrichment
+            .payee_repo
+            .find_owned_ids(user_id, &requested_payees)
+            .await?
+            .into_iter()
+            .collect();
+        let ctx = self.load_create_context(user_id).await?;
+
+        let mut errors = Vec::new();
+        let mut prepared = Vec::with_capacity(req.operations.len());
+        let mut deleted = HashSet::new();
+
+        for (index, op) in req.operations.into_iter().enumerate() {
+            let name = op.name();
+            let result = match op {
+                BatchOperation::Create(create) => {
+                    if create
+                        .category_id
+                        .is_some_and(|c| !category_ids.contains(&c))
+                    {
+                        Err(AppError::ValidationError("Category not found".to_string()))
+                    } else if create.pocket_id.is_some_and(|id| !pocket_ids.contains(&id)) {
+                        Err(AppError::ValidationError("Pocket not found".to_string()))
+                    } else if create.payee_id.is_some_and(|id| !payee_ids.contains(&id)) {
+                        Err(AppError::ValidationError("Payee not found".to_string()))
+                    } else {
+                        self.prepare_transaction(create, &ctx)
+                            .await
+                            .map(PreparedOperation::Create)
+                    }
+                }
+                BatchOperation::Update { id, changes } => {
+                    if !active_ids.contains(&id) || deleted.contains(&id) {
+                        Err(AppError::NotFoundError("Transaction not found".to_string()))
+                    } else if locked_ids.contains(&id) {
+                        Err(locked_error())
+                    } else if transfer_leg_ids.contains(&id) {
+                        Err(AppError::ValidationError(
+                            "Transfer legs must be edited through the transfer".to_string(),
+                        ))
+                    } else if changes
+                        .category_id
+                        .is_some_and(|c| !category_ids.contains(&c))
+                    {
+                        Err(AppError::ValidationError("Category not found".to_string()))
+                    } else if changes.pocket_id.is_some_and(|p| !pocket_ids.contains(&p)) {
+                        Err(AppError::ValidationError("Pocket not found".to_string()))
+                    } else if changes.payee_id.is_some_and(|p| !payee_ids.contains(&p)) {
+                        Err(AppError::ValidationError("Payee not found".to_string()))
+                    } else {
+                        self.prepare_changes(id, user_id, changes)
+                            .await
+                            .map(|changes| PreparedOperation::Update(id, changes))
+                    }
+                }
+                BatchOperation::Delete { id } => {
+                    if !active_ids.contains(&id) || !deleted.insert(id) {
+                        Err(AppError::NotFoundError("Transaction not found".to_string()))
+                    } else if locked_ids.contains(&id) {
+                        Err(locked_error())
+                    } else if transfer_leg_ids.contains(&id) {
+                        // Deleting one leg removes both; that goes through the transfer
+                        Err(AppError::ValidationError(
+                            "Transfer legs must be deleted through the transfer".to_string(),
+                        ))
+                    } else {
+                        Ok(PreparedOperation::Delete(id))
+                    }
+                }
+                BatchOperation::Recategorize { id, category_id } => {
+                    if !active_ids.contains(&id) || deleted.contains(&id) {
+                        Err(AppError::NotFoundError("Transaction not found".to_string()))
+                    } else if locked_ids.contains(&id) {
+                        Err(locked_error())
+                    } else if transfer_leg_ids.contains(&id) {
+                        Err(AppError::ValidationError(
+                            "Transfer legs must be edited through the transfer".to_string(),
+                        ))
+                    } else if !category_ids.contains(&category_id) {
+                        Err(AppError::ValidationError("Category not found".to_string()))
+                    } else {
+                        Ok(PreparedOperation::Update(
+                            id,
+                            TransactionChanges {
+                                category_id: Some(category_id),
+                                ..Default::default()
+                            },
+                        ))
+                    }
+                }
+            };
+
+            match result {
+                Ok(op) => prepared.push((index, name, op)),
+                Err(e) => errors.push(BatchItemError {
+                    index,
+                    op: name,
+                    error: e.detail(),
+                }),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(AppError::BatchError(errors));
+        }
+
+        let mut tx = self.transaction_repo.begin().await?;
+        let mut results = Vec::with_capacity(prepared.len());
+
+        for (index, name, op) in prepared {
+            let id = match op {
+                PreparedOperation::Create(new) => {
+                    let id = self
+                        .transaction_repo
+                        .create_tx(&mut tx, user_id, &new)
+                        .await?;
+                    self.flag_duplicates(&mut tx, user_id, id, &new).await?;
+                    id
+                }
+                PreparedOperation::Update(id, changes) => {
+                    self.transaction_repo
+                        .update_tx(&mut tx, id, user_id, &changes)
+                        .await?;
+                    id
+                }
+                PreparedOperation::Delete(id) => {
+                    self.transaction_repo
+                        .delete_tx(&mut tx, id, user_id)
+                        .await?;
+                    id
+                }
+            };
+            results.push(BatchOperationResult {
+                index,
+                op: name,
+                id,
+            });
+        }
+
+        tx.commit().await?;
+
+        let created: Vec<Uuid> = results
+            .iter()
+            .filter(|r| r.op == "create")
+            .map(|r| r.id)
+            .collect();
+        self.match_bills(user_id, &created).await;
+
+        Ok(BatchTransactionResponse { results })
     }
 
     pub async fn get_transactions(
         &self,
         user_id: Uuid,
-        start_date: Option<DateTime<Utc>>,
-        end_date: Option<DateTime<Utc>>,
-        pocket_id: Option<Uuid>,
-        page: i64,
-        limit: i64,
+        params: crate::schemas::TransactionQueryParams,
     ) -> Result<crate::schemas::PaginatedTransactions, AppError> {
-        if let (Some(start), Some(end)) = (start_date, end_date) {
-            if end < start {
-                return Err(AppError::ValidationError(
-                    "End date cannot be before start date".to_string(),
-                ));
-            }
+        if let (Some(start), Some(end)) = (params.start_date, params.end_date)
+            && end < start
+        {
+            return Err(AppError::ValidationError(
+                "End date cannot be before start date".to_string(),
+            ));
         }
 
         // Clamp limit to reasonable values
-        let limit = limit.clamp(1, 100);
-        let page = page.max(1);
+        let limit = params.limit.clamp(1, 100);
+        let page = params.page.max(1);
         let offset = (page - 1) * limit;
 
         let transactions = self
             .transaction_repo
-            .find_by_user_and_date(user_id, start_date, end_date, pocket_id, limit, offset)
+            .find_by_user_and_date(user_id, &params, limit, offset)
             .await?;
 
         let total = self
             .transaction_repo
-            .count_by_user_and_date(user_id, start_date, end_date, pocket_id)
+            .count_by_user_and_date(user_id, &params)
             .await?;
 
         let total_pages = (total as f64 / limit as f64).ceil() as i64;
@@ -329,14 +787,31 @@ impl TransactionService {
     pub async fn get_spending_analysis(
         &self,
         user_id: Uuid,
-        start_date: DateTime<Utc>,
-        end_date: DateTime<Utc>,
+        params: crate::schemas::SpendingAnalysisParams,
     ) -> Result<crate::schemas::SpendingAnalysisResponse, AppError> {
+        if params.end_date < params.start_date {
+            return Err(AppError::ValidationError(
+                "end_date must not be before start_date".to_string(),
+            ));
+        }
         let categories = self
             .transaction_repo
-            .get_spending_analysis(user_id, start_date, end_date)
+            .get_spending_analysis(user_id, params.start_date, params.end_date)
             .await?;
 
+        let comparison = match params.compare {
+            Some(period) => {
+                let (start, end) =
+                    comparison::comparison_range(params.start_date, params.end_date, period);
+                let previous = self
+                    .transaction_repo
+                    .get_spending_analysis(user_id, start, end)
+                    .await?;
+                Some(comparison::build(start, end, &categories, previous))
+            }
+            None => None,
+        };
+
         let mut total_income = Decimal::ZERO;
         let mut total_spent = Decimal::ZERO;
 
@@ -355,450 +830,3691 @@ impl TransactionService {
             total_spent,
             net_income,
             categories,
+            comparison,
         })
     }
-    pub async fn update_transaction(
+    /// Income, spending and net per day, week or month, optionally split by category or pocket
+    pub async fn get_cash_flow(
         &self,
-        id: Uuid,
         user_id: Uuid,
-        req: crate::schemas::UpdateTransaction,
-    ) -> Result<(), AppError> {
-        if let Some(amount) = req.amount {
-            if amount <= Decimal::ZERO {
-                return Err(AppError::ValidationError(
-                    "Amount must be positive".to_string(),
-                ));
-            }
+        params: CashFlowParams,
+    ) -> Result<Vec<CashFlowPoint>, AppError> {
+        if params.end_date < params.start_date {
+            return Err(AppError::ValidationError(
+                "end_date must not be before start_date".to_string(),
+            ));
+        }
+        let (start, end) = (params.start_date.date_naive(), params.end_date.date_naive());
+        let points = cash_flow::bucket_count(params.interval, start, end);
+        if points > MAX_HISTORY_POINTS {
+            return Err(AppError::ValidationError(format!(
+                "Range covers {} points; at most {} are allowed",
+                points, MAX_HISTORY_POINTS
+            )));
+        }
+        if let Some(pocket_id) = params.pocket_id {
+            self.pocket_repo.get_by_id(pocket_id, user_id).await?;
         }
-        let description = req.description.filter(|d| !d.trim().is_empty());
 
-        self.transaction_repo
-            .update(
-                id,
+        let rows = self
+            .transaction_repo
+            .get_cash_flow(
                 user_id,
-                req.amount,
-                description,
-                req.category_id,
-                req.occurred_at,
-                req.original_currency,
-                req.original_amount,
-                req.exchange_rate,
+                params.start_date,
+                params.end_date,
+                params.interval,
+                params.pocket_id,
             )
-            .await
+            .await?;
+        Ok(cash_flow::build(
+            params.interval,
+            start,
+            end,
+            params.breakdown,
+            rows,
+        ))
     }
 
-    pub async fn delete_transaction(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let deleted = self.transaction_repo.delete(id, user_id).await?;
-        if deleted == 0 {
-            return Err(AppError::NotFoundError("Transaction not found".to_string()));
+    /// Transactions in the trash can't be edited (NotFoundError); restore them first
+    pub async fn update_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: crate::schemas::UpdateTransaction,
+    ) -> Result<(), AppError> {
+        let current = self.transaction_repo.get_transaction(id, user_id).await?;
+        self.check_unlocked(user_id, &[id]).await?;
+        if let Some(transfer_id) = current.transfer_id {
+            return self
+                .update_transfer_leg(id, transfer_id, user_id, req)
+                .await;
         }
-        Ok(())
-    }
 
-    pub async fn restore_transaction(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let restored = self.transaction_repo.restore(id, user_id).await?;
-        if restored == 0 {
+        if let Some(pocket_id) = req.pocket_id {
+            self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+        }
+        if let Some(payee_id) = req.payee_id {
+            self.enrichment
+                .payee_repo
+                .get_by_id(payee_id, user_id)
+                .await?;
+        }
+        let changes = self.prepare_changes(id, user_id, req).await?;
+        let updated = self.transaction_repo.update(id, user_id, &changes).await?;
+        if updated == 0 {
             return Err(AppError::NotFoundError("Transaction not found".to_string()));
         }
         Ok(())
     }
-    pub async fn get_transaction(
-        &self,
-        user_id: Uuid,
-        id: Uuid,
-    ) -> Result<TransactionDetail, AppError> {
-        self.transaction_repo.get_transaction(id, user_id).await
-    }
 
-    pub async fn transfer_funds(
+    /// Edits to one leg of a transfer go through the transfer so both legs stay in sync;
+    /// only tags are per leg
+    async fn update_transfer_leg(
         &self,
+        id: Uuid,
+        transfer_id: Uuid,
         user_id: Uuid,
-        req: crate::schemas::TransferRequest,
+        req: crate::schemas::UpdateTransaction,
     ) -> Result<(), AppError> {
-        if req.amount <= Decimal::ZERO {
+        if req.category_id.is_some()
+            || req.currency_code.is_some()
+            || req.exchange_rate.is_some()
+            || req.payee_id.is_some()
+            || req.clear_payee
+        {
             return Err(AppError::ValidationError(
-                "Transfer amount must be positive".to_string(),
+                "Category, currency and payee cannot be changed on a transfer".to_string(),
             ));
         }
 
-        if req.source_pocket_id == req.destination_pocket_id {
+        let transfer = self
+            .transaction_repo
+            .get_transfer(transfer_id, user_id)
+            .await?;
+        let is_out_leg = transfer.out_transaction_id == id;
+        if !is_out_leg
+            && req.amount.is_some()
+            && transfer.source_pocket.currency != transfer.destination_pocket.currency
+        {
             return Err(AppError::ValidationError(
-                "Cannot transfer to the same pocket".to_string(),
+                "Edit the transfer to change the amount of a cross-currency transfer".to_string(),
             ));
         }
+        let update = UpdateTransfer {
+            source_pocket_id: req.pocket_id.filter(|_| is_out_leg),
+            destination_pocket_id: req.pocket_id.filter(|_| !is_out_leg),
+            amount: req.amount,
+            description: req.description,
+            occurred_at: req.occurred_at,
+            exchange_rate: None,
+            fee: None,
+        };
 
-        // Verify pockets exist and belong to user
-        let _source_pocket = self
-            .pocket_repo
-            .get_by_id(req.source_pocket_id, user_id)
-            .await?;
-        let _dest_pocket = self
-            .pocket_repo
-            .get_by_id(req.destination_pocket_id, user_id)
+        let mut tx = self.transaction_repo.begin().await?;
+        self.update_transfer_tx(&mut tx, transfer_id, user_id, update)
             .await?;
+        if let Some(tags) = req.tags {
+            let changes = TransactionChanges {
+                tags: Some(normalize_tags(tags)),
+                ..Default::default()
+            };
+            self.transaction_repo
+                .update_tx(&mut tx, id, user_id, &changes)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
 
-        // Check source pocket has sufficient balance
-        let source_balance = self
-            .transaction_repo
-            .get_pocket_balance(user_id, req.source_pocket_id)
-            .await?;
-        if source_balance < req.amount {
+    async fn prepare_changes(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: crate::schemas::UpdateTransaction,
+    ) -> Result<TransactionChanges, AppError> {
+        if let Some(amount) = req.amount
+            && amount <= Decimal::ZERO
+        {
             return Err(AppError::ValidationError(
-                "Insufficient funds in source pocket".to_string(),
+                "Amount must be positive".to_string(),
+            ));
+        }
+        if let Some(rate) = req.exchange_rate
+            && rate <= Decimal::ZERO
+        {
+            return Err(AppError::ValidationError(
+                "Exchange rate must be positive".to_string(),
+            ));
+        }
+        if req.clear_payee && req.payee_id.is_some() {
+            return Err(AppError::ValidationError(
+                "payee_id and clear_payee cannot be combined".to_string(),
             ));
         }
 
-        // Get special categories
-        let cat_out = self
-            .transaction_repo
-            .get_category_by_name("Transfer Out")
+        let mut changes = TransactionChanges {
+            description: req.description.filter(|d| !d.trim().is_empty()),
+            category_id: req.category_id,
+            occurred_at: req.occurred_at,
+            pocket_id: req.pocket_id,
+            tags: req.tags.map(normalize_tags),
+            payee_id: req.payee_id,
+            clear_payee: req.clear_payee,
+            ..Default::default()
+        };
+
+        let touches_amount =
+            req.amount.is_some() || req.currency_code.is_some() || req.exchange_rate.is_some();
+        if !touches_amount && req.occurred_at.is_none() && req.pocket_id.is_none() {
+            return Ok(changes);
+        }
+
+        // Amount, currency and rate are always written together so they can't drift apart
+        let current = self.transaction_repo.get_transaction(id, user_id).await?;
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let currency_changed = req.currency_code.is_some();
+        let currency = match req.currency_code {
+            Some(code) => {
+                let code = code.trim().to_uppercase();
+                if code != base_currency && !self.settings_repo.validate_currency(&code).await? {
+                    return Err(AppError::ValidationError(format!(
+                        "Invalid currency code: {}",
+                        code
+                    )));
+                }
+                code
+            }
+            None => current
+                .original_currency
+                .clone()
+                .unwrap_or_else(|| base_currency.clone()),
+        };
+
+        if (currency_changed || req.pocket_id.is_some())
+            && let Some(pocket_id) = req.pocket_id.or(current.pocket.as_ref().map(|p| p.id))
+        {
+            let pocket = self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+            if current.pocket.as_ref().map(|p| p.id) != Some(pocket_id) {
+                check_pocket_active(&pocket)?;
+            }
+            check_pocket_currency(&pocket, &currency, &base_currency)?;
+        }
+        if !touches_amount && current.original_currency.is_none() {
+            return Ok(changes);
+        }
+
+        if currency == base_currency {
+            if req.exchange_rate.is_some() {
+                return Err(AppError::ValidationError(
+                    "exchange_rate only applies to foreign-currency transactions".to_string(),
+                ));
+            }
+            changes.amount = Some(req.amount.unwrap_or(current.amount));
+            changes.clear_foreign_currency = true;
+            return Ok(changes);
+        }
+
+        // Keep the stored rate unless the currency or date changes, so edits don't re-price history
+        let same_currency = current.original_currency.as_deref() == Some(currency.as_str());
+        let occurred_at = req.occurred_at.unwrap_or(current.occurred_at);
+        let rate = req.exchange_rate.or(current
+            .exchange_rate
+            .filter(|_| same_currency && occurred_at == current.occurred_at));
+        let entered = req
+            .amount
+            .or(current.original_amount)
+            .unwrap_or(current.amount);
+
+        let converted = self
+            .convert_to_base(entered, &currency, &base_currency, occurred_at, rate)
             .await?;
-        let cat_in = self
+        changes.amount = Some(converted.amount);
+        changes.original_currency = converted.original_currency;
+        changes.original_amount = converted.original_amount;
+        changes.exchange_rate = converted.exchange_rate;
+        Ok(changes)
+    }
+
+    /// Convert `amount` in `currency` to base currency, using `rate` when given and the
+    /// market rate on the day of `occurred_at` otherwise. Base-currency amounts pass through.
+    async fn convert_to_base(
+        &self,
+        amount: Decimal,
+        currency: &str,
+        base_currency: &str,
+        occurred_at: DateTime<Utc>,
+        rate: Option<Decimal>,
+    ) -> Result<ConvertedAmount, AppError> {
+        if currency == base_currency {
+            return Ok(ConvertedAmount {
+                amount,
+                original_currency: None,
+                original_amount: None,
+                exchange_rate: None,
+            });
+        }
+
+        let rate = match rate {
+            Some(rate) => rate,
+            None => {
+                self.exchange_rates
+                    .rate_on(currency, base_currency, occurred_at.date_naive())
+                    .await?
+            }
+        };
+
+        Ok(ConvertedAmount {
+            amount: amount * rate,
+            original_currency: Some(currency.to_string()),
+            original_amount: Some(amount),
+            exchange_rate: Some(rate),
+        })
+    }
+
+    /// Re-price a foreign-currency transaction with the rate for its date
+    pub async fn recompute_exchange_rate(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<TransactionDetail, AppError> {
+        let current = self.transaction_repo.get_transaction(id, user_id).await?;
+        self.check_unlocked(user_id, &[id]).await?;
+        let (Some(currency), Some(original_amount)) =
+            (&current.original_currency, current.original_amount)
+        else {
+            return Err(AppError::ValidationError(
+                "Transaction is not in a foreign currency".to_string(),
+            ));
+        };
+
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let changes = self
+            .recompute_changes(
+                currency,
+                original_amount,
+                &base_currency,
+                current.occurred_at,
+            )
+            .await?;
+        self.transaction_repo.update(id, user_id, &changes).await?;
+        self.transaction_repo.get_transaction(id, user_id).await
+    }
+
+    /// Re-price every foreign-currency transaction of the user in one SQL transaction.
+    /// Returns how many transactions actually changed.
+    pub async fn recompute_exchange_rates(&self, user_id: Uuid) -> Result<usize, AppError> {
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let rows = self.transaction_repo.get_foreign_currency(user_id).await?;
+        // Reconciled transactions keep the amounts they were reconciled with
+        let ids: Vec<Uuid> = rows.iter().map(|r| r.id).collect();
+        let locked: HashSet<Uuid> = self
             .transaction_repo
-            .get_category_by_name("Transfer In")
+            .find_locked_ids(user_id, &ids)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut updates = Vec::new();
+        for row in rows.into_iter().filter(|r| !locked.contains(&r.id)) {
+            let changes = self
+                .recompute_changes(
+                    &row.original_currency,
+                    row.original_amount,
+                    &base_currency,
+                    row.occurred_at,
+                )
+                .await?;
+            if changes.exchange_rate != row.exchange_rate {
+                updates.push((row.id, changes));
+            }
+        }
+
+        let mut tx = self.transaction_repo.begin().await?;
+        for (id, changes) in &updates {
+            self.transaction_repo
+                .update_tx(&mut tx, *id, user_id, changes)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(updates.len())
+    }
+
+    async fn recompute_changes(
+        &self,
+        currency: &str,
+        original_amount: Decimal,
+        base_currency: &str,
+        occurred_at: DateTime<Utc>,
+    ) -> Result<TransactionChanges, AppError> {
+        let converted = self
+            .convert_to_base(original_amount, currency, base_currency, occurred_at, None)
+            .await?;
+        Ok(TransactionChanges {
+            amount: Some(converted.amount),
+            original_currency: converted.original_currency,
+            original_amount: converted.original_amount,
+            exchange_rate: converted.exchange_rate,
+            // A transaction in the (new) base currency no longer needs conversion
+            clear_foreign_currency: currency == base_currency,
+            ..Default::default()
+        })
+    }
+
+    pub async fn get_transaction_history(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<TransactionVersion>, AppError> {
+        if !self.transaction_repo.exists_for_user(id, user_id).await? {
+            return Err(AppError::NotFoundError("Transaction not found".to_string()));
+        }
+
+        let versions = self.transaction_repo.get_versions(id, user_id).await?;
+        Ok(versions
+            .into_iter()
+            .map(|v| TransactionVersion {
+                id: v.id,
+                version: v.version,
+                changed_by: v.changed_by,
+                changed_at: v.changed_at,
+                changed_fields: changed_fields(&v.old_values, &v.new_values),
+                old_values: v.old_values,
+                new_values: v.new_values,
+            })
+            .collect())
+    }
+
+    /// Restore the transaction to the state it had at `version` (0 = before the first edit).
+    /// The revert itself is recorded as a new version.
+    pub async fn revert_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        version: i32,
+    ) -> Result<(), AppError> {
+        if !self
+            .transaction_repo
+            .find_transfer_leg_ids(user_id, &[id])
+            .await?
+            .is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "Transfer legs cannot be reverted; edit the transfer instead".to_string(),
+            ));
+        }
+        self.check_unlocked(user_id, &[id]).await?;
+
+        let versions = self.transaction_repo.get_versions(id, user_id).await?;
+        let target = if version == 0 {
+            versions.into_iter().next().map(|v| v.old_values)
+        } else {
+            versions
+                .into_iter()
+                .find(|v| v.version == version)
+                .map(|v| v.new_values)
+        }
+        .ok_or(AppError::NotFoundError(format!(
+            "Version {} not found",
+            version
+        )))?;
+
+        // The old values go through the same checks as an edit: the pocket may have been
+        // archived since, and the payee deleted
+        let current = self.transaction_repo.get_transaction(id, user_id).await?;
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let pocket = self
+            .pocket_repo
+            .get_by_id(target.pocket_id, user_id)
+            .await?;
+        if current.pocket.as_ref().map(|p| p.id) != Some(target.pocket_id) {
+            check_pocket_active(&pocket)?;
+        }
+        let currency = target
+            .original_currency
+            .as_deref()
+            .unwrap_or(&base_currency);
+        check_pocket_currency(&pocket, currency, &base_currency)?;
+        if let Some(payee_id) = target.payee_id {
+            self.enrichment
+                .payee_repo
+                .get_by_id(payee_id, user_id)
+                .await?;
+        }
+
+        let reverted = self
+            .transaction_repo
+            .restore_snapshot(id, user_id, &target)
+            .await?;
+        if reverted == 0 {
+            return Err(AppError::NotFoundError("Transaction not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_duplicates(&self, user_id: Uuid) -> Result<Vec<DuplicatePair>, AppError> {
+        self.enrichment.duplicate_repo.get_pending(user_id).await
+    }
+
+    /// Keep one side of a pair and move the other to the trash. Tags are combined and the
+    /// kept transaction inherits the description and payee if it has none.
+    pub async fn merge_duplicate(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: MergeDuplicateRequest,
+    ) -> Result<Uuid, AppError> {
+        let pair = self
+            .enrichment
+            .duplicate_repo
+            .get_pending_pair(id, user_id)
+            .await?;
+        let keep_id = req.keep_id.unwrap_or(pair.duplicate_of_id);
+        let discard_id = if keep_id == pair.duplicate_of_id {
+            pair.transaction_id
+        } else if keep_id == pair.transaction_id {
+            pair.duplicate_of_id
+        } else {
+            return Err(AppError::ValidationError(
+                "keep_id must be one of the transactions in the pair".to_string(),
+            ));
+        };
+
+        self.check_unlocked(user_id, &[keep_id, discard_id]).await?;
+        let keep = self
+            .transaction_repo
+            .get_transaction(keep_id, user_id)
+            .await?;
+        let discard = self
+            .transaction_repo
+            .get_transaction(discard_id, user_id)
+            .await?;
+
+        let tags = normalize_tags(keep.tags.iter().chain(&discard.tags).cloned());
+        let changes = TransactionChanges {
+            description: keep
+                .description
+                .is_none()
+                .then_some(discard.description)
+                .flatten(),
+            payee_id: keep
+                .payee
+                .is_none()
+                .then(|| discard.payee.map(|p| p.id))
+                .flatten(),
+            tags: (tags != keep.tags).then_some(tags),
+            ..Default::default()
+        };
+
+        let mut tx = self.transaction_repo.begin().await?;
+        self.transaction_repo
+            .update_tx(&mut tx, keep_id, user_id, &changes)
+            .await?;
+        self.transaction_repo
+            .delete_tx(&mut tx, discard_id, user_id)
+            .await?;
+        self.enrichment
+            .duplicate_repo
+            .resolve_tx(&mut tx, id, user_id, "merged")
+            .await?;
+        tx.commit().await?;
+
+        Ok(keep_id)
+    }
+
+    pub async fn dismiss_duplicate(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let dismissed = self
+            .enrichment
+            .duplicate_repo
+            .resolve(id, user_id, "dismissed")
+            .await?;
+        if dismissed == 0 {
+            return Err(AppError::NotFoundError(
+                "Duplicate pair not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_transaction(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        self.check_unlocked(user_id, &[id]).await?;
+        let deleted = self.transaction_repo.delete(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Transaction not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Allow edits to a reconciled transaction again
+    pub async fn unlock_transaction(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        self.transaction_repo.get_transaction(id, user_id).await?;
+        let unlocked = self.transaction_repo.unlock(id, user_id).await?;
+        if unlocked == 0 {
+            return Err(AppError::ValidationError(
+                "Transaction is not reconciled".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Settle a pending transaction. The amount and date it was authorized with are replaced
+    /// when the posted ones differ; the change is versioned like any other edit.
+    pub async fn post_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: crate::schemas::PostTransaction,
+    ) -> Result<(), AppError> {
+        let current = self.pending_transaction(id, user_id).await?;
+        let changes = self
+            .prepare_changes(
+                current.id,
+                user_id,
+                crate::schemas::UpdateTransaction {
+                    amount: req.amount,
+                    description: None,
+                    category_id: None,
+                    occurred_at: req.occurred_at,
+                    currency_code: None,
+                    exchange_rate: None,
+                    pocket_id: None,
+                    tags: None,
+                    payee_id: None,
+                    clear_payee: false,
+                },
+            )
+            .await?;
+
+        let mut tx = self.transaction_repo.begin().await?;
+        if req.amount.is_some() || req.occurred_at.is_some() {
+            self.transaction_repo
+                .update_tx(&mut tx, id, user_id, &changes)
+                .await?;
+        }
+        self.transaction_repo
+            .set_status_tx(&mut tx, id, user_id, TransactionStatus::Posted)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Cancel a pending transaction that will never post (e.g. a released card hold). Void
+    /// transactions stay visible but no longer count towards any balance or report.
+    pub async fn void_transaction(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        self.pending_transaction(id, user_id).await?;
+        let mut tx = self.transaction_repo.begin().await?;
+        self.transaction_repo
+            .set_status_tx(&mut tx, id, user_id, TransactionStatus::Void)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn pending_transaction(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<crate::schemas::TransactionDetail, AppError> {
+        let current = self.transaction_repo.get_transaction(id, user_id).await?;
+        self.check_unlocked(user_id, &[id]).await?;
+        if current.status != TransactionStatus::Pending {
+            return Err(AppError::ValidationError(
+                "Only pending transactions can be posted or voided".to_string(),
+            ));
+        }
+        Ok(current)
+    }
+
+    /// Reconciled transactions (and transfers with a reconciled leg) are locked until unlocked
+    async fn check_unlocked(&self, user_id: Uuid, ids: &[Uuid]) -> Result<(), AppError> {
+        if self
+            .transaction_repo
+            .find_locked_ids(user_id, ids)
+            .await?
+            .is_empty()
+        {
+            Ok(())
+        } else {
+            Err(locked_error())
+        }
+    }
+
+    pub async fn restore_transaction(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let restored = self.transaction_repo.restore(id, user_id).await?;
+        if restored == 0 {
+            return Err(self.trash_miss(id, user_id).await?);
+        }
+        Ok(())
+    }
+
+    pub async fn get_trash(
+        &self,
+        user_id: Uuid,
+        page: i64,
+        limit: i64,
+        retention_days: i64,
+    ) -> Result<crate::schemas::PaginatedDeletedTransactions, AppError> {
+        let limit = limit.clamp(1, 100);
+        let page = page.max(1);
+        let offset = (page - 1) * limit;
+
+        let mut transactions = self
+            .transaction_repo
+            .find_deleted(user_id, limit, offset)
+            .await?;
+        for t in &mut transactions {
+            t.purge_at = Some(t.deleted_at + chrono::Duration::days(retention_days));
+        }
+
+        let total = self.transaction_repo.count_deleted(user_id).await?;
+        let total_pages = (total as f64 / limit as f64).ceil() as i64;
+
+        Ok(crate::schemas::PaginatedDeletedTransactions {
+            transactions,
+            total,
+            page,
+            limit,
+            total_pages,
+        })
+    }
+
+    pub async fn delete_transaction_permanently(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(), AppError> {
+        let deleted = self
+            .transaction_repo
+            .delete_permanently(id, user_id)
+            .await?;
+        if deleted == 0 {
+            return Err(self.trash_miss(id, user_id).await?);
+        }
+        Ok(())
+    }
+
+    pub async fn empty_trash(&self, user_id: Uuid) -> Result<u64, AppError> {
+        self.transaction_repo.empty_trash(user_id).await
+    }
+
+    /// Permanently remove transactions that have been in the trash longer than `retention_days`
+    pub async fn purge_expired_trash(&self, retention_days: i64) -> Result<u64, AppError> {
+        let cutoff = Utc::now() - chrono::Duration::days(retention_days);
+        self.transaction_repo.purge_deleted_before(cutoff).await
+    }
+
+    /// Error for a trash operation that matched nothing: either the id is unknown,
+    /// or the transaction exists but was never deleted
+    async fn trash_miss(&self, id: Uuid, user_id: Uuid) -> Result<AppError, AppError> {
+        if self.transaction_repo.exists_for_user(id, user_id).await? {
+            Ok(AppError::ValidationError(
+                "Transaction is not in the trash".to_string(),
+            ))
+        } else {
+            Ok(AppError::NotFoundError("Transaction not found".to_string()))
+        }
+    }
+
+    pub async fn get_transaction(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<TransactionDetail, AppError> {
+        self.transaction_repo.get_transaction(id, user_id).await
+    }
+
+    /// Move money between two pockets. The transfer and both of its legs are written in one
+    /// SQL transaction, with the source pocket locked while its balance is checked.
+    /// Between pockets in different currencies the amount (less the fee) is converted with
+    /// the given rate, or the market rate on the transfer date.
+    pub async fn transfer_funds(
+        &self,
+        user_id: Uuid,
+        req: TransferRequest,
+    ) -> Result<Uuid, AppError> {
+        if req.amount <= Decimal::ZERO {
+            return Err(AppError::ValidationError(
+                "Transfer amount must be positive".to_string(),
+            ));
+        }
+
+        if req.source_pocket_id == req.destination_pocket_id {
+            return Err(AppError::ValidationError(
+                "Cannot transfer to the same pocket".to_string(),
+            ));
+        }
+
+        // Verify pockets exist and belong to user
+        let source = self
+            .pocket_repo
+            .get_by_id(req.source_pocket_id, user_id)
+            .await?;
+        let destination = self
+            .pocket_repo
+            .get_by_id(req.destination_pocket_id, user_id)
+            .await?;
+        check_pocket_active(&source)?;
+        check_pocket_active(&destination)?;
+
+        let occurred_at = req.occurred_at.unwrap_or_else(Utc::now);
+        let fee = req.fee.unwrap_or(Decimal::ZERO);
+        let (destination_amount, exchange_rate) = self
+            .transfer_terms(
+                &source,
+                &destination,
+                req.amount,
+                fee,
+                req.exchange_rate,
+                occurred_at,
+            )
+            .await?;
+        let transfer = NewTransfer {
+            source_pocket_id: source.id,
+            destination_pocket_id: destination.id,
+            amount: req.amount,
+            destination_amount,
+            exchange_rate,
+            fee,
+            description: req.description.filter(|d| !d.trim().is_empty()),
+            occurred_at,
+        };
+        let (out_leg, in_leg) = self
+            .transfer_leg_amounts(user_id, &source, &destination, &transfer)
+            .await?;
+
+        // Get special categories
+        let cat_out = self
+            .transaction_repo
+            .get_category_by_name("Transfer Out")
+            .await?;
+        let cat_in = self
+            .transaction_repo
+            .get_category_by_name("Transfer In")
+            .await?;
+
+        let mut tx = self.transaction_repo.begin().await?;
+        self.pocket_repo
+            .lock_tx(&mut tx, source.id, user_id)
+            .await?;
+        let source_balance = self
+            .transaction_repo
+            .get_pocket_balance_tx(&mut tx, user_id, source.id)
+            .await?;
+        // Credit cards spend on credit, so their balance may go below zero
+        if !source.is_credit_card() && source_balance < transfer.amount {
+            return Err(AppError::ValidationError(
+                "Insufficient funds in source pocket".to_string(),
+            ));
+        }
+
+        let transfer_id = self
+            .transaction_repo
+            .create_transfer_tx(&mut tx, user_id, &transfer)
+            .await?;
+
+        // 1. Withdraw from Source
+        let out_id = self
+            .transaction_repo
+            .create_tx(
+                &mut tx,
+                user_id,
+                &NewTransaction {
+                    amount: out_leg.amount, // Positive amount (category indicates it's an outflow)
+                    description: Some(
+                        transfer
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "Transfer Out".to_string()),
+                    ),
+                    category_id: cat_out.id,
+                    occurred_at,
+                    original_currency: out_leg.original_currency,
+                    original_amount: out_leg.original_amount,
+                    exchange_rate: out_leg.exchange_rate,
+                    pocket_id: source.id,
+                    tags: vec![],
+                    payee_id: None,
+                    transfer_id: Some(transfer_id),
+                    status: TransactionStatus::Posted,
+                },
+            )
+            .await?;
+
+        // 2. Deposit to Destination
+        let in_id = self
+            .transaction_repo
+            .create_tx(
+                &mut tx,
+                user_id,
+                &NewTransaction {
+                    amount: in_leg.amount, // Positive amount for income
+                    description: Some(
+                        transfer
+                            .description
+                            .clone()
+                            .unwrap_or_else(|| "Transfer In".to_string()),
+                    ),
+                    category_id: cat_in.id,
+                    occurred_at,
+                    original_currency: in_leg.original_currency,
+                    original_amount: in_leg.original_amount,
+                    exchange_rate: in_leg.exchange_rate,
+                    pocket_id: destination.id,
+                    tags: vec![],
+                    payee_id: None,
+                    transfer_id: Some(transfer_id),
+                    status: TransactionStatus::Posted,
+                },
+            )
+            .await?;
+
+        self.transaction_repo
+            .set_transfer_legs_tx(&mut tx, transfer_id, out_id, in_id)
+            .await?;
+        tx.commit().await?;
+
+        Ok(transfer_id)
+    }
+
+    /// Validate the fee and rate of a transfer and work out what arrives in the destination.
+    /// Returns the destination amount and the rate used (none for same-currency transfers).
+    async fn transfer_terms(
+        &self,
+        source: &Pocket,
+        destination: &Pocket,
+        amount: Decimal,
+        fee: Decimal,
+        exchange_rate: Option<Decimal>,
+        occurred_at: DateTime<Utc>,
+    ) -> Result<(Decimal, Option<Decimal>), AppError> {
+        if source.currency == destination.currency {
+            if exchange_rate.is_some() || fee != Decimal::ZERO {
+                return Err(AppError::ValidationError(
+                    "exchange_rate and fee only apply to transfers between currencies".to_string(),
+                ));
+            }
+            return Ok((amount, None));
+        }
+
+        if fee < Decimal::ZERO || fee >= amount {
+            return Err(AppError::ValidationError(
+                "Fee must be at least zero and less than the amount".to_string(),
+            ));
+        }
+        let rate = match exchange_rate {
+            Some(rate) if rate <= Decimal::ZERO => {
+                return Err(AppError::ValidationError(
+                    "Exchange rate must be positive".to_string(),
+                ));
+            }
+            Some(rate) => rate,
+            None => {
+                self.exchange_rates
+                    .rate_on(
+                        &source.currency,
+                        &destination.currency,
+                        occurred_at.date_naive(),
+                    )
+                    .await?
+            }
+        };
+        Ok(((amount - fee) * rate, Some(rate)))
+    }
+
+    /// Base-currency amounts of the two legs. When one side is in base currency the transfer's
+    /// own rate prices the other side, so both legs agree with what was actually exchanged.
+    async fn transfer_leg_amounts(
+        &self,
+        user_id: Uuid,
+        source: &Pocket,
+        destination: &Pocket,
+        transfer: &NewTransfer,
+    ) -> Result<(ConvertedAmount, ConvertedAmount), AppError> {
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let out_rate = transfer
+            .exchange_rate
+            .filter(|_| destination.currency == base_currency);
+        let in_rate = transfer
+            .exchange_rate
+            .filter(|_| source.currency == base_currency)
+            .map(|rate| Decimal::ONE / rate);
+
+        let out_leg = self
+            .convert_to_base(
+                transfer.amount,
+                &source.currency,
+                &base_currency,
+                transfer.occurred_at,
+                out_rate,
+            )
+            .await?;
+        let in_leg = self
+            .convert_to_base(
+                transfer.destination_amount,
+                &destination.currency,
+                &base_currency,
+                transfer.occurred_at,
+                in_rate,
+            )
+            .await?;
+        Ok((out_leg, in_leg))
+    }
+
+    pub async fn get_transfers(
+        &self,
+        user_id: Uuid,
+        page: i64,
+        limit: i64,
+    ) -> Result<PaginatedTransfers, AppError> {
+        let limit = limit.clamp(1, 100);
+        let page = page.max(1);
+        let offset = (page - 1) * limit;
+
+        let transfers = self
+            .transaction_repo
+            .get_transfers(user_id, None, limit, offset)
+            .await?;
+        let total = self.transaction_repo.count_transfers(user_id).await?;
+        let total_pages = (total as f64 / limit as f64).ceil() as i64;
+
+        Ok(PaginatedTransfers {
+            transfers,
+            total,
+            page,
+            limit,
+            total_pages,
+        })
+    }
+
+    pub async fn get_transfer(&self, id: Uuid, user_id: Uuid) -> Result<Transfer, AppError> {
+        self.transaction_repo.get_transfer(id, user_id).await
+    }
+
+    pub async fn update_transfer(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdateTransfer,
+    ) -> Result<(), AppError> {
+        let transfer = self.transaction_repo.get_transfer(id, user_id).await?;
+        self.check_unlocked(user_id, &[transfer.out_transaction_id])
+            .await?;
+        let mut tx = self.transaction_repo.begin().await?;
+        self.update_transfer_tx(&mut tx, id, user_id, req).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Apply `req` to the transfer and mirror it onto both legs, so they never disagree.
+    /// The balance check only runs when the edit takes more out of the source pocket.
+    async fn update_transfer_tx(
+        &self,
+        conn: &mut PgConnection,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdateTransfer,
+    ) -> Result<(), AppError> {
+        if let Some(amount) = req.amount
+            && amount <= Decimal::ZERO
+        {
+            return Err(AppError::ValidationError(
+                "Transfer amount must be positive".to_string(),
+            ));
+        }
+
+        let old = self
+            .transaction_repo
+            .lock_transfer_tx(&mut *conn, id, user_id)
+            .await?;
+        let source_pocket_id = req.source_pocket_id.unwrap_or(old.source_pocket_id);
+        let destination_pocket_id = req
+            .destination_pocket_id
+            .unwrap_or(old.destination_pocket_id);
+        if source_pocket_id == destination_pocket_id {
+            return Err(AppError::ValidationError(
+                "Cannot transfer to the same pocket".to_string(),
+            ));
+        }
+        let source = self
+            .pocket_repo
+            .get_by_id(source_pocket_id, user_id)
+            .await?;
+        let destination = self
+            .pocket_repo
+            .get_by_id(destination_pocket_id, user_id)
+            .await?;
+        if source_pocket_id != old.source_pocket_id {
+            check_pocket_active(&source)?;
+        }
+        if destination_pocket_id != old.destination_pocket_id {
+            check_pocket_active(&destination)?;
+        }
+
+        let amount = req.amount.unwrap_or(old.amount);
+        let occurred_at = req.occurred_at.unwrap_or(old.occurred_at);
+        let same_terms = source_pocket_id == old.source_pocket_id
+            && destination_pocket_id == old.destination_pocket_id
+            && occurred_at == old.occurred_at;
+        // Keep the agreed rate unless the pockets or date change, like transaction edits do
+        let exchange_rate = req
+            .exchange_rate
+            .or(old.exchange_rate.filter(|_| same_terms));
+        let fee = match req.fee {
+            Some(fee) => fee,
+            None if source.currency == destination.currency => Decimal::ZERO,
+            None => old.fee,
+        };
+        let (destination_amount, exchange_rate) = self
+            .transfer_terms(
+                &source,
+                &destination,
+                amount,
+                fee,
+                exchange_rate,
+                occurred_at,
+            )
+            .await?;
+        let new = TransferRow {
+            source_pocket_id,
+            destination_pocket_id,
+            amount,
+            destination_amount,
+            exchange_rate,
+            fee,
+            description: match req.description {
+                Some(d) if !d.trim().is_empty() => Some(d),
+                _ => old.description.clone(),
+            },
+            occurred_at,
+            ..old
+        };
+
+        if !source.is_credit_card()
+            && (new.source_pocket_id != old.source_pocket_id || new.amount > old.amount)
+        {
+            self.pocket_repo
+                .lock_tx(&mut *conn, new.source_pocket_id, user_id)
+                .await?;
+            // Balance as if this transfer had not happened yet
+            let mut available = self
+                .transaction_repo
+                .get_pocket_balance_tx(&mut *conn, user_id, new.source_pocket_id)
+                .await?;
+            if old.source_pocket_id == new.source_pocket_id {
+                available += old.amount;
+            }
+            if old.destination_pocket_id == new.source_pocket_id {
+                available -= old.destination_amount;
+            }
+            if available < new.amount {
+                return Err(AppError::ValidationError(
+                    "Insufficient funds in source pocket".to_string(),
+                ));
+            }
+        }
+
+        self.transaction_repo
+            .update_transfer_tx(&mut *conn, user_id, &new)
+            .await?;
+
+        let money_changed = new.amount != old.amount
+            || new.destination_amount != old.destination_amount
+            || new.exchange_rate != old.exchange_rate
+            || !same_terms;
+        let (out_leg, in_leg) = if money_changed {
+            let transfer = NewTransfer {
+                source_pocket_id: new.source_pocket_id,
+                destination_pocket_id: new.destination_pocket_id,
+                amount: new.amount,
+                destination_amount: new.destination_amount,
+                exchange_rate: new.exchange_rate,
+                fee: new.fee,
+                description: new.description.clone(),
+                occurred_at: new.occurred_at,
+            };
+            let (out_leg, in_leg) = self
+                .transfer_leg_amounts(user_id, &source, &destination, &transfer)
+                .await?;
+            (Some(out_leg), Some(in_leg))
+        } else {
+            (None, None)
+        };
+
+        let occurred_at = (new.occurred_at != old.occurred_at).then_some(new.occurred_at);
+        let description_changed = new.description != old.description;
+        let legs = [
+            (
+                new.out_transaction_id,
+                out_leg,
+                new.source_pocket_id,
+                old.source_pocket_id,
+                "Transfer Out",
+            ),
+            (
+                new.in_transaction_id,
+                in_leg,
+                new.destination_pocket_id,
+                old.destination_pocket_id,
+                "Transfer In",
+            ),
+        ];
+        for (leg_id, converted, pocket_id, old_pocket_id, default_description) in legs {
+            let mut changes = TransactionChanges {
+                occurred_at,
+                description: description_changed.then(|| {
+                    new.description
+                        .clone()
+                        .unwrap_or_else(|| default_description.to_string())
+                }),
+                pocket_id: (pocket_id != old_pocket_id).then_some(pocket_id),
+                ..Default::default()
+            };
+            if let Some(converted) = converted {
+                changes.clear_foreign_currency = converted.original_currency.is_none();
+                changes.amount = Some(converted.amount);
+                changes.original_currency = converted.original_currency;
+                changes.original_amount = converted.original_amount;
+                changes.exchange_rate = converted.exchange_rate;
+            }
+            self.transaction_repo
+                .update_tx(&mut *conn, leg_id, user_id, &changes)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Move both legs of the transfer to the trash
+    pub async fn delete_transfer(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let transfer = self.transaction_repo.get_transfer(id, user_id).await?;
+        self.delete_transaction(transfer.out_transaction_id, user_id)
+            .await
+    }
+}
+
+fn locked_error() -> AppError {
+    AppError::ConflictError(
+        "Transaction is reconciled; unlock it before making changes".to_string(),
+    )
+}
+
+/// Archived pockets keep their history but accept no new money movements
+fn check_pocket_active(pocket: &Pocket) -> Result<(), AppError> {
+    if pocket.archived_at.is_some() {
+        return Err(AppError::ValidationError(format!(
+            "Pocket '{}' is archived",
+            pocket.name
+        )));
+    }
+    Ok(())
+}
+
+/// Pockets outside the base currency only hold transactions in their own currency
+fn check_pocket_currency(
+    pocket: &Pocket,
+    currency: &str,
+    base_currency: &str,
+) -> Result<(), AppError> {
+    if pocket.currency != base_currency && pocket.currency != currency {
+        return Err(AppError::ValidationError(format!(
+            "Transactions in pocket '{}' must be in {}",
+            pocket.name, pocket.currency
+        )));
+    }
+    Ok(())
+}
+
+/// Names of the fields that differ between two snapshots
+fn changed_fields(old: &TransactionSnapshot, new: &TransactionSnapshot) -> Vec<&'static str> {
+    let mut fields = Vec::new();
+    if old.amount != new.amount {
+        fields.push("amount");
+    }
+    if old.description != new.description {
+        fields.push("description");
+    }
+    if old.category_id != new.category_id {
+        fields.push("category_id");
+    }
+    if old.occurred_at != new.occurred_at {
+        fields.push("occurred_at");
+    }
+    if old.original_currency != new.original_currency {
+        fields.push("original_currency");
+    }
+    if old.original_amount != new.original_amount {
+        fields.push("original_amount");
+    }
+    if old.exchange_rate != new.exchange_rate {
+        fields.push("exchange_rate");
+    }
+    if old.pocket_id != new.pocket_id {
+        fields.push("pocket_id");
+    }
+    if old.tags != new.tags {
+        fields.push("tags");
+    }
+    if old.payee_id != new.payee_id {
+        fields.push("payee_id");
+    }
+    fields
+}
+
+/// Trim, drop empty tags and de-duplicate while keeping the original order
+fn normalize_tags(tags: impl IntoIterator<Item = String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !normalized.iter().any(|t| t == tag) {
+            normalized.push(tag.to_string());
+        }
+    }
+    normalized
+}
+
+/// Exchange rates by date. Past dates are stored permanently since published rates never
+/// change; today's rate comes from the latest quote.
+pub struct ExchangeRateService {
+    rate_repo: ExchangeRateRepository,
+    exchange_rate_cache: moka::future::Cache<String, Decimal>,
+    http_client: reqwest::Client,
+}
+
+impl ExchangeRateService {
+    pub fn new(
+        rate_repo: ExchangeRateRepository,
+        exchange_rate_cache: moka::future::Cache<String, Decimal>,
+        http_client: reqwest::Client,
+    ) -> Self {
+        Self {
+            rate_repo,
+            exchange_rate_cache,
+            http_client,
+        }
+    }
+
+    pub async fn rate_on(
+        &self,
+        from: &str,
+        to: &str,
+        date: NaiveDate,
+    ) -> Result<Decimal, AppError> {
+        if from == to {
+            return Ok(Decimal::ONE);
+        }
+        if date >= Utc::now().date_naive() {
+            return self.latest_rate(from, to).await;
+        }
+
+        if let Some(rate) = self.rate_repo.get(from, to, date).await? {
+            return Ok(rate);
+        }
+        let rate = investments::fetch_exchange_rate_on(&self.http_client, from, to, date).await?;
+        self.rate_repo.insert(from, to, date, rate).await?;
+        Ok(rate)
+    }
+
+    /// Today's rate, shared through the same cache as `FinanceService`
+    async fn latest_rate(&self, from: &str, to: &str) -> Result<Decimal, AppError> {
+        let cache_key = format!("{}_{}", from, to);
+        if let Some(rate) = self.exchange_rate_cache.get(&cache_key).await {
+            return Ok(rate);
+        }
+
+        let rate = investments::fetch_exchange_rate(&self.http_client, from, to).await?;
+        self.exchange_rate_cache.insert(cache_key, rate).await;
+        Ok(rate)
+    }
+
+    /// Rate for every day from `start` to `end` (both before today), fetched as one series
+    /// instead of day by day. Days without a published rate use the previous working day's.
+    pub async fn rates_between(
+        &self,
+        from: &str,
+        to: &str,
+        start: NaiveDate,
+        end: NaiveDate,
+    ) -> Result<HashMap<NaiveDate, Decimal>, AppError> {
+        if from == to {
+            return Ok(start
+                .iter_days()
+                .take_while(|d| *d <= end)
+                .map(|d| (d, Decimal::ONE))
+                .collect());
+        }
+        if start == end {
+            let rate = self.rate_on(from, to, start).await?;
+            return Ok(HashMap::from([(start, rate)]));
+        }
+
+        let stored = self.rate_repo.get_range(from, to, start, end).await?;
+        if stored.len() as i64 == (end - start).num_days() + 1 {
+            return Ok(stored.into_iter().collect());
+        }
+
+        let published =
+            investments::fetch_exchange_rates_between(&self.http_client, from, to, start, end)
+                .await?;
+        let mut rates = net_worth::daily_rates(start, end, &published);
+        // The series normally starts on or before `start`; if it doesn't, ask for that day
+        if rates.first().map(|(day, _)| *day) != Some(start) {
+            let seed = self.rate_on(from, to, start).await?;
+            let mut seeded = vec![(start, seed)];
+            seeded.extend(published);
+            rates = net_worth::daily_rates(start, end, &seeded);
+        }
+        self.rate_repo.insert_many(from, to, &rates).await?;
+        Ok(rates.into_iter().collect())
+    }
+}
+
+pub struct FinanceService {
+    portfolio_repo: PortfolioRepository,
+    transaction_repo: TransactionRepository,
+    settings_repo: SettingsRepository,
+    liability_repo: LiabilityRepository,
+    price_cache: moka::future::Cache<String, Decimal>,
+    exchange_rate_cache: moka::future::Cache<String, Decimal>,
+    http_client: reqwest::Client,
+}
+
+impl FinanceService {
+    pub fn new(
+        portfolio_repo: PortfolioRepository,
+        transaction_repo: TransactionRepository,
+        settings_repo: SettingsRepository,
+        liability_repo: LiabilityRepository,
+        price_cache: moka::future::Cache<String, Decimal>,
+        exchange_rate_cache: moka::future::Cache<String, Decimal>,
+        http_client: reqwest::Client,
+    ) -> Self {
+        Self {
+            portfolio_repo,
+            transaction_repo,
+            settings_repo,
+            liability_repo,
+            price_cache,
+            exchange_rate_cache,
+            http_client,
+        }
+    }
+
+    /// Cached exchange rate lookup with 60s TTL
+    async fn get_cached_exchange_rate(&self, from: &str, to: &str) -> Result<Decimal, AppError> {
+        if from == to {
+            return Ok(Decimal::new(1, 0));
+        }
+
+        let cache_key = format!("{}_{}", from, to);
+        if let Some(rate) = self.exchange_rate_cache.get(&cache_key).await {
+            tracing::info!("Exchange rate cache HIT for {} -> {}", from, to);
+            return Ok(rate);
+        }
+
+        tracing::info!("Exchange rate cache MISS for {} -> {}", from, to);
+        let rate = investments::fetch_exchange_rate(&self.http_client, from, to).await?;
+        self.exchange_rate_cache.insert(cache_key, rate).await;
+        Ok(rate)
+    }
+
+    pub async fn get_financial_health(&self, user_id: Uuid) -> Result<FinancialHealth, AppError> {
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        // Each currency's cash is converted at today's rate
+        let mut cash = Decimal::ZERO;
+        let mut posted_cash = Decimal::ZERO;
+        for row in self.transaction_repo.get_cash_by_currency(user_id).await? {
+            let rate = self
+                .get_cached_exchange_rate(&row.currency, &base_currency)
+                .await?;
+            cash += row.balance * rate;
+            posted_cash += row.posted_balance * rate;
+        }
+        let invested_usd = self.portfolio_repo.get_total_invested(user_id).await?;
+
+        let rate = if base_currency != "USD" {
+            self.get_cached_exchange_rate("USD", &base_currency).await?
+        } else {
+            Decimal::new(1, 0)
+        };
+
+        let invested_converted = invested_usd * rate;
+
+        // Card debt is a liability; a card in credit counts as cash
+        let mut liabilities = Decimal::ZERO;
+        for card in self.transaction_repo.get_card_balances(user_id).await? {
+            let rate = self
+                .get_cached_exchange_rate(&card.currency, &base_currency)
+                .await?;
+            if card.balance < Decimal::ZERO {
+                liabilities -= card.balance * rate;
+            } else {
+                cash += card.balance * rate;
+            }
+            if card.posted_balance > Decimal::ZERO {
+                posted_cash += card.posted_balance * rate;
+            }
+        }
+
+        // What is still owed on each liability, converted like cash
+        let mut repayments =
+            repayments_by_liability(self.liability_repo.get_payments(user_id, None).await?);
+        for liability in self.liability_repo.get_all(user_id).await? {
+            let paid = repayments.remove(&liability.id).unwrap_or_default();
+            let rate = self
+                .get_cached_exchange_rate(&liability.currency, &base_currency)
+                .await?;
+            liabilities += amortization::outstanding(&liability, &paid) * rate;
+        }
+
+        let net_worth = cash + invested_converted - liabilities;
+
+        Ok(FinancialHealth {
+            cash_balance: cash,
+            posted_cash_balance: posted_cash,
+            investment_balance: invested_converted,
+            liabilities_balance: liabilities,
+            total_net_worth: net_worth,
+        })
+    }
+
+    pub async fn refresh_portfolio(&self, user_id: Uuid) -> Result<u64, AppError> {
+        let tickers = self.portfolio_repo.get_tickers(user_id).await?;
+        let count = tickers.len() as u64;
+
+        let fetch_futures: Vec<_> = tickers
+            .iter()
+            .map(|ticker| async move {
+                if let Err(e) = self.ensure_price_fresh(ticker).await {
+                    tracing::error!("Failed to refresh price for {}: {:?}", ticker, e);
+                }
+            })
+            .collect();
+        futures::future::join_all(fetch_futures).await;
+
+        Ok(count)
+    }
+
+    pub async fn add_investment(
+        &self,
+        user_id: Uuid,
+        item: CreatePortfolioItem,
+    ) -> Result<(), AppError> {
+        // Validate ticker and ensure price is in assets table
+        self.ensure_price_fresh(&item.ticker).await?;
+
+        // Ensure asset exists in DB (already done by seed data or we error out in add_item)
+
+        match self.portfolio_repo.add_item(user_id, item.clone()).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                // AppError is likely DatabaseError
+                // Simple string matching for now
+                let msg = format!("{:?}", e);
+                if msg.contains("duplicate key value") {
+                    Err(AppError::ValidationError(format!(
+                        "{} is already in your portfolio",
+                        &item.ticker,
+                    )))
+                } else {
+                    Err(e)
+                }
+            }
+        }
+    }
+
+    // Helper to get price with cache
+    async fn ensure_price_fresh(&self, ticker: &str) -> Result<Decimal, AppError> {
+        if let Some(price) = self.price_cache.get(ticker).await {
+            tracing::info!("Cache HIT for {}", ticker);
+            return Ok(price);
+        }
+        tracing::info!("Cache MISS for {}", ticker);
+
+        // Fetch asset from DB to know Source and API Ticker
+        let asset_opt = self.portfolio_repo.get_asset(ticker).await?;
+        let (api_ticker, source) = if let Some(asset) = asset_opt {
+            let api_ticker = asset.api_ticker.unwrap_or(ticker.to_string());
+            let source = asset.source.unwrap_or("YAHOO".to_string());
+
+            // NEW: Check for missing icon and populate it lazily
+            if asset.icon_url.is_none() && source == "COINGECKO" {
+                tracing::info!("Missing icon for {}, fetching from CoinGecko...", ticker);
+                // We don't want to fail the whole request if icon fetch fails
+                match investments::fetch_coingecko_icon(&self.http_client, &api_ticker).await {
+                    Ok(Some(url)) => {
+                        if let Err(e) = self.portfolio_repo.update_asset_icon(ticker, &url).await {
+                            tracing::error!("Failed to save icon for {}: {:?}", ticker, e);
+                        } else {
+                            tracing::info!("Updated icon for {}", ticker);
+                        }
+                    }
+                    Ok(None) => tracing::warn!("No icon found for {}", ticker),
+                    Err(e) => tracing::error!("Failed to fetch icon for {}: {:?}", ticker, e),
+                }
+            }
+
+            (api_ticker, source)
+        } else {
+            // If asset not found in DB, for now we default to YAHOO/Ticker
+            // (e.g. legacy behavior or if someone manually inserted via sql)
+            (ticker.to_string(), "YAHOO".to_string())
+        };
+
+        let (price, currency) =
+            investments::fetch_price_with_source(&self.http_client, ticker, &api_ticker, &source)
+                .await?;
+
+        // Update DB
+        self.portfolio_repo
+            .update_asset_price(ticker, price, &currency)
+            .await?;
+
+        self.price_cache.insert(ticker.to_string(), price).await;
+
+        Ok(price)
+    }
+
+    pub async fn get_portfolio_list(
+        &self,
+        user_id: Uuid,
+    ) -> Result<crate::schemas::PortfolioResponse, AppError> {
+        // Fetch prices in parallel for all tickers
+        let tickers = self.portfolio_repo.get_tickers(user_id).await?;
+        let fetch_futures: Vec<_> = tickers
+            .iter()
+            .map(|ticker| async move {
+                if let Err(e) = self.ensure_price_fresh(ticker).await {
+                    tracing::error!("Failed to refresh price for {}: {:?}", ticker, e);
+                }
+            })
+            .collect();
+        futures::future::join_all(fetch_futures).await;
+
+        // Get data
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let items = self.portfolio_repo.get_all_joined(user_id).await?;
+
+        // Pre-fetch all unique exchange rates (async part)
+        let unique_currencies: std::collections::HashSet<String> = items
+            .iter()
+            .filter_map(|item| item.currency.clone())
+            .filter(|c| c != &base_currency)
+            .collect();
+
+        let mut exchange_rates = std::collections::HashMap::new();
+        for currency in unique_currencies {
+            let rate = self
+                .get_cached_exchange_rate(&currency, &base_currency)
+                .await?;
+            exchange_rates.insert(currency, rate);
+        }
+
+        // Use tested pure function to build the full response
+        Ok(crate::portfolio::build_portfolio_response(
+            items,
+            &exchange_rates,
+            &base_currency,
+        ))
+    }
+    pub async fn update_base_currency(
+        &self,
+        user_id: Uuid,
+        currency: String,
+    ) -> Result<(), AppError> {
+        if !self.settings_repo.validate_currency(&currency).await? {
+            return Err(AppError::ValidationError(format!(
+                "Invalid currency code: {}",
+                currency
+            )));
+        }
+        self.settings_repo
+            .set_base_currency(user_id, &currency)
+            .await
+    }
+
+    pub async fn remove_investment(&self, user_id: Uuid, ticker: String) -> Result<(), AppError> {
+        let deleted = self.portfolio_repo.delete(user_id, &ticker).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError(format!(
+                "Investment {} not found",
+                ticker
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn update_investment(
+        &self,
+        user_id: Uuid,
+        ticker: String,
+        payload: UpdateInvestment,
+    ) -> Result<(), AppError> {
+        self.portfolio_repo
+            .update(user_id, &ticker, payload.quantity, payload.avg_buy_price)
+            .await
+    }
+}
+
+/// Upper bound on buckets returned by the balance history, cash-flow and net-worth history
+/// endpoints, and on days backfilled at once
+const MAX_HISTORY_POINTS: i64 = 1000;
+
+pub struct PocketService {
+    pocket_repo: PocketRepository,
+    settings_repo: SettingsRepository,
+}
+
+impl PocketService {
+    pub fn new(pocket_repo: PocketRepository, settings_repo: SettingsRepository) -> Self {
+        Self {
+            pocket_repo,
+            settings_repo,
+        }
+    }
+
+    pub async fn create_pocket(&self, user_id: Uuid, req: CreatePocket) -> Result<Uuid, AppError> {
+        if req.name.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Pocket name cannot be empty".to_string(),
+            ));
+        }
+
+        let currency = match req.currency {
+            Some(code) => {
+                let code = code.trim().to_uppercase();
+                if !self.settings_repo.validate_currency(&code).await? {
+                    return Err(AppError::ValidationError(format!(
+                        "Invalid currency code: {}",
+                        code
+                    )));
+                }
+                code
+            }
+            None => self.settings_repo.get_base_currency(user_id).await?,
+        };
+
+        let credit_card = match (req.kind, &req.credit_card) {
+            (PocketKind::CreditCard, Some(settings)) => {
+                let card = credit_cards::settings(settings);
+                Self::validate_credit_card(&card)?;
+                Some(card)
+            }
+            (PocketKind::CreditCard, None) => {
+                return Err(AppError::ValidationError(
+                    "Credit card pockets need credit_card settings".to_string(),
+                ));
+            }
+            (PocketKind::Cash, Some(_)) => {
+                return Err(AppError::ValidationError(
+                    "Only credit card pockets take credit_card settings".to_string(),
+                ));
+            }
+            (PocketKind::Cash, None) => None,
+        };
+
+        self.pocket_repo
+            .create(
+                user_id,
+                &req.name,
+                req.description,
+                req.icon,
+                &currency,
+                credit_card.as_ref(),
+            )
+            .await
+    }
+
+    pub async fn update_credit_card(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: CreditCardSettings,
+    ) -> Result<(), AppError> {
+        let pocket = self.get_credit_card_pocket(id, user_id).await?;
+        let card = credit_cards::settings(&req);
+        Self::validate_credit_card(&card)?;
+        self.pocket_repo.set_credit_card(pocket.id, &card).await
+    }
+
+    pub async fn get_card_statement(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<CardStatement, AppError> {
+        let pocket = self.get_credit_card_pocket(id, user_id).await?;
+        let card = self.pocket_repo.get_credit_card(pocket.id).await?;
+        let today = Utc::now().date_naive();
+        let activity = self
+            .pocket_repo
+            .get_card_activity(pocket.id, credit_cards::last_closing(&card, today))
+            .await?;
+        Ok(credit_cards::statement(&pocket, card, &activity, today))
+    }
+
+    /// The transfer that pays a credit card from a cash pocket, by default for the rest of the
+    /// last statement balance
+    pub async fn card_payment(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: PayCardRequest,
+    ) -> Result<TransferRequest, AppError> {
+        let statement = self.get_card_statement(id, user_id).await?;
+        let source = self
+            .pocket_repo
+            .get_by_id(req.source_pocket_id, user_id)
+            .await?;
+        if source.is_credit_card() {
+            return Err(AppError::ValidationError(
+                "Cards must be paid from a cash pocket".to_string(),
+            ));
+        }
+        if source.currency != statement.currency {
+            return Err(AppError::ValidationError(format!(
+                "Cards must be paid from a pocket in {}",
+                statement.currency
+            )));
+        }
+
+        let amount = match req.amount {
+            Some(amount) => amount,
+            None if statement.remaining_statement_balance > Decimal::ZERO => {
+                statement.remaining_statement_balance
+            }
+            None => {
+                return Err(AppError::ValidationError(
+                    "Nothing is due on this card".to_string(),
+                ));
+            }
+        };
+
+        Ok(TransferRequest {
+            source_pocket_id: source.id,
+            destination_pocket_id: statement.pocket_id,
+            amount,
+            description: req
+                .description
+                .or_else(|| Some("Credit card payment".to_string())),
+            occurred_at: req.occurred_at,
+            exchange_rate: None,
+            fee: None,
+        })
+    }
+
+    async fn get_credit_card_pocket(&self, id: Uuid, user_id: Uuid) -> Result<Pocket, AppError> {
+        let pocket = self.pocket_repo.get_by_id(id, user_id).await?;
+        if !pocket.is_credit_card() {
+            return Err(AppError::ValidationError(
+                "This pocket is not a credit card".to_string(),
+            ));
+        }
+        Ok(pocket)
+    }
+
+    fn validate_credit_card(card: &CreditCard) -> Result<(), AppError> {
+        if !(1..=31).contains(&card.statement_day) {
+            return Err(AppError::ValidationError(
+                "statement_day must be between 1 and 31".to_string(),
+            ));
+        }
+        if !(1..=60).contains(&card.due_days) {
+            return Err(AppError::ValidationError(
+                "due_days must be between 1 and 60".to_string(),
+            ));
+        }
+        if card.credit_limit.is_some_and(|l| l <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "credit_limit must be positive".to_string(),
+            ));
+        }
+        if card.minimum_payment_percent < Decimal::ZERO
+            || card.minimum_payment_percent > Decimal::ONE_HUNDRED
+        {
+            return Err(AppError::ValidationError(
+                "minimum_payment_percent must be between 0 and 100".to_string(),
+            ));
+        }
+        if card.minimum_payment_floor < Decimal::ZERO {
+            return Err(AppError::ValidationError(
+                "minimum_payment_floor must not be negative".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn get_pockets(
+        &self,
+        user_id: Uuid,
+        include_archived: bool,
+    ) -> Result<Vec<Pocket>, AppError> {
+        self.pocket_repo.get_all(user_id, include_archived).await
+    }
+
+    pub async fn get_pocket(&self, id: Uuid, user_id: Uuid) -> Result<Pocket, AppError> {
+        self.pocket_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn get_statement(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+    ) -> Result<PocketStatement, AppError> {
+        if end_date < start_date {
+            return Err(AppError::ValidationError(
+                "end_date must not be before start_date".to_string(),
+            ));
+        }
+
+        let pocket = self.pocket_repo.get_by_id(id, user_id).await?;
+        let opening_balance = self
+            .pocket_repo
+            .get_balance_before(id, user_id, start_date)
+            .await?;
+        let rows = self
+            .pocket_repo
+            .get_statement_rows(id, user_id, start_date, end_date)
+            .await?;
+
+        let summary = PocketSummary {
+            id: pocket.id,
+            name: pocket.name,
+            icon: pocket.icon,
+            currency: pocket.currency,
+        };
+        Ok(statements::build(
+            summary,
+            start_date,
+            end_date,
+            opening_balance,
+            rows,
+        ))
+    }
+
+    pub async fn get_balance_history(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        params: BalanceHistoryParams,
+    ) -> Result<Vec<BalancePoint>, AppError> {
+        if params.end_date < params.start_date {
+            return Err(AppError::ValidationError(
+                "end_date must not be before start_date".to_string(),
+            ));
+        }
+        let (start, end) = (params.start_date, params.end_date);
+        let points = cash_flow::bucket_count(params.interval, start.date_naive(), end.date_naive());
+        if points > MAX_HISTORY_POINTS {
+            return Err(AppError::ValidationError(format!(
+                "Range covers {} points; at most {} are allowed",
+                points, MAX_HISTORY_POINTS
+            )));
+        }
+
+        self.pocket_repo.get_by_id(id, user_id).await?;
+        self.pocket_repo
+            .get_balance_history(id, user_id, start, end, params.interval)
+            .await
+    }
+
+    pub async fn update_pocket(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdatePocket,
+    ) -> Result<(), AppError> {
+        // Validate name if provided
+        if let Some(ref name) = req.name
+            && name.trim().is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "Pocket name cannot be empty".to_string(),
+            ));
+        }
+
+        self.pocket_repo
+            .update(id, user_id, req.name, req.description, req.icon)
+            .await
+    }
+
+    pub async fn archive_pocket(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        archived: bool,
+    ) -> Result<(), AppError> {
+        let pocket = self.pocket_repo.get_by_id(id, user_id).await?;
+        if archived && pocket.is_default {
+            return Err(AppError::ValidationError(
+                "Cannot archive the default pocket".to_string(),
+            ));
+        }
+        self.pocket_repo.set_archived(id, user_id, archived).await?;
+        Ok(())
+    }
+
+    /// Delete a pocket. A pocket with history must name a `reassign_to` pocket of the same
+    /// currency and kind; its transactions, transfers, rules, goal and envelope allocations move there
+    /// before the pocket is removed.
+    /// Reconciled transactions block the reassignment, since their reconciliation would be lost.
+    pub async fn delete_pocket(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        reassign_to: Option<Uuid>,
+    ) -> Result<DeletePocketResponse, AppError> {
+        let deleted = match reassign_to {
+            None => {
+                if self.pocket_repo.has_transactions(id, user_id).await? {
+                    return Err(AppError::ValidationError(
+                        "Pocket has transactions; choose a pocket to move them to with reassign_to"
+                            .to_string(),
+                    ));
+                }
+                (self.pocket_repo.delete(id, user_id).await? > 0)
+                    .then(DeletePocketResponse::default)
+            }
+            Some(target_id) => {
+                let pocket = self.pocket_repo.get_by_id(id, user_id).await?;
+                if pocket.is_default {
+                    return Err(AppError::ValidationError(
+                        "Cannot delete the default pocket".to_string(),
+                    ));
+                }
+                let target = self.pocket_repo.get_by_id(target_id, user_id).await?;
+                if let Some(conflict) = pockets::reassign_conflict(&pocket, &target) {
+                    return Err(AppError::ValidationError(conflict));
+                }
+                check_pocket_active(&target)?;
+                if self
+                    .pocket_repo
+                    .has_reconciled_for_reassign(id, user_id, target_id)
+                    .await?
+                {
+                    return Err(AppError::ConflictError(
+                        "Pocket has reconciled transactions; unlock them before deleting it"
+                            .to_string(),
+                    ));
+                }
+                self.pocket_repo
+                    .reassign_and_delete(id, user_id, target_id)
+                    .await?
+            }
+        };
+        deleted.ok_or(AppError::NotFoundError("Pocket not found".to_string()))
+    }
+}
+
+pub struct BudgetService {
+    budget_repo: BudgetRepository,
+    transaction_repo: TransactionRepository,
+}
+
+impl BudgetService {
+    pub fn new(budget_repo: BudgetRepository, transaction_repo: TransactionRepository) -> Self {
+        Self {
+            budget_repo,
+            transaction_repo,
+        }
+    }
+
+    pub async fn get_budgets(&self, user_id: Uuid) -> Result<Vec<Budget>, AppError> {
+        self.budget_repo.get_all(user_id).await
+    }
+
+    pub async fn get_budget(&self, id: Uuid, user_id: Uuid) -> Result<Budget, AppError> {
+        self.budget_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn create_budget(&self, user_id: Uuid, req: CreateBudget) -> Result<Uuid, AppError> {
+        Self::validate_amount(Some(req.amount))?;
+        let category = self
+            .transaction_repo
+            .get_all_categories()
+            .await?
+            .into_iter()
+            .find(|c| c.id == req.category_id)
+            .ok_or(AppError::ValidationError("Category not found".to_string()))?;
+        if category.is_income || category.exclude_from_analysis {
+            return Err(AppError::ValidationError(format!(
+                "Category '{}' cannot have a budget; budgets apply to expense categories",
+                category.name
+            )));
+        }
+
+        let starts_on = budgets::month_of(req.starts_on.unwrap_or_else(|| Utc::now().date_naive()));
+        self.budget_repo
+            .create(user_id, category.id, req.amount, req.rollover, starts_on)
+            .await
+    }
+
+    pub async fn update_budget(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdateBudget,
+    ) -> Result<(), AppError> {
+        Self::validate_amount(req.amount)?;
+        let updated = self
+            .budget_repo
+            .update(id, user_id, req.amount, req.rollover)
+            .await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError("Budget not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_budget(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = self.budget_repo.delete(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Budget not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Budget vs actual for the month containing `month` (default: the current month).
+    /// Spending comes from the same category aggregation as the spending analysis.
+    pub async fn get_report(
+        &self,
+        user_id: Uuid,
+        month: Option<NaiveDate>,
+    ) -> Result<BudgetReport, AppError> {
+        let today = Utc::now().date_naive();
+        let month = budgets::month_of(month.unwrap_or(today));
+        let active: Vec<Budget> = self
+            .budget_repo
+            .get_all(user_id)
+            .await?
+            .into_iter()
+            .filter(|b| b.starts_on <= month)
+            .collect();
+
+        // Each earlier month is aggregated once, however many budgets roll over from it
+        let rollover_months: BTreeSet<NaiveDate> = active
+            .iter()
+            .flat_map(|b| budgets::rollover_months(b, month))
+            .collect();
+        let mut history = HashMap::new();
+        for earlier in rollover_months {
+            history.insert(earlier, self.spending_in(user_id, earlier).await?);
+        }
+        let spending = self.spending_in(user_id, month).await?;
+
+        let lines: Vec<BudgetLine> = active
+            .iter()
+            .map(|budget| {
+                let spent_before: Vec<Decimal> = budgets::rollover_months(budget, month)
+                    .iter()
+                    .map(|m| budgets::spent(&history[m], budget.category_id))
+                    .collect();
+                let carried = budgets::carried_over(budget.amount, &spent_before);
+                let spent = budgets::spent(&spending, budget.category_id);
+                budgets::line(budget, carried, spent, month, today)
+            })
+            .collect();
+        let unbudgeted: Vec<CategorySummary> = spending
+            .into_iter()
+            .filter(|c| !c.is_income && !active.iter().any(|b| b.category_id == c.category_id))
+            .collect();
+
+        Ok(BudgetReport {
+            month,
+            total_available: lines.iter().map(|l| l.available).sum(),
+            total_spent: lines.iter().map(|l| l.spent).sum(),
+            lines,
+            unbudgeted,
+        })
+    }
+
+    /// Current-month budgets that have reached 80% or 100% of what is available
+    pub async fn get_alerts(&self, user_id: Uuid) -> Result<Vec<BudgetLine>, AppError> {
+        let report = self.get_report(user_id, None).await?;
+        Ok(report
+            .lines
+            .into_iter()
+            .filter(|l| l.alert.is_some())
+            .collect())
+    }
+
+    async fn spending_in(
+        &self,
+        user_id: Uuid,
+        month: NaiveDate,
+    ) -> Result<Vec<CategorySummary>, AppError> {
+        let (start, end) = budgets::month_bounds(month);
+        self.transaction_repo
+            .get_spending_analysis(user_id, start, end)
+            .await
+    }
+
+    fn validate_amount(amount: Option<Decimal>) -> Result<(), AppError> {
+        if amount.is_some_and(|a| a <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "Budget amount must be positive".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct EnvelopeService {
+    envelope_repo: EnvelopeRepository,
+    settings_repo: SettingsRepository,
+    pocket_repo: PocketRepository,
+    transaction_repo: TransactionRepository,
+}
+
+impl EnvelopeService {
+    pub fn new(
+        envelope_repo: EnvelopeRepository,
+        settings_repo: SettingsRepository,
+        pocket_repo: PocketRepository,
+        transaction_repo: TransactionRepository,
+    ) -> Self {
+        Self {
+            envelope_repo,
+            settings_repo,
+            pocket_repo,
+            transaction_repo,
+        }
+    }
+
+    /// Turn zero-based budgeting on or off. Turning it on starts counting income and spending
+    /// from the current month; switching it on again while already on keeps the original start.
+    pub async fn set_mode(&self, user_id: Uuid, zero_based: bool) -> Result<(), AppError> {
+        let current = self.settings_repo.get_zero_based_since(user_id).await?;
+        let since = match (zero_based, current) {
+            (false, _) => None,
+            (true, Some(since)) => Some(since),
+            (true, None) => Some(budgets::month_of(Utc::now().date_naive())),
+        };
+        self.settings_repo
+            .set_zero_based_since(user_id, since)
+            .await
+    }
+
+    pub async fn get_report(
+        &self,
+        user_id: Uuid,
+        month: Option<NaiveDate>,
+    ) -> Result<EnvelopeReport, AppError> {
+        let since = self.enabled_since(user_id).await?;
+        let month = budgets::month_of(month.unwrap_or_else(|| Utc::now().date_naive()));
+        Self::check_month(month, since)?;
+
+        let allocations = self
+            .envelope_repo
+            .get_allocations(user_id, since, month)
+            .await?;
+        let activity = self
+            .envelope_repo
+            .get_activity(user_id, since, month + Months::new(1))
+            .await?;
+        Ok(envelopes::build(month, &allocations, &activity))
+    }
+
+    /// Set what is assigned to one envelope for a month, replacing any earlier assignment
+    pub async fn assign(&self, user_id: Uuid, req: AssignEnvelope) -> Result<(), AppError> {
+        let since = self.enabled_since(user_id).await?;
+        let month = budgets::month_of(req.month);
+        Self::check_month(month, since)?;
+        if req.amount < Decimal::ZERO {
+            return Err(AppError::ValidationError(
+                "Assigned amount cannot be negative".to_string(),
+            ));
+        }
+
+        match (req.category_id, req.pocket_id) {
+            (Some(category_id), None) => {
+                let category = self
+                    .transaction_repo
+                    .get_all_categories()
+                    .await?
+                    .into_iter()
+                    .find(|c| c.id == category_id)
+                    .ok_or(AppError::ValidationError("Category not found".to_string()))?;
+                if category.is_income || category.exclude_from_analysis {
+                    return Err(AppError::ValidationError(format!(
+                        "Category '{}' cannot be an envelope; envelopes are expense categories or pockets",
+                        category.name
+                    )));
+                }
+                self.envelope_repo
+                    .assign_category(user_id, month, category_id, req.amount)
+                    .await
+            }
+            (None, Some(pocket_id)) => {
+                let pocket = self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+                check_pocket_active(&pocket)?;
+                self.envelope_repo
+                    .assign_pocket(user_id, month, pocket_id, req.amount)
+                    .await
+            }
+            _ => Err(AppError::ValidationError(
+                "Provide exactly one of category_id or pocket_id".to_string(),
+            )),
+        }
+    }
+
+    async fn enabled_since(&self, user_id: Uuid) -> Result<NaiveDate, AppError> {
+        self.settings_repo
+            .get_zero_based_since(user_id)
+            .await?
+            .ok_or(AppError::ValidationError(
+                "Zero-based budgeting is not enabled".to_string(),
+            ))
+    }
+
+    fn check_month(month: NaiveDate, since: NaiveDate) -> Result<(), AppError> {
+        if month < since {
+            return Err(AppError::ValidationError(format!(
+                "Zero-based budgeting starts in {}",
+                since.format("%Y-%m")
+            )));
+        }
+        Ok(())
+    }
+}
+
+pub struct GoalService {
+    goal_repo: GoalRepository,
+    pocket_repo: PocketRepository,
+}
+
+impl GoalService {
+    pub fn new(goal_repo: GoalRepository, pocket_repo: PocketRepository) -> Self {
+        Self {
+            goal_repo,
+            pocket_repo,
+        }
+    }
+
+    pub async fn get_goals(&self, user_id: Uuid) -> Result<Vec<Goal>, AppError> {
+        let rows = self.goal_repo.get_all(user_id).await?;
+        self.with_progress(user_id, rows).await
+    }
+
+    pub async fn get_goal(&self, id: Uuid, user_id: Uuid) -> Result<Goal, AppError> {
+        let row = self.goal_repo.get_by_id(id, user_id).await?;
+        let mut goals = self.with_progress(user_id, vec![row]).await?;
+        Ok(goals.remove(0))
+    }
+
+    pub async fn create_goal(&self, user_id: Uuid, req: CreateGoal) -> Result<Uuid, AppError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Goal name cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_terms(
+            Some(req.target_amount),
+            req.target_date,
+            req.monthly_contribution,
+        )?;
+        let pocket = self.pocket_repo.get_by_id(req.pocket_id, user_id).await?;
+        check_pocket_active(&pocket)?;
+
+        self.goal_repo
+            .create(
+                user_id,
+                pocket.id,
+                name,
+                req.target_amount,
+                req.target_date,
+                req.monthly_contribution,
+            )
+            .await
+    }
+
+    pub async fn update_goal(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdateGoal,
+    ) -> Result<(), AppError> {
+        if let Some(ref name) = req.name
+            && name.trim().is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "Goal name cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_terms(req.target_amount, req.target_date, req.monthly_contribution)?;
+
+        let updated = self
+            .goal_repo
+            .update(
+                id,
+                user_id,
+                req.name.map(|n| n.trim().to_string()),
+                req.target_amount,
+                req.target_date,
+                req.monthly_contribution,
+            )
+            .await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError("Goal not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_goal(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = self.goal_repo.delete(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Goal not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Attach progress to goals, estimating each pocket's saving rate from its recent months
+    async fn with_progress(
+        &self,
+        user_id: Uuid,
+        rows: Vec<GoalRow>,
+    ) -> Result<Vec<Goal>, AppError> {
+        let today = Utc::now().date_naive();
+        let pocket_ids: Vec<Uuid> = rows.iter().map(|g| g.pocket_id).collect();
+        let flows = self
+            .goal_repo
+            .get_monthly_flows(
+                user_id,
+                &pocket_ids,
+                goals::history_start(today),
+                goals::month_start(today),
+            )
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let pocket_flows: Vec<MonthlyFlowRow> = flows
+                    .iter()
+                    .filter(|f| f.pocket_id == row.pocket_id)
+                    .cloned()
+                    .collect();
+                let average = goals::average_monthly_saving(&pocket_flows, today);
+                let progress = goals::project(&row, average, today);
+                Goal {
+                    id: row.id,
+                    pocket: PocketSummary {
+                        id: row.pocket_id,
+                        name: row.pocket_name,
+                        icon: row.pocket_icon,
+                        currency: row.pocket_currency,
+                    },
+                    name: row.name,
+                    target_amount: row.target_amount,
+                    target_date: row.target_date,
+                    monthly_contribution: row.monthly_contribution,
+                    progress,
+                    created_at: row.created_at,
+                }
+            })
+            .collect())
+    }
+
+    fn validate_terms(
+        target_amount: Option<Decimal>,
+        target_date: Option<NaiveDate>,
+        monthly_contribution: Option<Decimal>,
+    ) -> Result<(), AppError> {
+        if target_amount.is_some_and(|a| a <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "target_amount must be positive".to_string(),
+            ));
+        }
+        if monthly_contribution.is_some_and(|c| c <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "monthly_contribution must be positive".to_string(),
+            ));
+        }
+        if target_date.is_some_and(|d| d < Utc::now().date_naive()) {
+            return Err(AppError::ValidationError(
+                "target_date must not be in the past".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Default and maximum look-ahead of the upcoming bills endpoint, in days
+const DEFAULT_UPCOMING_DAYS: i64 = 30;
+const MAX_UPCOMING_DAYS: i64 = 366;
+
+pub struct BillService {
+    bill_repo: BillRepository,
+    payee_repo: PayeeRepository,
+    pocket_repo: PocketRepository,
+}
+
+impl BillService {
+    pub fn new(
+        bill_repo: BillRepository,
+        payee_repo: PayeeRepository,
+        pocket_repo: PocketRepository,
+    ) -> Self {
+        Self {
+            bill_repo,
+            payee_repo,
+            pocket_repo,
+        }
+    }
+
+    pub async fn get_bills(&self, user_id: Uuid) -> Result<Vec<Bill>, AppError> {
+        let rows = self.bill_repo.get_all(user_id).await?;
+        Ok(rows.into_iter().map(Self::to_bill).collect())
+    }
+
+    pub async fn get_bill(&self, id: Uuid, user_id: Uuid) -> Result<Bill, AppError> {
+        let row = self.bill_repo.get_by_id(id, user_id).await?;
+        Ok(Self::to_bill(row))
+    }
+
+    pub async fn create_bill(&self, user_id: Uuid, req: CreateBill) -> Result<Uuid, AppError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Bill name cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_terms(Some(req.expected_amount), Some(req.due_day))?;
+        self.payee_repo.get_by_id(req.payee_id, user_id).await?;
+        if let Some(pocket_id) = req.pocket_id {
+            let pocket = self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+            check_pocket_active(&pocket)?;
+        }
+
+        let starts_on = req.starts_on.unwrap_or_else(|| Utc::now().date_naive());
+        self.bill_repo.create(user_id, &req, name, starts_on).await
+    }
+
+    pub async fn update_bill(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdateBill,
+    ) -> Result<(), AppError> {
+        if let Some(ref name) = req.name
+            && name.trim().is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "Bill name cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_terms(req.expected_amount, req.due_day)?;
+        if let Some(pocket_id) = req.pocket_id {
+            let pocket = self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+            check_pocket_active(&pocket)?;
+        }
+
+        let updated = self.bill_repo.update(id, user_id, &req).await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError("Bill not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_bill(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = self.bill_repo.delete(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Bill not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Occurrences due within the next `days` days, plus earlier ones that are still unpaid
+    pub async fn get_upcoming(
+        &self,
+        user_id: Uuid,
+        days: Option<i64>,
+    ) -> Result<Vec<BillOccurrence>, AppError> {
+        let days = days.unwrap_or(DEFAULT_UPCOMING_DAYS);
+        if !(0..=MAX_UPCOMING_DAYS).contains(&days) {
+            return Err(AppError::ValidationError(format!(
+                "days must be between 0 and {}",
+                MAX_UPCOMING_DAYS
+            )));
+        }
+
+        let today = Utc::now().date_naive();
+        let from = today - Duration::days(bills::OVERDUE_LOOKBACK_DAYS);
+        let bills = self.bill_repo.get_all(user_id).await?;
+        let bill_ids: Vec<Uuid> = bills.iter().map(|b| b.id).collect();
+        let payments = self.bill_repo.get_payments(&bill_ids, from).await?;
+
+        let mut occurrences = Vec::new();
+        for bill in &bills {
+            for due_date in bills::occurrences(bill, from, today + Duration::days(days)) {
+                let payment = payments
+                    .iter()
+                    .find(|p| p.bill_id == bill.id && p.due_date == due_date);
+                let status = bills::status(due_date, payment.is_some(), today);
+                // Past occurrences only matter while they are unpaid
+                if due_date < today && status == BillStatus::Paid {
+                    continue;
+                }
+                occurrences.push(BillOccurrence {
+                    bill_id: bill.id,
+                    name: bill.name.clone(),
+                    payee: PayeeSummary {
+                        id: bill.payee_id,
+                        name: bill.payee_name.clone(),
+                    },
+                    pocket: Self::pocket_summary(bill),
+                    expected_amount: bill.expected_amount,
+                    due_date,
+                    status,
+                    transaction_id: payment.map(|p| p.transaction_id),
+                });
+            }
+        }
+        occurrences.sort_by(|a, b| a.due_date.cmp(&b.due_date).then(a.name.cmp(&b.name)));
+        Ok(occurrences)
+    }
+
+    /// Mark bill occurrences paid by newly created transactions. Returns how many matched.
+    pub async fn match_transactions(
+        &self,
+        user_id: Uuid,
+        transaction_ids: &[Uuid],
+    ) -> Result<u64, AppError> {
+        let candidates = self
+            .bill_repo
+            .get_match_candidates(user_id, transaction_ids)
+            .await?;
+        let Some(earliest) = candidates.iter().map(|c| c.occurred_at.date_naive()).min() else {
+            return Ok(0);
+        };
+        let bills = self.bill_repo.get_all(user_id).await?;
+        if bills.is_empty() {
+            return Ok(0);
+        }
+
+        let bill_ids: Vec<Uuid> = bills.iter().map(|b| b.id).collect();
+        let since = earliest - Duration::days(bills::MATCH_WINDOW_DAYS);
+        let mut paid: HashSet<(Uuid, NaiveDate)> = self
+            .bill_repo
+            .get_payments(&bill_ids, since)
+            .await?
+            .into_iter()
+            .map(|p| (p.bill_id, p.due_date))
+            .collect();
+
+        let mut matched = 0;
+        for candidate in &candidates {
+            if let Some((bill_id, due_date)) = bills::find_match(&bills, &paid, candidate)
+                && self
+                    .bill_repo
+                    .record_payment(bill_id, due_date, candidate.id)
+                    .await?
+            {
+                paid.insert((bill_id, due_date));
+                matched += 1;
+            }
+        }
+        Ok(matched)
+    }
+
+    /// Notify users once about each bill occurrence that passed its due date unpaid.
+    /// Returns how many reminders were sent.
+    pub async fn send_overdue_reminders(&self, notifier: &dyn Notifier) -> Result<u64, AppError> {
+        let today = Utc::now().date_naive();
+        let from = today - Duration::days(bills::OVERDUE_LOOKBACK_DAYS);
+        let bills = self.bill_repo.get_all_users().await?;
+        let bill_ids: Vec<Uuid> = bills.iter().map(|b| b.id).collect();
+        let mut settled: HashSet<(Uuid, NaiveDate)> = self
+            .bill_repo
+            .get_payments(&bill_ids, from)
+            .await?
+            .into_iter()
+            .map(|p| (p.bill_id, p.due_date))
+            .collect();
+        settled.extend(self.bill_repo.get_reminders(&bill_ids, from).await?);
+
+        let mut sent = 0;
+        for bill in &bills {
+            for due_date in bills::occurrences(bill, from, today - Duration::days(1)) {
+                if settled.contains(&(bill.id, due_date)) {
+                    continue;
+                }
+                notifier
+                    .notify(&NewNotification {
+                        user_id: bill.user_id,
+                        kind: "bill_overdue",
+                        title: format!("{} is overdue", bill.name),
+                        body: format!(
+                            "{} to {} was due on {}",
+                            bill.expected_amount.round_dp(2),
+                            bill.payee_name,
+                            due_date
+                        ),
+                    })
+                    .await?;
+                self.bill_repo.record_reminder(bill.id, due_date).await?;
+                sent += 1;
+            }
+        }
+        Ok(sent)
+    }
+
+    fn to_bill(row: BillRow) -> Bill {
+        Bill {
+            pocket: Self::pocket_summary(&row),
+            id: row.id,
+            name: row.name,
+            payee: PayeeSummary {
+                id: row.payee_id,
+                name: row.payee_name,
+            },
+            expected_amount: row.expected_amount,
+            due_day: row.due_day,
+            starts_on: row.starts_on,
+            created_at: row.created_at,
+        }
+    }
+
+    fn pocket_summary(row: &BillRow) -> Option<PocketSummary> {
+        Some(PocketSummary {
+            id: row.pocket_id?,
+            name: row.pocket_name.clone()?,
+            icon: row.pocket_icon.clone()?,
+            currency: row.pocket_currency.clone()?,
+        })
+    }
+
+    fn validate_terms(
+        expected_amount: Option<Decimal>,
+        due_day: Option<i16>,
+    ) -> Result<(), AppError> {
+        if expected_amount.is_some_and(|a| a <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "expected_amount must be positive".to_string(),
+            ));
+        }
+        if due_day.is_some_and(|d| !(1..=31).contains(&d)) {
+            return Err(AppError::ValidationError(
+                "due_day must be between 1 and 31".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct LiabilityService {
+    liability_repo: LiabilityRepository,
+    settings_repo: SettingsRepository,
+}
+
+impl LiabilityService {
+    pub fn new(liability_repo: LiabilityRepository, settings_repo: SettingsRepository) -> Self {
+        Self {
+            liability_repo,
+            settings_repo,
+        }
+    }
+
+    pub async fn get_liabilities(&self, user_id: Uuid) -> Result<Vec<Liability>, AppError> {
+        let rows = self.liability_repo.get_all(user_id).await?;
+        let mut payments =
+            repayments_by_liability(self.liability_repo.get_payments(user_id, None).await?);
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let paid = payments.remove(&row.id).unwrap_or_default();
+                Self::to_liability(row, &paid)
+            })
+            .collect())
+    }
+
+    pub async fn get_liability(&self, id: Uuid, user_id: Uuid) -> Result<Liability, AppError> {
+        let row = self.liability_repo.get_by_id(id, user_id).await?;
+        let payments = self.liability_repo.get_payments(user_id, Some(id)).await?;
+        Ok(Self::to_liability(row, &payments))
+    }
+
+    pub async fn create_liability(
+        &self,
+        user_id: Uuid,
+        req: CreateLiability,
+    ) -> Result<Uuid, AppError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Liability name cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_terms(
+            Some(req.principal),
+            Some(req.annual_rate),
+            Some(req.term_months),
+        )?;
+
+        let currency = match req.currency {
+            Some(ref code) => {
+                let code = code.trim().to_uppercase();
+                if !self.settings_repo.validate_currency(&code).await? {
+                    return Err(AppError::ValidationError(format!(
+                        "Invalid currency code: {}",
+                        code
+                    )));
+                }
+                code
+            }
+            None => self.settings_repo.get_base_currency(user_id).await?,
+        };
+
+        self.liability_repo
+            .create(user_id, &req, name, &currency)
+            .await
+    }
+
+    pub async fn update_liability(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: UpdateLiability,
+    ) -> Result<(), AppError> {
+        if let Some(ref name) = req.name
+            && name.trim().is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "Liability name cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_terms(req.principal, req.annual_rate, req.term_months)?;
+
+        let updated = self.liability_repo.update(id, user_id, &req).await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError("Liability not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn delete_liability(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = self.liability_repo.delete(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Liability not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_schedule(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<AmortizationEntry>, AppError> {
+        let row = self.liability_repo.get_by_id(id, user_id).await?;
+        Ok(amortization::schedule(&row))
+    }
+
+    /// Recorded repayments, oldest first, each split into principal and interest
+    pub async fn get_payments(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<LiabilityPayment>, AppError> {
+        let row = self.liability_repo.get_by_id(id, user_id).await?;
+        let payments = self.liability_repo.get_payments(user_id, Some(id)).await?;
+        let splits = amortization::split_payments(&row, &payments);
+
+        Ok(payments
+            .into_iter()
+            .zip(splits)
+            .map(|(payment, split)| LiabilityPayment {
+                transaction_id: payment.transaction_id,
+                occurred_at: payment.occurred_at,
+                amount: payment.amount,
+                principal: split.principal,
+                interest: split.interest,
+                balance: split.balance,
+            })
+            .collect())
+    }
+
+    /// Record an existing expense as a repayment and return its split
+    pub async fn record_payment(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: RecordLiabilityPayment,
+    ) -> Result<LiabilityPayment, AppError> {
+        let liability = self.liability_repo.get_by_id(id, user_id).await?;
+        let candidate = self
+            .liability_repo
+            .get_repayment_candidate(req.transaction_id, user_id)
             .await?;
+        if candidate.is_transfer {
+            return Err(AppError::ValidationError(
+                "Transfers cannot be recorded as repayments".to_string(),
+            ));
+        }
+        if candidate.is_income {
+            return Err(AppError::ValidationError(
+                "Only expenses can be recorded as repayments".to_string(),
+            ));
+        }
+        if candidate.currency != liability.currency {
+            return Err(AppError::ValidationError(format!(
+                "Repayments of this liability must be paid from a {} pocket",
+                liability.currency
+            )));
+        }
 
-        // 1. Withdraw from Source
-        self.transaction_repo
-            .create(
-                user_id,
-                req.amount, // Positive amount (category indicates it's an outflow)
-                Some(
-                    req.description
-                        .clone()
-                        .unwrap_or_else(|| "Transfer Out".to_string()),
-                ),
-                cat_out.id,
-                Utc::now(),
-                None,
-                None,
-                None,
-                req.source_pocket_id,
-            )
+        self.liability_repo
+            .add_payment(id, req.transaction_id)
             .await?;
 
-        // 2. Deposit to Destination
-        self.transaction_repo
-            .create(
-                user_id,
-                req.amount, // Positive amount for income
-                Some(
-                    req.description
-                        .clone()
-                        .unwrap_or_else(|| "Transfer In".to_string()),
-                ),
-                cat_in.id,
-                Utc::now(),
-                None,
-                None,
-                None,
-                req.destination_pocket_id,
-            )
+        self.get_payments(id, user_id)
+            .await?
+            .into_iter()
+            .find(|p| p.transaction_id == req.transaction_id)
+            .ok_or(AppError::NotFoundError("Transaction not found".to_string()))
+    }
+
+    pub async fn remove_payment(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<(), AppError> {
+        let removed = self
+            .liability_repo
+            .remove_payment(id, user_id, transaction_id)
             .await?;
+        if removed == 0 {
+            return Err(AppError::NotFoundError("Repayment not found".to_string()));
+        }
+        Ok(())
+    }
 
+    fn to_liability(row: LiabilityRow, payments: &[LiabilityPaymentRow]) -> Liability {
+        let splits = amortization::split_payments(&row, payments);
+        Liability {
+            scheduled_payment: amortization::scheduled_payment(&row),
+            principal_paid: splits.iter().map(|s| s.principal).sum(),
+            interest_paid: splits.iter().map(|s| s.interest).sum(),
+            outstanding_balance: splits.last().map_or(row.principal, |s| s.balance),
+            frequency: PaymentFrequency::from_db(&row.frequency),
+            id: row.id,
+            name: row.name,
+            principal: row.principal,
+            annual_rate: row.annual_rate,
+            term_months: row.term_months,
+            first_payment_on: row.first_payment_on,
+            currency: row.currency,
+            created_at: row.created_at,
+        }
+    }
+
+    fn validate_terms(
+        principal: Option<Decimal>,
+        annual_rate: Option<Decimal>,
+        term_months: Option<i32>,
+    ) -> Result<(), AppError> {
+        if principal.is_some_and(|p| p <= Decimal::ZERO) {
+            return Err(AppError::ValidationError(
+                "principal must be positive".to_string(),
+            ));
+        }
+        if annual_rate.is_some_and(|r| r < Decimal::ZERO || r > Decimal::ONE_HUNDRED) {
+            return Err(AppError::ValidationError(
+                "annual_rate must be between 0 and 100".to_string(),
+            ));
+        }
+        if term_months.is_some_and(|t| !(1..=amortization::MAX_TERM_MONTHS).contains(&t)) {
+            return Err(AppError::ValidationError(format!(
+                "term_months must be between 1 and {}",
+                amortization::MAX_TERM_MONTHS
+            )));
+        }
         Ok(())
     }
 }
 
-pub struct FinanceService {
-    portfolio_repo: PortfolioRepository,
-    transaction_repo: TransactionRepository,
-    settings_repo: SettingsRepository,
-    price_cache: moka::future::Cache<String, Decimal>,
-    exchange_rate_cache: moka::future::Cache<String, Decimal>,
-    http_client: reqwest::Client,
+/// Repayments per liability, keeping their order
+fn repayments_by_liability(
+    payments: Vec<LiabilityPaymentRow>,
+) -> HashMap<Uuid, Vec<LiabilityPaymentRow>> {
+    let mut by_liability: HashMap<Uuid, Vec<LiabilityPaymentRow>> = HashMap::new();
+    for payment in payments {
+        by_liability
+            .entry(payment.liability_id)
+            .or_default()
+            .push(payment);
+    }
+    by_liability
 }
 
-impl FinanceService {
+pub struct ReconciliationService {
+    reconciliation_repo: ReconciliationRepository,
+    pocket_repo: PocketRepository,
+}
+
+impl ReconciliationService {
     pub fn new(
-        portfolio_repo: PortfolioRepository,
-        transaction_repo: TransactionRepository,
-        settings_repo: SettingsRepository,
-        price_cache: moka::future::Cache<String, Decimal>,
-        exchange_rate_cache: moka::future::Cache<String, Decimal>,
-        http_client: reqwest::Client,
+        reconciliation_repo: ReconciliationRepository,
+        pocket_repo: PocketRepository,
     ) -> Self {
         Self {
-            portfolio_repo,
-            transaction_repo,
-            settings_repo,
-            price_cache,
-            exchange_rate_cache,
-            http_client,
+            reconciliation_repo,
+            pocket_repo,
         }
     }
 
-    /// Cached exchange rate lookup with 60s TTL
-    async fn get_cached_exchange_rate(&self, from: &str, to: &str) -> Result<Decimal, AppError> {
-        if from == to {
-            return Ok(Decimal::new(1, 0));
-        }
-
-        let cache_key = format!("{}_{}", from, to);
-        if let Some(rate) = self.exchange_rate_cache.get(&cache_key).await {
-            tracing::info!("Exchange rate cache HIT for {} -> {}", from, to);
-            return Ok(rate);
-        }
-
-        tracing::info!("Exchange rate cache MISS for {} -> {}", from, to);
-        let rate = investments::fetch_exchange_rate(&self.http_client, from, to).await?;
-        self.exchange_rate_cache.insert(cache_key, rate).await;
-        Ok(rate)
+    pub async fn start(
+        &self,
+        pocket_id: Uuid,
+        user_id: Uuid,
+        req: StartReconciliation,
+    ) -> Result<Uuid, AppError> {
+        self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+        self.reconciliation_repo
+            .create(user_id, pocket_id, &req)
+            .await
     }
 
-    pub async fn get_financial_health(&self, user_id: Uuid) -> Result<FinancialHealth, AppError> {
-        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
-        let cash = self.transaction_repo.get_net_cash(user_id).await?;
-        let invested_usd = self.portfolio_repo.get_total_invested(user_id).await?;
+    pub async fn get_reconciliations(
+        &self,
+        pocket_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<Reconciliation>, AppError> {
+        self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+        let rows = self.reconciliation_repo.get_all(user_id, pocket_id).await?;
+        Ok(rows.into_iter().map(Self::to_reconciliation).collect())
+    }
 
-        let rate = if base_currency != "USD" {
-            self.get_cached_exchange_rate("USD", &base_currency).await?
+    pub async fn get_reconciliation(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<ReconciliationDetail, AppError> {
+        let row = self.reconciliation_repo.get_by_id(id, user_id).await?;
+        let transactions = if row.completed_at.is_none() {
+            self.reconciliation_repo
+                .get_items(row.pocket_id, row.statement_date)
+                .await?
         } else {
-            Decimal::new(1, 0)
+            Vec::new()
         };
+        Ok(ReconciliationDetail {
+            reconciliation: Self::to_reconciliation(row),
+            transactions,
+        })
+    }
 
-        let invested_converted = invested_usd * rate;
-        let net_worth = cash + invested_converted;
+    pub async fn set_cleared(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: ClearTransactions,
+    ) -> Result<Reconciliation, AppError> {
+        if req.transaction_ids.is_empty() {
+            return Err(AppError::ValidationError(
+                "transaction_ids cannot be empty".to_string(),
+            ));
+        }
+        let row = self.open_reconciliation(id, user_id).await?;
+        self.reconciliation_repo
+            .set_cleared(
+                row.pocket_id,
+                row.statement_date,
+                &req.transaction_ids,
+                req.cleared,
+            )
+            .await?;
 
-        Ok(FinancialHealth {
-            cash_balance: cash,
-            investment_balance: invested_converted,
-            total_net_worth: net_worth,
-        })
+        let row = self.reconciliation_repo.get_by_id(id, user_id).await?;
+        Ok(Self::to_reconciliation(row))
     }
 
-    pub async fn refresh_portfolio(&self, user_id: Uuid) -> Result<u64, AppError> {
-        let tickers = self.portfolio_repo.get_tickers(user_id).await?;
-        let count = tickers.len() as u64;
+    /// Finish once the cleared balance matches the statement, locking the cleared transactions
+    pub async fn complete(&self, id: Uuid, user_id: Uuid) -> Result<Reconciliation, AppError> {
+        let row = self.open_reconciliation(id, user_id).await?;
+        let difference = row.ending_balance - row.cleared_balance;
+        if !difference.is_zero() {
+            return Err(AppError::ValidationError(format!(
+                "Cleared balance differs from the statement by {}",
+                difference.round_dp(2)
+            )));
+        }
+        self.reconciliation_repo
+            .complete(id, row.pocket_id, row.statement_date)
+            .await?;
 
-        let fetch_futures: Vec<_> = tickers
-            .iter()
-            .map(|ticker| async move {
-                if let Err(e) = self.ensure_price_fresh(ticker).await {
-                    tracing::error!("Failed to refresh price for {}: {:?}", ticker, e);
-                }
-            })
-            .collect();
-        futures::future::join_all(fetch_futures).await;
+        let row = self.reconciliation_repo.get_by_id(id, user_id).await?;
+        Ok(Self::to_reconciliation(row))
+    }
 
-        Ok(count)
+    pub async fn delete(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        self.open_reconciliation(id, user_id).await?;
+        let deleted = self.reconciliation_repo.delete_open(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError(
+                "Reconciliation not found".to_string(),
+            ));
+        }
+        Ok(())
     }
 
-    pub async fn add_investment(
+    async fn open_reconciliation(
         &self,
+        id: Uuid,
         user_id: Uuid,
-        item: CreatePortfolioItem,
-    ) -> Result<(), AppError> {
-        // Validate ticker and ensure price is in assets table
-        self.ensure_price_fresh(&item.ticker).await?;
-
-        // Ensure asset exists in DB (already done by seed data or we error out in add_item)
-
-        match self.portfolio_repo.add_item(user_id, item.clone()).await {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                // AppError is likely DatabaseError
-                // Simple string matching for now
-                let msg = format!("{:?}", e);
-                if msg.contains("duplicate key value") {
-                    Err(AppError::ValidationError(format!(
-                        "{} is already in your portfolio",
-                        &item.ticker,
-                    )))
-                } else {
-                    Err(e)
-                }
-            }
+    ) -> Result<ReconciliationRow, AppError> {
+        let row = self.reconciliation_repo.get_by_id(id, user_id).await?;
+        if row.completed_at.is_some() {
+            return Err(AppError::ValidationError(
+                "This reconciliation is already completed".to_string(),
+            ));
         }
+        Ok(row)
     }
 
-    // Helper to get price with cache
-    async fn ensure_price_fresh(&self, ticker: &str) -> Result<Decimal, AppError> {
-        if let Some(price) = self.price_cache.get(ticker).await {
-            tracing::info!("Cache HIT for {}", ticker);
-            return Ok(price);
+    fn to_reconciliation(row: ReconciliationRow) -> Reconciliation {
+        Reconciliation {
+            id: row.id,
+            pocket: PocketSummary {
+                id: row.pocket_id,
+                name: row.pocket_name,
+                icon: row.pocket_icon,
+                currency: row.pocket_currency,
+            },
+            statement_date: row.statement_date,
+            ending_balance: row.ending_balance,
+            cleared_balance: row.cleared_balance,
+            difference: row.ending_balance - row.cleared_balance,
+            completed_at: row.completed_at,
+            created_at: row.created_at,
         }
-        tracing::info!("Cache MISS for {}", ticker);
-
-        // Fetch asset from DB to know Source and API Ticker
-        let asset_opt = self.portfolio_repo.get_asset(ticker).await?;
-        let (api_ticker, source) = if let Some(asset) = asset_opt {
-            let api_ticker = asset.api_ticker.unwrap_or(ticker.to_string());
-            let source = asset.source.unwrap_or("YAHOO".to_string());
-
-            // NEW: Check for missing icon and populate it lazily
-            if asset.icon_url.is_none() && source == "COINGECKO" {
-                tracing::info!("Missing icon for {}, fetching from CoinGecko...", ticker);
-                // We don't want to fail the whole request if icon fetch fails
-                match investments::fetch_coingecko_icon(&self.http_client, &api_ticker).await {
-                    Ok(Some(url)) => {
-                        if let Err(e) = self.portfolio_repo.update_asset_icon(ticker, &url).await {
-                            tracing::error!("Failed to save icon for {}: {:?}", ticker, e);
-                        } else {
-                            tracing::info!("Updated icon for {}", ticker);
-                        }
-                    }
-                    Ok(None) => tracing::warn!("No icon found for {}", ticker),
-                    Err(e) => tracing::error!("Failed to fetch icon for {}: {:?}", ticker, e),
-                }
-            }
+    }
+}
 
-            (api_ticker, source)
-        } else {
-            // If asset not found in DB, for now we default to YAHOO/Ticker
-            // (e.g. legacy behavior or if someone manually inserted via sql)
-            (ticker.to_string(), "YAHOO".to_string())
-        };
+pub struct NetWorthService {
+    net_worth_repo: NetWorthRepository,
+    portfolio_repo: PortfolioRepository,
+    liability_repo: LiabilityRepository,
+    settings_repo: SettingsRepository,
+    exchange_rates: ExchangeRateService,
+}
 
-        let (price, currency) =
-            investments::fetch_price_with_source(&self.http_client, ticker, &api_ticker, &source)
-                .await?;
+impl NetWorthService {
+    pub fn new(
+        net_worth_repo: NetWorthRepository,
+        portfolio_repo: PortfolioRepository,
+        liability_repo: LiabilityRepository,
+        settings_repo: SettingsRepository,
+        exchange_rates: ExchangeRateService,
+    ) -> Self {
+        Self {
+            net_worth_repo,
+            portfolio_repo,
+            liability_repo,
+            settings_repo,
+            exchange_rates,
+        }
+    }
 
-        // Update DB
-        self.portfolio_repo
-            .update_asset_price(ticker, price, &currency)
-            .await?;
+    pub async fn get_history(
+        &self,
+        user_id: Uuid,
+        params: NetWorthHistoryParams,
+    ) -> Result<Vec<NetWorthSnapshot>, AppError> {
+        check_snapshot_range(params.start_date, params.end_date)?;
+        self.net_worth_repo
+            .get_history(user_id, params.start_date, params.end_date)
+            .await
+    }
 
-        self.price_cache.insert(ticker.to_string(), price).await;
+    pub async fn users_without_snapshot(&self, day: NaiveDate) -> Result<Vec<Uuid>, AppError> {
+        self.net_worth_repo.get_users_without_snapshot(day).await
+    }
 
-        Ok(price)
+    /// Record the user's net worth at the end of `day`, valuing investments at their latest
+    /// prices. Does nothing when the day already has a snapshot.
+    pub async fn record_snapshot(&self, user_id: Uuid, day: NaiveDate) -> Result<u64, AppError> {
+        self.record(user_id, day, day, false).await
     }
 
-    pub async fn get_portfolio_list(
+    /// Reconstruct cash and liabilities for past days from transaction history. Returns the
+    /// number of snapshots written.
+    pub async fn backfill(&self, user_id: Uuid, req: BackfillNetWorth) -> Result<u64, AppError> {
+        let yesterday = Utc::now().date_naive() - Duration::days(1);
+        let end = req.end_date.unwrap_or(yesterday);
+        if end > yesterday {
+            return Err(AppError::ValidationError(
+                "Only days before today can be backfilled".to_string(),
+            ));
+        }
+        let start = match req.start_date {
+            Some(start) => start,
+            None => match self.net_worth_repo.get_first_activity(user_id).await? {
+                Some(first) => first.min(end),
+                None => return Ok(0),
+            },
+        };
+        check_snapshot_range(start, end)?;
+        self.record(user_id, start, end, true).await
+    }
+
+    async fn record(
         &self,
         user_id: Uuid,
-    ) -> Result<crate::schemas::PortfolioResponse, AppError> {
-        // Fetch prices in parallel for all tickers
-        let tickers = self.portfolio_repo.get_tickers(user_id).await?;
-        let fetch_futures: Vec<_> = tickers
-            .iter()
-            .map(|ticker| async move {
-                if let Err(e) = self.ensure_price_fresh(ticker).await {
-                    tracing::error!("Failed to refresh price for {}: {:?}", ticker, e);
-                }
-            })
+        start: NaiveDate,
+        end: NaiveDate,
+        is_backfill: bool,
+    ) -> Result<u64, AppError> {
+        let recorded: HashSet<NaiveDate> = self
+            .net_worth_repo
+            .get_recorded_days(user_id, start, end)
+            .await?
+            .into_iter()
             .collect();
-        futures::future::join_all(fetch_futures).await;
-
-        // Get data
         let base_currency = self.settings_repo.get_base_currency(user_id).await?;
-        let items = self.portfolio_repo.get_all_joined(user_id).await?;
+        let mut pockets: HashMap<NaiveDate, Vec<PocketDayBalanceRow>> = HashMap::new();
+        for row in self
+            .net_worth_repo
+            .get_pocket_balances(user_id, start, end)
+            .await?
+        {
+            pockets.entry(row.day).or_default().push(row);
+        }
+        let liabilities = self.liability_repo.get_all(user_id).await?;
+        let payments = self.liability_repo.get_payments(user_id, None).await?;
+        // Past holdings and prices aren't known, so backfilled days leave investments out
+        let assets = if is_backfill {
+            Vec::new()
+        } else {
+            self.portfolio_repo.get_all_joined(user_id).await?
+        };
 
-        // Pre-fetch all unique exchange rates (async part)
-        let unique_currencies: std::collections::HashSet<String> = items
+        let days: Vec<(NaiveDate, Vec<net_worth::Holding>)> = start
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .filter(|day| !recorded.contains(day))
+            .map(|day| {
+                let holdings = net_worth::holdings(
+                    day,
+                    pockets.get(&day).map_or(&[][..], Vec::as_slice),
+                    &assets,
+                    &liabilities,
+                    &payments,
+                );
+                (day, holdings)
+            })
+            .collect();
+
+        // One rate lookup per currency for the whole range
+        let currencies: BTreeSet<&str> = days
             .iter()
-            .filter_map(|item| item.currency.clone())
-            .filter(|c| c != &base_currency)
+            .flat_map(|(_, holdings)| holdings.iter().map(|h| h.currency.as_str()))
+            .filter(|currency| *currency != base_currency)
             .collect();
+        let mut rates_by_currency = HashMap::new();
+        for currency in currencies {
+            let rates = self
+                .exchange_rates
+                .rates_between(currency, &base_currency, start, end)
+                .await?;
+            rates_by_currency.insert(currency.to_string(), rates);
+        }
 
-        let mut exchange_rates = std::collections::HashMap::new();
-        for currency in unique_currencies {
-            let rate = self
-                .get_cached_exchange_rate(&currency, &base_currency)
+        let mut tx = self.net_worth_repo.begin().await?;
+        let mut written = 0;
+        for (day, holdings) in days {
+            let rates: HashMap<String, Decimal> = rates_by_currency
+                .iter()
+                .filter_map(|(currency, rates)| rates.get(&day).map(|r| (currency.clone(), *r)))
+                .collect();
+            let snapshot = net_worth::snapshot(day, &base_currency, holdings, &rates, is_backfill);
+            written += self
+                .net_worth_repo
+                .insert_tx(&mut tx, user_id, &snapshot)
                 .await?;
-            exchange_rates.insert(currency, rate);
         }
+        tx.commit().await?;
+        Ok(written)
+    }
+}
 
-        // Use tested pure function to build the full response
-        Ok(crate::portfolio::build_portfolio_response(
-            items,
-            &exchange_rates,
-            &base_currency,
-        ))
+fn check_snapshot_range(start: NaiveDate, end: NaiveDate) -> Result<(), AppError> {
+    if end < start {
+        return Err(AppError::ValidationError(
+            "end_date must not be before start_date".to_string(),
+        ));
     }
-    pub async fn update_base_currency(
+    let days = (end - start).num_days() + 1;
+    if days > MAX_HISTORY_POINTS {
+        return Err(AppError::ValidationError(format!(
+            "Range covers {} days; at most {} are allowed",
+            days, MAX_HISTORY_POINTS
+        )));
+    }
+    Ok(())
+}
+
+/// Most notifications returned by the notifications endpoint
+const NOTIFICATION_LIMIT: i64 = 50;
+
+pub struct NotificationService {
+    notification_repo: NotificationRepository,
+}
+
+impl NotificationService {
+    pub fn new(notification_repo: NotificationRepository) -> Self {
+        Self { notification_repo }
+    }
+
+    pub async fn get_notifications(&self, user_id: Uuid) -> Result<Vec<Notification>, AppError> {
+        self.notification_repo
+            .get_recent(user_id, NOTIFICATION_LIMIT)
+            .await
+    }
+
+    pub async fn mark_read(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let updated = self.notification_repo.mark_read(id, user_id).await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError(
+                "Notification not found".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct PayeeService {
+    payee_repo: PayeeRepository,
+    transaction_repo: TransactionRepository,
+}
+
+impl PayeeService {
+    pub fn new(payee_repo: PayeeRepository, transaction_repo: TransactionRepository) -> Self {
+        Self {
+            payee_repo,
+            transaction_repo,
+        }
+    }
+
+    pub async fn get_payees(&self, user_id: Uuid) -> Result<Vec<Payee>, AppError> {
+        self.payee_repo.get_all(user_id).await
+    }
+
+    pub async fn get_payee(&self, id: Uuid, user_id: Uuid) -> Result<Payee, AppError> {
+        self.payee_repo.get_by_id(id, user_id).await
+    }
+
+    pub async fn create_payee(&self, user_id: Uuid, req: CreatePayee) -> Result<Uuid, AppError> {
+        let name = req.name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Payee name cannot be empty".to_string(),
+            ));
+        }
+        self.validate_category(req.default_category_id).await?;
+
+        // The payee's own name always resolves to it
+        let mut aliases: Vec<String> = Vec::new();
+        for alias in std::iter::once(name).chain(req.aliases.iter().map(String::as_str)) {
+            let alias = Self::normalize_alias(alias)?;
+            if !aliases.contains(&alias) {
+                aliases.push(alias);
+            }
+        }
+
+        self.payee_repo
+            .create(user_id, name, req.default_category_id, &aliases)
+            .await
+    }
+
+    pub async fn update_payee(
         &self,
+        id: Uuid,
         user_id: Uuid,
-        currency: String,
+        req: UpdatePayee,
     ) -> Result<(), AppError> {
-        if !self.settings_repo.validate_currency(&currency).await? {
-            return Err(AppError::ValidationError(format!(
-                "Invalid currency code: {}",
-                currency
-            )));
+        if let Some(ref name) = req.name
+            && name.trim().is_empty()
+        {
+            return Err(AppError::ValidationError(
+                "Payee name cannot be empty".to_string(),
+            ));
         }
-        self.settings_repo
-            .set_base_currency(user_id, &currency)
-            .await
+        self.validate_category(req.default_category_id).await?;
+
+        let updated = self
+            .payee_repo
+            .update(
+                id,
+                user_id,
+                req.name.map(|n| n.trim().to_string()),
+                req.default_category_id,
+            )
+            .await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError("Payee not found".to_string()));
+        }
+        Ok(())
     }
 
-    pub async fn remove_investment(&self, user_id: Uuid, ticker: String) -> Result<(), AppError> {
-        let deleted = self.portfolio_repo.delete(user_id, &ticker).await?;
+    pub async fn delete_payee(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = self.payee_repo.delete(id, user_id).await?;
         if deleted == 0 {
-            return Err(AppError::NotFoundError(format!(
-                "Investment {} not found",
-                ticker
-            )));
+            return Err(AppError::NotFoundError("Payee not found".to_string()));
         }
         Ok(())
     }
 
-    pub async fn update_investment(
+    pub async fn add_alias(
         &self,
+        payee_id: Uuid,
+        user_id: Uuid,
+        alias: &str,
+    ) -> Result<Uuid, AppError> {
+        self.payee_repo.get_by_id(payee_id, user_id).await?;
+        let alias = Self::normalize_alias(alias)?;
+        self.payee_repo.add_alias(user_id, payee_id, &alias).await
+    }
+
+    pub async fn delete_alias(
+        &self,
+        payee_id: Uuid,
+        alias_id: Uuid,
         user_id: Uuid,
-        ticker: String,
-        payload: UpdateInvestment,
     ) -> Result<(), AppError> {
-        self.portfolio_repo
-            .update(user_id, &ticker, payload.quantity, payload.avg_buy_price)
+        let deleted = self
+            .payee_repo
+            .delete_alias(alias_id, payee_id, user_id)
+            .await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Alias not found".to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn get_top_payees(
+        &self,
+        user_id: Uuid,
+        start_date: DateTime<Utc>,
+        end_date: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<PayeeSpending>, AppError> {
+        if end_date < start_date {
+            return Err(AppError::ValidationError(
+                "End date cannot be before start date".to_string(),
+            ));
+        }
+
+        self.payee_repo
+            .get_top_payees(user_id, start_date, end_date, limit.clamp(1, 100))
             .await
     }
+
+    fn normalize_alias(alias: &str) -> Result<String, AppError> {
+        let normalized = payees::normalize_name(alias);
+        if normalized.is_empty() {
+            return Err(AppError::ValidationError(format!(
+                "Alias '{}' has no matchable characters",
+                alias
+            )));
+        }
+        Ok(normalized)
+    }
+
+    async fn validate_category(&self, category_id: Option<i32>) -> Result<(), AppError> {
+        if let Some(category_id) = category_id
+            && !self.transaction_repo.category_exists(category_id).await?
+        {
+            return Err(AppError::ValidationError("Category not found".to_string()));
+        }
+        Ok(())
+    }
 }
 
-pub struct PocketService {
+pub struct RuleService {
+    rule_repo: RuleRepository,
+    transaction_repo: TransactionRepository,
     pocket_repo: PocketRepository,
+    settings_repo: SettingsRepository,
 }
 
-impl PocketService {
-    pub fn new(pocket_repo: PocketRepository) -> Self {
-        Self { pocket_repo }
+impl RuleService {
+    pub fn new(
+        rule_repo: RuleRepository,
+        transaction_repo: TransactionRepository,
+        pocket_repo: PocketRepository,
+        settings_repo: SettingsRepository,
+    ) -> Self {
+        Self {
+            rule_repo,
+            transaction_repo,
+            pocket_repo,
+            settings_repo,
+        }
     }
 
-    pub async fn create_pocket(&self, user_id: Uuid, req: CreatePocket) -> Result<Uuid, AppError> {
-        if req.name.trim().is_empty() {
-            return Err(AppError::ValidationError(
-                "Pocket name cannot be empty".to_string(),
-            ));
+    pub async fn get_rules(&self, user_id: Uuid) -> Result<Vec<CategorizationRule>, AppError> {
+        self.rule_repo.get_all(user_id).await
+    }
+
+    pub async fn create_rule(&self, user_id: Uuid, req: RuleRequest) -> Result<Uuid, AppError> {
+        let req = self.validate_rule(user_id, req).await?;
+        self.rule_repo.create(user_id, &req).await
+    }
+
+    pub async fn update_rule(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+        req: RuleRequest,
+    ) -> Result<(), AppError> {
+        let req = self.validate_rule(user_id, req).await?;
+        let updated = self.rule_repo.update(id, user_id, &req).await?;
+        if updated == 0 {
+            return Err(AppError::NotFoundError("Rule not found".to_string()));
         }
+        Ok(())
+    }
 
-        self.pocket_repo
-            .create(user_id, &req.name, req.description, req.icon)
-            .await
+    pub async fn delete_rule(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
+        let deleted = self.rule_repo.delete(id, user_id).await?;
+        if deleted == 0 {
+            return Err(AppError::NotFoundError("Rule not found".to_string()));
+        }
+        Ok(())
     }
 
-    pub async fn get_pockets(&self, user_id: Uuid) -> Result<Vec<Pocket>, AppError> {
-        self.pocket_repo.get_all(user_id).await
+    /// Existing transactions the rule would change, without changing them
+    pub async fn dry_run(&self, id: Uuid, user_id: Uuid) -> Result<RuleDryRunResponse, AppError> {
+        let (changes, pocket_skipped) = self.find_rule_changes(id, user_id).await?;
+        let changes: Vec<RuleDryRunItem> = changes.into_iter().map(|(item, _)| item).collect();
+
+        Ok(RuleDryRunResponse {
+            rule_id: id,
+            affected: changes.len(),
+            changes,
+            pocket_skipped,
+        })
     }
 
-    pub async fn get_pocket(&self, id: Uuid, user_id: Uuid) -> Result<Pocket, AppError> {
-        self.pocket_repo.get_by_id(id, user_id).await
+    /// Apply the rule to every matching transaction in one SQL transaction.
+    /// Each change is recorded in the transaction's edit history.
+    /// Returns the number of transactions changed and the number kept in their pocket
+    /// because their currency doesn't fit the target pocket.
+    pub async fn apply_to_history(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<(usize, usize), AppError> {
+        let (changes, pocket_skipped) = self.find_rule_changes(id, user_id).await?;
+
+        let mut tx = self.transaction_repo.begin().await?;
+        for (item, update) in &changes {
+            self.transaction_repo
+                .update_tx(&mut tx, item.transaction_id, user_id, update)
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok((changes.len(), pocket_skipped.len()))
     }
 
-    pub async fn update_pocket(
+    /// The changes the rule makes to matching transactions, plus the ids of matches whose
+    /// currency the target pocket can't hold (those keep their pocket)
+    async fn find_rule_changes(
         &self,
         id: Uuid,
         user_id: Uuid,
-        req: UpdatePocket,
-    ) -> Result<(), AppError> {
-        // Validate name if provided
-        if let Some(ref name) = req.name {
-            if name.trim().is_empty() {
-                return Err(AppError::ValidationError(
-                    "Pocket name cannot be empty".to_string(),
-                ));
+    ) -> Result<(Vec<(RuleDryRunItem, TransactionChanges)>, Vec<Uuid>), AppError> {
+        let rule = self.rule_repo.get_by_id(id, user_id).await?;
+        let compiled = CompiledRule::compile(rule)
+            .map_err(|e| AppError::ValidationError(format!("Invalid rule regex: {}", e)))?;
+        let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+        let candidates = self.transaction_repo.get_rule_candidates(user_id).await?;
+
+        let rule = &compiled.rule;
+        // The pocket may have been archived since the rule was saved
+        let target_pocket = match rule.set_pocket_id {
+            Some(pocket_id) => {
+                let pocket = self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+                check_pocket_active(&pocket)?;
+                Some(pocket)
+            }
+            None => None,
+        };
+
+        let mut changes = Vec::new();
+        let mut pocket_skipped = Vec::new();
+        for t in candidates {
+            let input = RuleInput {
+                description: t.description.as_deref(),
+                amount: t.original_amount.unwrap_or(t.amount),
+                currency: t.original_currency.as_deref().unwrap_or(&base_currency),
+                pocket_id: t.pocket_id,
+            };
+            if !compiled.matches(&input) {
+                continue;
+            }
+
+            let new_category_id = rule.set_category_id.filter(|c| Some(*c) != t.category_id);
+            let mut new_pocket_id = rule.set_pocket_id.filter(|p| *p != t.pocket_id);
+            if let Some(pocket) = &target_pocket
+                && new_pocket_id.is_some()
+                && check_pocket_currency(pocket, input.currency, &base_currency).is_err()
+            {
+                new_pocket_id = None;
+                pocket_skipped.push(t.id);
             }
+            let added_tags: Vec<String> = rule
+                .add_tags
+                .iter()
+                .filter(|tag| !t.tags.contains(tag))
+                .cloned()
+                .collect();
+
+            if new_category_id.is_none() && new_pocket_id.is_none() && added_tags.is_empty() {
+                continue;
+            }
+
+            let update = TransactionChanges {
+                category_id: new_category_id,
+                pocket_id: new_pocket_id,
+                tags: (!added_tags.is_empty())
+                    .then(|| t.tags.iter().chain(&added_tags).cloned().collect()),
+                ..Default::default()
+            };
+            let item = RuleDryRunItem {
+                transaction_id: t.id,
+                description: t.description,
+                occurred_at: t.occurred_at,
+                current_category_id: t.category_id,
+                new_category_id,
+                current_pocket_id: t.pocket_id,
+                new_pocket_id,
+                added_tags,
+            };
+            changes.push((item, update));
         }
 
-        self.pocket_repo
-            .update(id, user_id, req.name, req.description, req.icon)
-            .await
+        Ok((changes, pocket_skipped))
     }
 
-    pub async fn delete_pocket(&self, id: Uuid, user_id: Uuid) -> Result<(), AppError> {
-        let deleted = self.pocket_repo.delete(id, user_id).await?;
-        if deleted == 0 {
-            return Err(AppError::NotFoundError("Pocket not found".to_string()));
+    async fn validate_rule(
+        &self,
+        user_id: Uuid,
+        mut req: RuleRequest,
+    ) -> Result<RuleRequest, AppError> {
+        req.name = req.name.trim().to_string();
+        if req.name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Rule name cannot be empty".to_string(),
+            ));
         }
-        Ok(())
+
+        req.description_contains = req.description_contains.filter(|d| !d.trim().is_empty());
+        req.description_regex = req.description_regex.filter(|r| !r.trim().is_empty());
+        req.add_tags = normalize_tags(req.add_tags);
+
+        let has_condition = req.description_contains.is_some()
+            || req.description_regex.is_some()
+            || req.min_amount.is_some()
+            || req.max_amount.is_some()
+            || req.pocket_id.is_some()
+            || req.currency.is_some();
+        if !has_condition {
+            return Err(AppError::ValidationError(
+                "Rule must have at least one condition".to_string(),
+            ));
+        }
+
+        if req.set_category_id.is_none() && req.set_pocket_id.is_none() && req.add_tags.is_empty() {
+            return Err(AppError::ValidationError(
+                "Rule must set a category, pocket or tags".to_string(),
+            ));
+        }
+
+        if let Some(pattern) = &req.description_regex {
+            rules::build_regex(pattern)
+                .map_err(|e| AppError::ValidationError(format!("Invalid regex: {}", e)))?;
+        }
+
+        if let (Some(min), Some(max)) = (req.min_amount, req.max_amount)
+            && min > max
+        {
+            return Err(AppError::ValidationError(
+                "min_amount cannot be greater than max_amount".to_string(),
+            ));
+        }
+
+        if let Some(currency) = &req.currency
+            && !self.settings_repo.validate_currency(currency).await?
+        {
+            return Err(AppError::ValidationError(format!(
+                "Invalid currency code: {}",
+                currency
+            )));
+        }
+
+        if let Some(pocket_id) = req.pocket_id {
+            self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+        }
+
+        if let Some(pocket_id) = req.set_pocket_id {
+            let pocket = self.pocket_repo.get_by_id(pocket_id, user_id).await?;
+            check_pocket_active(&pocket)?;
+            // A rule limited to one currency must not target a pocket that can't hold it
+            if let Some(currency) = &req.currency {
+                let base_currency = self.settings_repo.get_base_currency(user_id).await?;
+                check_pocket_currency(&pocket, currency, &base_currency)?;
+            }
+        }
+
+        if let Some(category_id) = req.set_category_id
+            && !self.transaction_repo.category_exists(category_id).await?
+        {
+            return Err(AppError::ValidationError("Category not found".to_string()));
+        }
+
+        Ok(req)
     }
 }
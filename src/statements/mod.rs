@@ -0,0 +1,106 @@
+mod tests;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::schemas::{PocketStatement, PocketSummary, StatementLine, StatementRow};
+
+/// Split per-category rows into inflows, outflows and transfers, and derive the totals
+/// and closing balance. Rows keep the order they came in (largest first from the repository).
+pub fn build(
+    pocket: PocketSummary,
+    start_date: DateTime<Utc>,
+    end_date: DateTime<Utc>,
+    opening_balance: Decimal,
+    rows: Vec<StatementRow>,
+) -> PocketStatement {
+    let mut inflows = Vec::new();
+    let mut outflows = Vec::new();
+    let mut transfers_in = Decimal::ZERO;
+    let mut transfers_out = Decimal::ZERO;
+
+    for row in rows {
+        match (row.is_transfer, row.is_income) {
+            (true, true) => transfers_in += row.total,
+            (true, false) => transfers_out += row.total,
+            (false, is_income) => {
+                let line = StatementLine {
+                    category_id: row.category_id,
+                    category_name: row.category_name,
+                    total: row.total,
+                    transaction_count: row.transaction_count,
+                };
+                if is_income {
+                    inflows.push(line);
+                } else {
+                    outflows.push(line);
+                }
+            }
+        }
+    }
+
+    let total_inflows: Decimal = inflows.iter().map(|l| l.total).sum();
+    let total_outflows: Decimal = outflows.iter().map(|l| l.total).sum();
+    let closing_balance =
+        opening_balance + total_inflows - total_outflows + transfers_in - transfers_out;
+
+    PocketStatement {
+        pocket,
+        start_date,
+        end_date,
+        opening_balance,
+        inflows,
+        total_inflows,
+        outflows,
+        total_outflows,
+        transfers_in,
+        transfers_out,
+        closing_balance,
+    }
+}
+
+/// Render a statement as CSV with one row per balance, category line and transfer total
+pub fn to_csv(statement: &PocketStatement) -> String {
+    let mut out = String::from("section,category,transactions,amount\n");
+    let mut row = |section: &str, category: &str, count: Option<i64>, amount: Decimal| {
+        let count = count.map(|c| c.to_string()).unwrap_or_default();
+        out.push_str(&format!(
+            "{},{},{},{:.2}\n",
+            section,
+            escape(category),
+            count,
+            amount.round_dp(2)
+        ));
+    };
+
+    row("opening_balance", "", None, statement.opening_balance);
+    for line in &statement.inflows {
+        row(
+            "inflow",
+            &line.category_name,
+            Some(line.transaction_count),
+            line.total,
+        );
+    }
+    for line in &statement.outflows {
+        row(
+            "outflow",
+            &line.category_name,
+            Some(line.transaction_count),
+            line.total,
+        );
+    }
+    row("transfers_in", "", None, statement.transfers_in);
+    row("transfers_out", "", None, statement.transfers_out);
+    row("closing_balance", "", None, statement.closing_balance);
+    out
+}
+
+/// Quote a field when it contains a delimiter, quote or line break (RFC 4180)
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
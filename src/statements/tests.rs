@@ -0,0 +1,135 @@
+#![cfg(test)]
+
+use chrono::{DateTime, TimeZone, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use uuid::Uuid;
+
+use crate::schemas::{PocketSummary, StatementRow};
+
+use super::{build, to_csv};
+
+// ============================================================================
+// Test Helpers
+// ============================================================================
+
+fn pocket() -> PocketSummary {
+    PocketSummary {
+        id: Uuid::new_v4(),
+        name: "Main".to_string(),
+        icon: "account_balance_wallet".to_string(),
+        currency: "SGD".to_string(),
+    }
+}
+
+fn period() -> (DateTime<Utc>, DateTime<Utc>) {
+    (
+        Utc.with_ymd_and_hms(2026, 9, 1, 0, 0, 0).unwrap(),
+        Utc.with_ymd_and_hms(2026, 9, 30, 23, 59, 59).unwrap(),
+    )
+}
+
+fn make_row(name: &str, is_income: bool, is_transfer: bool, total: Decimal) -> StatementRow {
+    StatementRow {
+        category_id: 1,
+        category_name: name.to_string(),
+        is_income,
+        is_transfer,
+        total,
+        transaction_count: 2,
+    }
+}
+
+// ============================================================================
+// Build Tests
+// ============================================================================
+
+mod building {
+    use super::*;
+
+    #[test]
+    fn closing_balance_adds_up() {
+        // Given: salary in, food out, and a transfer in each direction
+        let (start, end) = period();
+        let rows = vec![
+            make_row("Salary", true, false, dec!(3000)),
+            make_row("Food", false, false, dec!(420.50)),
+            make_row("Transfer In", true, true, dec!(100)),
+            make_row("Transfer Out", false, true, dec!(250)),
+        ];
+
+        let statement = build(pocket(), start, end, dec!(1000), rows);
+
+        assert_eq!(statement.total_inflows, dec!(3000));
+        assert_eq!(statement.total_outflows, dec!(420.50));
+        assert_eq!(statement.transfers_in, dec!(100));
+        assert_eq!(statement.transfers_out, dec!(250));
+        assert_eq!(statement.closing_balance, dec!(3429.50));
+    }
+
+    #[test]
+    fn transfers_are_not_category_lines() {
+        let (start, end) = period();
+        let rows = vec![
+            make_row("Transfer In", true, true, dec!(100)),
+            make_row("Food", false, false, dec!(20)),
+        ];
+
+        let statement = build(pocket(), start, end, Decimal::ZERO, rows);
+
+        assert!(statement.inflows.is_empty());
+        assert_eq!(statement.outflows.len(), 1);
+        assert_eq!(statement.outflows[0].category_name, "Food");
+    }
+
+    #[test]
+    fn empty_period_keeps_opening_balance() {
+        let (start, end) = period();
+
+        let statement = build(pocket(), start, end, dec!(55.25), vec![]);
+
+        assert_eq!(statement.closing_balance, dec!(55.25));
+    }
+}
+
+// ============================================================================
+// CSV Tests
+// ============================================================================
+
+mod csv {
+    use super::*;
+
+    #[test]
+    fn renders_one_row_per_line() {
+        let (start, end) = period();
+        let rows = vec![
+            make_row("Salary", true, false, dec!(3000)),
+            make_row("Food", false, false, dec!(420.5)),
+        ];
+        let statement = build(pocket(), start, end, dec!(10), rows);
+
+        let csv = to_csv(&statement);
+
+        assert_eq!(
+            csv,
+            "section,category,transactions,amount\n\
+             opening_balance,,,10.00\n\
+             inflow,Salary,2,3000.00\n\
+             outflow,Food,2,420.50\n\
+             transfers_in,,,0.00\n\
+             transfers_out,,,0.00\n\
+             closing_balance,,,2589.50\n"
+        );
+    }
+
+    #[test]
+    fn category_names_are_quoted_when_needed() {
+        let (start, end) = period();
+        let rows = vec![make_row("Food, \"Dining\"", false, false, dec!(5))];
+        let statement = build(pocket(), start, end, Decimal::ZERO, rows);
+
+        let csv = to_csv(&statement);
+
+        assert!(csv.contains("outflow,\"Food, \"\"Dining\"\"\",2,5.00\n"));
+    }
+}